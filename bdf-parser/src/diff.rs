@@ -0,0 +1,433 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{BoundingBox, Coord, Encoding, Font, Glyph, Metrics, PropertyValue};
+
+/// Differences between two fonts.
+///
+/// See [`Font::diff`] for more information.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FontDiff {
+    /// Glyphs which are only included in the new font.
+    pub added: Vec<Glyph>,
+
+    /// Glyphs which are only included in the old font.
+    pub removed: Vec<Glyph>,
+
+    /// Glyphs which are included in both fonts, but have changed.
+    pub changed: Vec<GlyphDiff>,
+
+    /// Changed properties.
+    pub properties: Vec<PropertyDiff>,
+
+    /// Old and new font bounding box, if it has changed.
+    pub bounding_box: Option<(BoundingBox, BoundingBox)>,
+
+    /// Old and new font metrics, if they have changed.
+    pub metrics: Option<(Metrics, Metrics)>,
+
+    /// Old and new bits per pixel, if they have changed.
+    pub bits_per_pixel: Option<(u32, u32)>,
+}
+
+impl FontDiff {
+    /// Returns `true` if both fonts are equivalent.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.changed.is_empty()
+            && self.properties.is_empty()
+            && self.bounding_box.is_none()
+            && self.metrics.is_none()
+            && self.bits_per_pixel.is_none()
+    }
+}
+
+/// Changed glyph.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlyphDiff {
+    /// Old glyph.
+    pub old: Glyph,
+
+    /// New glyph.
+    pub new: Glyph,
+
+    /// Bits per pixel of the old and new font.
+    pub bits_per_pixel: (u32, u32),
+}
+
+impl GlyphDiff {
+    /// Returns `true` if the glyph name has changed.
+    pub fn name_changed(&self) -> bool {
+        self.old.name != self.new.name
+    }
+
+    /// Returns `true` if the glyph bitmap has changed.
+    ///
+    /// The bitmaps are compared after the bounding box offsets are applied.
+    /// Changes to the bounding box which don't affect any set pixels aren't
+    /// considered a bitmap change. For grayscale fonts the pixel values are
+    /// compared.
+    pub fn bitmap_changed(&self) -> bool {
+        let (old_bits_per_pixel, new_bits_per_pixel) = self.bits_per_pixel;
+
+        pixel_values(&self.old, old_bits_per_pixel) != pixel_values(&self.new, new_bits_per_pixel)
+    }
+
+    /// Returns `true` if the glyph bounding box has changed.
+    pub fn bounding_box_changed(&self) -> bool {
+        self.old.bounding_box != self.new.bounding_box
+    }
+
+    /// Returns `true` if the glyph widths or the origin offset have changed.
+    pub fn width_changed(&self) -> bool {
+        self.old.width_horizontal != self.new.width_horizontal
+            || self.old.width_vertical != self.new.width_vertical
+            || self.old.origin_offset != self.new.origin_offset
+    }
+}
+
+/// Changed property.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropertyDiff {
+    /// Property name.
+    pub name: String,
+
+    /// Old value or `None` if the property was added.
+    pub old: Option<PropertyValue>,
+
+    /// New value or `None` if the property was removed.
+    pub new: Option<PropertyValue>,
+}
+
+/// Key used to match glyphs in both fonts.
+///
+/// Glyphs with a specified encoding are matched by their encoding and all
+/// other glyphs are matched by their name.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum GlyphKey<'a> {
+    Encoding(Encoding),
+    Name(&'a str),
+}
+
+impl<'a> GlyphKey<'a> {
+    fn new(glyph: &'a Glyph) -> Self {
+        match glyph.encoding {
+            Encoding::Unspecified => Self::Name(&glyph.name),
+            encoding => Self::Encoding(encoding),
+        }
+    }
+}
+
+/// Returns the values of all non zero pixels relative to the glyph origin.
+fn pixel_values(glyph: &Glyph, bits_per_pixel: u32) -> BTreeMap<Coord, u8> {
+    let BoundingBox { offset, size } = glyph.bounding_box;
+    let width = size.x.max(0) as usize;
+    let height = size.y.max(0) as usize;
+
+    (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .filter_map(|(x, y)| {
+            let value = glyph.pixel_value(x, y, bits_per_pixel)?;
+            let coord = Coord::new(offset.x + x as i32, offset.y + size.y - 1 - y as i32);
+
+            (value != 0).then_some((coord, value))
+        })
+        .collect()
+}
+
+/// Returns the coordinates of all set pixels relative to the glyph origin.
+pub(crate) fn set_pixels(glyph: &Glyph) -> BTreeSet<Coord> {
+    let BoundingBox { offset, size } = glyph.bounding_box;
+    let width = size.x.max(0) as usize;
+
+    glyph
+        .pixels()
+        .enumerate()
+        .filter(|(_, pixel)| *pixel)
+        .map(|(index, _)| {
+            let x = (index % width) as i32;
+            let y = (index / width) as i32;
+
+            Coord::new(offset.x + x, offset.y + size.y - 1 - y)
+        })
+        .collect()
+}
+
+impl Font {
+    /// Compares this font with a new version of the font.
+    ///
+    /// Glyphs are matched by their encoding. Glyphs with an unspecified
+    /// encoding are matched by their name instead.
+    pub fn diff(&self, new: &Font) -> FontDiff {
+        let old_glyphs = self
+            .glyphs
+            .iter()
+            .map(|glyph| (GlyphKey::new(glyph), glyph))
+            .collect::<BTreeMap<_, _>>();
+        let new_glyphs = new
+            .glyphs
+            .iter()
+            .map(|glyph| (GlyphKey::new(glyph), glyph))
+            .collect::<BTreeMap<_, _>>();
+
+        let added = new_glyphs
+            .iter()
+            .filter(|(key, _)| !old_glyphs.contains_key(key))
+            .map(|(_, glyph)| (*glyph).clone())
+            .collect();
+
+        let removed = old_glyphs
+            .iter()
+            .filter(|(key, _)| !new_glyphs.contains_key(key))
+            .map(|(_, glyph)| (*glyph).clone())
+            .collect();
+
+        let bits_per_pixel = (self.metadata.bits_per_pixel, new.metadata.bits_per_pixel);
+        let changed = old_glyphs
+            .iter()
+            .filter_map(|(key, old)| {
+                let new = new_glyphs.get(key)?;
+
+                let diff = GlyphDiff {
+                    old: (*old).clone(),
+                    new: (*new).clone(),
+                    bits_per_pixel,
+                };
+
+                (diff.name_changed()
+                    || diff.bitmap_changed()
+                    || diff.bounding_box_changed()
+                    || diff.width_changed())
+                .then_some(diff)
+            })
+            .collect();
+
+        let old_properties = self.metadata.properties.iter().collect::<BTreeMap<_, _>>();
+        let new_properties = new.metadata.properties.iter().collect::<BTreeMap<_, _>>();
+
+        let properties = old_properties
+            .keys()
+            .chain(new_properties.keys())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .filter_map(|name| {
+                let old = old_properties.get(name).copied();
+                let new = new_properties.get(name).copied();
+
+                (old != new).then(|| PropertyDiff {
+                    name: name.to_string(),
+                    old: old.cloned(),
+                    new: new.cloned(),
+                })
+            })
+            .collect();
+
+        let bounding_box = (self.metadata.bounding_box != new.metadata.bounding_box)
+            .then_some((self.metadata.bounding_box, new.metadata.bounding_box));
+        let metrics = (self.metrics != new.metrics).then_some((self.metrics, new.metrics));
+        let bits_per_pixel = (bits_per_pixel.0 != bits_per_pixel.1).then_some(bits_per_pixel);
+
+        FontDiff {
+            added,
+            removed,
+            changed,
+            properties,
+            bounding_box,
+            metrics,
+            bits_per_pixel,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    const OLD: &str = indoc! {r#"
+        STARTFONT 2.1
+        FONT test
+        SIZE 8 75 75
+        FONTBOUNDINGBOX 4 4 0 0
+        STARTPROPERTIES 2
+        COPYRIGHT "old"
+        FONT_VERSION "1.0"
+        ENDPROPERTIES
+        CHARS 4
+        STARTCHAR A
+        ENCODING 65
+        DWIDTH 4 0
+        BBX 3 3 0 0
+        BITMAP
+        40
+        E0
+        A0
+        ENDCHAR
+        STARTCHAR B
+        ENCODING 66
+        DWIDTH 4 0
+        BBX 3 3 0 0
+        BITMAP
+        C0
+        E0
+        C0
+        ENDCHAR
+        STARTCHAR C
+        ENCODING 67
+        DWIDTH 4 0
+        BBX 3 3 0 0
+        BITMAP
+        E0
+        80
+        E0
+        ENDCHAR
+        STARTCHAR unnamed
+        ENCODING -1
+        DWIDTH 4 0
+        BBX 1 1 0 0
+        BITMAP
+        80
+        ENDCHAR
+        ENDFONT
+    "#};
+
+    const NEW: &str = indoc! {r#"
+        STARTFONT 2.1
+        FONT test
+        SIZE 8 75 75
+        FONTBOUNDINGBOX 4 4 0 0
+        STARTPROPERTIES 2
+        COPYRIGHT "new"
+        FOUNDRY "foundry"
+        ENDPROPERTIES
+        CHARS 4
+        STARTCHAR A
+        ENCODING 65
+        DWIDTH 4 0
+        BBX 3 4 0 0
+        BITMAP
+        00
+        40
+        E0
+        A0
+        ENDCHAR
+        STARTCHAR B
+        ENCODING 66
+        DWIDTH 5 0
+        BBX 3 3 0 0
+        BITMAP
+        C0
+        E0
+        E0
+        ENDCHAR
+        STARTCHAR D
+        ENCODING 68
+        DWIDTH 4 0
+        BBX 3 3 0 0
+        BITMAP
+        C0
+        A0
+        C0
+        ENDCHAR
+        STARTCHAR unnamed
+        ENCODING -1
+        DWIDTH 4 0
+        BBX 1 1 0 0
+        BITMAP
+        80
+        ENDCHAR
+        ENDFONT
+    "#};
+
+    #[test]
+    fn diff() {
+        let old = Font::parse(OLD).unwrap();
+        let new = Font::parse(NEW).unwrap();
+        let diff = old.diff(&new);
+
+        assert_eq!(
+            diff.added.iter().map(|g| &g.name).collect::<Vec<_>>(),
+            ["D"]
+        );
+        assert_eq!(
+            diff.removed.iter().map(|g| &g.name).collect::<Vec<_>>(),
+            ["C"]
+        );
+
+        let [a, b] = diff.changed.as_slice() else {
+            panic!("unexpected changed glyphs: {:?}", diff.changed);
+        };
+
+        // Only the bounding box of 'A' was extended by an empty row.
+        assert_eq!(a.new.name, "A");
+        assert!(!a.bitmap_changed());
+        assert!(a.bounding_box_changed());
+        assert!(!a.width_changed());
+
+        assert_eq!(b.new.name, "B");
+        assert!(b.bitmap_changed());
+        assert!(!b.bounding_box_changed());
+        assert!(b.width_changed());
+
+        assert_eq!(
+            diff.properties,
+            [
+                PropertyDiff {
+                    name: "COPYRIGHT".to_string(),
+                    old: Some(PropertyValue::Text("old".to_string())),
+                    new: Some(PropertyValue::Text("new".to_string())),
+                },
+                PropertyDiff {
+                    name: "FONT_VERSION".to_string(),
+                    old: Some(PropertyValue::Text("1.0".to_string())),
+                    new: None,
+                },
+                PropertyDiff {
+                    name: "FOUNDRY".to_string(),
+                    old: None,
+                    new: Some(PropertyValue::Text("foundry".to_string())),
+                },
+            ]
+        );
+
+        assert_eq!(diff.bounding_box, None);
+        assert!(diff.metrics.is_some());
+        assert_eq!(diff.bits_per_pixel, None);
+        assert!(!diff.is_empty());
+    }
+
+    #[test]
+    fn no_differences() {
+        let font = Font::parse(OLD).unwrap();
+
+        assert!(font.diff(&font).is_empty());
+    }
+
+    #[test]
+    fn grayscale_bitmap_changed() {
+        let old = OLD.replace("SIZE 8 75 75", "SIZE 8 75 75 2");
+        // Changes the value of the third pixel in the first row of 'A', which
+        // isn't part of the bitmap if it's interpreted as 1 bit per pixel.
+        let new = old.replacen("BITMAP\n40\n", "BITMAP\n44\n", 1);
+        let old = Font::parse(&old).unwrap();
+        let new = Font::parse(&new).unwrap();
+        let diff = old.diff(&new);
+
+        let [a] = diff.changed.as_slice() else {
+            panic!("unexpected changed glyphs: {:?}", diff.changed);
+        };
+        assert_eq!(a.new.name, "A");
+        assert!(a.bitmap_changed());
+        assert_eq!(diff.bits_per_pixel, None);
+    }
+
+    #[test]
+    fn bits_per_pixel_changed() {
+        let old = Font::parse(OLD).unwrap();
+        let mut new = old.clone();
+        new.metadata.bits_per_pixel = 2;
+
+        assert_eq!(old.diff(&new).bits_per_pixel, Some((1, 2)));
+    }
+}
@@ -0,0 +1,590 @@
+//! Windows FNT and FON bitmap fonts.
+//!
+//! The FNT format is described in the "Font Files" section of the Windows 3.0
+//! Developer's Notes. FON files are NE executables containing FNT resources.
+
+use std::collections::HashMap;
+
+use crate::{
+    glyph::{calculate_swidth, pixels_to_bitmap},
+    properties::PropertyValue,
+    BoundingBox, Coord, Encoding, Font, Glyph, GlyphWidth, Metadata, Metrics, MetricsSet,
+    ParserError, Properties, Property,
+};
+
+mod charsets;
+
+/// Size of the FNT 2.0 header.
+const FNT2_HEADER_SIZE: usize = 118;
+/// Size of the FNT 3.0 header.
+const FNT3_HEADER_SIZE: usize = 148;
+
+const ANSI_CHARSET: u8 = 0;
+const DEFAULT_CHARSET: u8 = 1;
+const THAI_CHARSET: u8 = 222;
+const EASTEUROPE_CHARSET: u8 = 238;
+const RUSSIAN_CHARSET: u8 = 204;
+const GREEK_CHARSET: u8 = 161;
+const TURKISH_CHARSET: u8 = 162;
+const HEBREW_CHARSET: u8 = 177;
+const ARABIC_CHARSET: u8 = 178;
+const BALTIC_CHARSET: u8 = 186;
+const OEM_CHARSET: u8 = 255;
+
+/// Resource type of fonts in NE executables.
+const RT_FONT: u16 = 0x8008;
+
+impl Font {
+    /// Parses a Windows FNT font.
+    ///
+    /// Only raster fonts in the FNT 2.0 and 3.0 formats are supported. The
+    /// characters are mapped from the charset of the font to Unicode. Fonts
+    /// with the ANSI, OEM and the single byte Windows charsets are supported.
+    /// Characters of fonts with other charsets use
+    /// [`Encoding::NonStandard`] with the original character code.
+    pub fn parse_fnt(data: &[u8]) -> Result<Self, ParserError> {
+        let fnt = Bytes(data);
+
+        let version = fnt.u16(0)?;
+        let header_size = match version {
+            0x0200 => FNT2_HEADER_SIZE,
+            0x0300 => FNT3_HEADER_SIZE,
+            _ => {
+                return Err(ParserError::new(&format!(
+                    "unsupported FNT version {:X}.{:X}",
+                    version >> 8,
+                    version & 0xFF
+                )))
+            }
+        };
+
+        if fnt.u16(66)? & 1 != 0 {
+            return Err(ParserError::new("vector fonts aren't supported"));
+        }
+
+        let copyright = fnt.string(6, 60)?;
+        let points = fnt.u16(68)?;
+        let vertical_resolution = fnt.u16(70)?;
+        let horizontal_resolution = fnt.u16(72)?;
+        let ascent = fnt.u16(74)?;
+        let italic = fnt.u8(80)? != 0;
+        let weight = fnt.u16(83)?;
+        let charset = fnt.u8(85)?;
+        let fixed_width = fnt.u16(86)?;
+        let height = fnt.u16(88)?;
+        let average_width = fnt.u16(91)?;
+        let first_char = fnt.u8(95)?;
+        let last_char = fnt.u8(96)?;
+        let default_char = first_char.wrapping_add(fnt.u8(97)?);
+        let face_offset = fnt.u32(105)? as usize;
+
+        if ascent > height {
+            return Err(ParserError::new("ascent is larger than the font height"));
+        }
+        if points == 0 || vertical_resolution == 0 || horizontal_resolution == 0 {
+            return Err(ParserError::new("invalid point size or resolution"));
+        }
+        if first_char > last_char {
+            return Err(ParserError::new("invalid character range"));
+        }
+
+        let face = fnt.c_string(face_offset)?;
+
+        let metrics = Metrics {
+            ascent: ascent.into(),
+            descent: (height - ascent).into(),
+        };
+
+        let spacing = if fixed_width != 0 { "C" } else { "P" };
+        let weight_name = if weight >= 600 { "Bold" } else { "Medium" };
+        let slant = if italic { "I" } else { "R" };
+        let (registry, encoding) = match charset_table(charset) {
+            Some(_) => ("ISO10646", "1"),
+            None => ("FontSpecific", "0"),
+        };
+
+        let mut properties = HashMap::new();
+        for (property, value) in [
+            (Property::FamilyName, face.as_str()),
+            (Property::WeightName, weight_name),
+            (Property::Slant, slant),
+            (Property::SetwidthName, "Normal"),
+            (Property::Spacing, spacing),
+            (Property::CharsetRegistry, registry),
+            (Property::CharsetEncoding, encoding),
+            (Property::Copyright, copyright.as_str()),
+        ] {
+            properties.insert(property.to_string(), PropertyValue::Text(value.to_string()));
+        }
+        for (property, value) in [
+            (Property::PixelSize, i32::from(height)),
+            (Property::PointSize, i32::from(points) * 10),
+            (Property::ResolutionX, i32::from(horizontal_resolution)),
+            (Property::ResolutionY, i32::from(vertical_resolution)),
+            (Property::AverageWidth, i32::from(average_width) * 10),
+            (Property::FontAscent, metrics.ascent as i32),
+            (Property::FontDescent, metrics.descent as i32),
+        ] {
+            properties.insert(property.to_string(), PropertyValue::Int(value));
+        }
+        if let Encoding::Standard(default_char) = char_encoding(default_char, charset) {
+            properties.insert(
+                Property::DefaultChar.to_string(),
+                PropertyValue::Int(default_char as i32),
+            );
+        }
+
+        let mut metadata = Metadata {
+            name: format!(
+                "-unknown-{face}-{weight_name}-{slant}-Normal--{height}-{}-{horizontal_resolution}-{vertical_resolution}-{spacing}-{}-{registry}-{encoding}",
+                u32::from(points) * 10,
+                u32::from(average_width) * 10,
+            ),
+            point_size: points.into(),
+            resolution: Coord::new(horizontal_resolution.into(), vertical_resolution.into()),
+            bits_per_pixel: 1,
+            bounding_box: BoundingBox::default(),
+            metrics_set: MetricsSet::Horizontal,
+            properties: Properties::new(properties),
+        };
+
+        // FNT 3.0 uses 32 bit offsets in the character table.
+        let entry_size = if version == 0x0200 { 4 } else { 6 };
+
+        let mut glyphs = Vec::new();
+        for (index, c) in (first_char..=last_char).enumerate() {
+            let entry = header_size + index * entry_size;
+            let width = fnt.u16(entry)?;
+            let offset = if version == 0x0200 {
+                fnt.u16(entry + 2)?.into()
+            } else {
+                fnt.u32(entry + 2)? as usize
+            };
+
+            let columns = usize::from(width).div_ceil(8);
+            let rows = usize::from(height);
+            let data = offset
+                .checked_add(columns * rows)
+                .and_then(|end| data.get(offset..end))
+                .ok_or_else(|| ParserError::new("invalid glyph offset"))?;
+
+            // The bitmap is stored in columns of 8 pixels width.
+            let bitmap = pixels_to_bitmap(width.into(), rows, |x, y| {
+                data[x / 8 * rows + y] & (0x80 >> (x % 8)) != 0
+            });
+
+            let device = Coord::new(width.into(), 0);
+            let encoding = char_encoding(c, charset);
+
+            glyphs.push(Glyph {
+                name: match encoding {
+                    Encoding::Standard(code_point) => format!("U+{code_point:04X}"),
+                    _ => format!("char{c}"),
+                },
+                encoding,
+                width_horizontal: Some(GlyphWidth {
+                    scalable: calculate_swidth(device, &metadata),
+                    device,
+                }),
+                width_vertical: None,
+                bounding_box: BoundingBox {
+                    offset: Coord::new(0, -(metrics.descent as i32)),
+                    size: Coord::new(width.into(), height.into()),
+                },
+                origin_offset: None,
+                bitmap,
+            });
+        }
+
+        metadata.bounding_box = glyphs
+            .iter()
+            .fold(BoundingBox::default(), |bounding_box, glyph| {
+                bounding_box.union(&glyph.bounding_box)
+            });
+
+        Ok(Font {
+            metadata,
+            glyphs: glyphs.into_iter().collect(),
+            metrics,
+        })
+    }
+
+    /// Parses all fonts in a Windows FON file.
+    ///
+    /// FON files are NE executables, which contain one or more FNT fonts as
+    /// resources. Often each font in the file has a different size. See
+    /// [`parse_fnt`](Self::parse_fnt) for more information about the supported
+    /// FNT fonts.
+    pub fn parse_fon(data: &[u8]) -> Result<Vec<Self>, ParserError> {
+        let fon = Bytes(data);
+
+        if data.get(0..2) != Some(b"MZ") {
+            return Err(ParserError::new("invalid FON header"));
+        }
+
+        let ne_header = fon.u32(0x3C)? as usize;
+        if data.get(ne_header..ne_header + 2) != Some(b"NE") {
+            return Err(ParserError::new("unsupported executable format"));
+        }
+
+        let resource_table = ne_header + usize::from(fon.u16(ne_header + 0x24)?);
+        let alignment_shift = fon.u16(resource_table)?;
+        if alignment_shift >= 16 {
+            return Err(ParserError::new("invalid alignment in resource table"));
+        }
+
+        let mut fonts = Vec::new();
+        let mut type_info = resource_table + 2;
+        loop {
+            let type_id = fon.u16(type_info)?;
+            if type_id == 0 {
+                break;
+            }
+
+            let count = usize::from(fon.u16(type_info + 2)?);
+            let resources = type_info + 8;
+
+            if type_id == RT_FONT {
+                for index in 0..count {
+                    let name_info = resources + index * 12;
+                    let offset = usize::from(fon.u16(name_info)?) << alignment_shift;
+                    let length = usize::from(fon.u16(name_info + 2)?) << alignment_shift;
+
+                    let fnt = offset
+                        .checked_add(length)
+                        .and_then(|end| data.get(offset..end))
+                        .ok_or_else(|| ParserError::new("invalid font resource offset"))?;
+
+                    fonts.push(Self::parse_fnt(fnt)?);
+                }
+            }
+
+            type_info = resources + count * 12;
+        }
+
+        if fonts.is_empty() {
+            return Err(ParserError::new("no fonts in FON file"));
+        }
+
+        Ok(fonts)
+    }
+}
+
+/// Returns the code page table for a charset.
+fn charset_table(charset: u8) -> Option<&'static [u16; 128]> {
+    Some(match charset {
+        ANSI_CHARSET | DEFAULT_CHARSET => &charsets::CP1252,
+        THAI_CHARSET => &charsets::CP874,
+        EASTEUROPE_CHARSET => &charsets::CP1250,
+        RUSSIAN_CHARSET => &charsets::CP1251,
+        GREEK_CHARSET => &charsets::CP1253,
+        TURKISH_CHARSET => &charsets::CP1254,
+        HEBREW_CHARSET => &charsets::CP1255,
+        ARABIC_CHARSET => &charsets::CP1256,
+        BALTIC_CHARSET => &charsets::CP1257,
+        OEM_CHARSET => &charsets::CP437,
+        _ => return None,
+    })
+}
+
+/// Maps a character code in a charset to an encoding.
+fn char_encoding(c: u8, charset: u8) -> Encoding {
+    let Some(table) = charset_table(charset) else {
+        return Encoding::NonStandard(c.into());
+    };
+
+    let code_point = match c {
+        // OEM fonts contain graphical characters instead of control characters.
+        0x01..=0x1F if charset == OEM_CHARSET => charsets::CP437_CONTROL[usize::from(c)],
+        0x7F if charset == OEM_CHARSET => charsets::CP437_DELETE,
+        0x00..=0x7F => c.into(),
+        0x80..=0xFF => table[usize::from(c - 0x80)],
+    };
+
+    Encoding::Standard(code_point.into())
+}
+
+/// Little endian binary data.
+struct Bytes<'a>(&'a [u8]);
+
+impl Bytes<'_> {
+    fn get<const N: usize>(&self, offset: usize) -> Result<[u8; N], ParserError> {
+        offset
+            .checked_add(N)
+            .and_then(|end| self.0.get(offset..end))
+            .map(|bytes| bytes.try_into().unwrap())
+            .ok_or_else(|| ParserError::new("unexpected end of file"))
+    }
+
+    fn u8(&self, offset: usize) -> Result<u8, ParserError> {
+        self.get::<1>(offset).map(|[value]| value)
+    }
+
+    fn u16(&self, offset: usize) -> Result<u16, ParserError> {
+        self.get(offset).map(u16::from_le_bytes)
+    }
+
+    fn u32(&self, offset: usize) -> Result<u32, ParserError> {
+        self.get(offset).map(u32::from_le_bytes)
+    }
+
+    /// Reads a zero terminated string with a maximum length.
+    fn string(&self, offset: usize, max_length: usize) -> Result<String, ParserError> {
+        let bytes = self
+            .0
+            .get(offset..offset + max_length)
+            .ok_or_else(|| ParserError::new("unexpected end of file"))?;
+        let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+
+        // FNT strings use the Windows ANSI code page.
+        Ok(bytes[..end]
+            .iter()
+            .map(|b| match char_encoding(*b, ANSI_CHARSET) {
+                Encoding::Standard(c) => char::from_u32(c).unwrap(),
+                _ => unreachable!(),
+            })
+            .collect::<String>()
+            .trim()
+            .to_string())
+    }
+
+    /// Reads a zero terminated string.
+    fn c_string(&self, offset: usize) -> Result<String, ParserError> {
+        let max_length = self.0.len().saturating_sub(offset);
+        if max_length == 0 {
+            return Err(ParserError::new("unexpected end of file"));
+        }
+
+        self.string(offset, max_length)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Glyph widths in the test font.
+    const WIDTHS: [u16; 3] = [3, 9, 5];
+    const HEIGHT: u16 = 4;
+    const ASCENT: u16 = 3;
+
+    /// Creates a FNT file with the characters 0x80 to 0x82.
+    ///
+    /// Each glyph contains a diagonal line from the top left corner and a set
+    /// pixel in the rightmost column of the last row.
+    fn fnt_file(version: u16, charset: u8) -> Vec<u8> {
+        let (header_size, entry_size) = if version == 0x0200 {
+            (FNT2_HEADER_SIZE, 4)
+        } else {
+            (FNT3_HEADER_SIZE, 6)
+        };
+
+        let mut data = vec![0; header_size];
+        let set_u16 = |data: &mut Vec<u8>, offset: usize, value: u16| {
+            data[offset..offset + 2].copy_from_slice(&value.to_le_bytes())
+        };
+
+        set_u16(&mut data, 0, version);
+        data[6..6 + 9].copy_from_slice(b"Copyright");
+        set_u16(&mut data, 68, 9); // points
+        set_u16(&mut data, 70, 96); // vertical resolution
+        set_u16(&mut data, 72, 96); // horizontal resolution
+        set_u16(&mut data, 74, ASCENT);
+        set_u16(&mut data, 83, 400); // weight
+        data[85] = charset;
+        set_u16(&mut data, 88, HEIGHT);
+        set_u16(&mut data, 91, 5); // average width
+        data[95] = 0x80; // first char
+        data[96] = 0x82; // last char
+        data[97] = 2; // default char
+
+        // Character table with an additional entry for the absolute space.
+        let bitmaps_offset = header_size + (WIDTHS.len() + 1) * entry_size;
+        let mut bitmaps = Vec::new();
+        for width in WIDTHS {
+            let offset = (bitmaps_offset + bitmaps.len()) as u32;
+            data.extend(width.to_le_bytes());
+            if version == 0x0200 {
+                data.extend((offset as u16).to_le_bytes());
+            } else {
+                data.extend(offset.to_le_bytes());
+            }
+
+            let width = usize::from(width);
+            let height = usize::from(HEIGHT);
+            let mut glyph = vec![0u8; width.div_ceil(8) * height];
+            for y in 0..height {
+                for x in 0..width {
+                    if x == y || (y == height - 1 && x == width - 1) {
+                        glyph[x / 8 * height + y] |= 0x80 >> (x % 8);
+                    }
+                }
+            }
+            bitmaps.extend(glyph);
+        }
+        data.resize(data.len() + entry_size, 0);
+        data.extend(bitmaps);
+
+        let face_offset = data.len() as u32;
+        data[105..109].copy_from_slice(&face_offset.to_le_bytes());
+        data.extend(b"Test\0");
+
+        let size = data.len() as u32;
+        data[2..6].copy_from_slice(&size.to_le_bytes());
+
+        data
+    }
+
+    /// Wraps FNT files into a NE executable.
+    fn fon_file(fonts: &[Vec<u8>]) -> Vec<u8> {
+        const NE_HEADER: usize = 0x40;
+        const RESOURCE_TABLE: usize = 0x40;
+        const ALIGNMENT_SHIFT: u16 = 4;
+
+        let mut data = vec![0; NE_HEADER + RESOURCE_TABLE];
+        data[0..2].copy_from_slice(b"MZ");
+        data[0x3C..0x40].copy_from_slice(&(NE_HEADER as u32).to_le_bytes());
+        data[NE_HEADER..NE_HEADER + 2].copy_from_slice(b"NE");
+        data[NE_HEADER + 0x24..NE_HEADER + 0x26]
+            .copy_from_slice(&(RESOURCE_TABLE as u16).to_le_bytes());
+
+        let resource_table_size = 2 + 2 * 8 + (1 + fonts.len()) * 12 + 2;
+        let mut offset = (data.len() + resource_table_size).next_multiple_of(16);
+
+        let mut table = ALIGNMENT_SHIFT.to_le_bytes().to_vec();
+
+        // Font directory, which should be skipped.
+        table.extend([0x07, 0x80, 1, 0, 0, 0, 0, 0]);
+        table.extend([0; 12]);
+
+        table.extend(RT_FONT.to_le_bytes());
+        table.extend((fonts.len() as u16).to_le_bytes());
+        table.extend([0; 4]);
+        for font in fonts {
+            let length = font.len().next_multiple_of(16);
+            table.extend(((offset >> ALIGNMENT_SHIFT) as u16).to_le_bytes());
+            table.extend(((length >> ALIGNMENT_SHIFT) as u16).to_le_bytes());
+            table.extend([0; 8]);
+            offset += length;
+        }
+        table.extend([0, 0]);
+
+        data.extend(table);
+        for font in fonts {
+            data.resize(data.len().next_multiple_of(16), 0);
+            data.extend(font);
+        }
+        data.resize(data.len().next_multiple_of(16), 0);
+
+        data
+    }
+
+    #[track_caller]
+    fn assert_glyphs(font: &Font, chars: [char; 3]) {
+        assert_eq!(font.glyphs.len(), 3);
+
+        for (c, width) in chars.into_iter().zip(WIDTHS) {
+            let glyph = font.glyphs.get(c).unwrap();
+            let width = i32::from(width);
+
+            assert_eq!(
+                glyph.bounding_box,
+                BoundingBox {
+                    offset: Coord::new(0, -1),
+                    size: Coord::new(width, 4),
+                }
+            );
+            assert_eq!(glyph.width_horizontal.unwrap().device, Coord::new(width, 0));
+
+            for y in 0..4 {
+                for x in 0..width as usize {
+                    let expected = x == y || (y == 3 && x == width as usize - 1);
+                    assert_eq!(glyph.pixel(x, y), Some(expected), "{c} {x} {y}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn fnt2_ansi() {
+        let font = Font::parse_fnt(&fnt_file(0x0200, ANSI_CHARSET)).unwrap();
+
+        assert_glyphs(&font, ['€', '\u{81}', '‚']);
+        assert_eq!(
+            font.metrics,
+            Metrics {
+                ascent: 3,
+                descent: 1
+            }
+        );
+        assert_eq!(font.metadata.point_size, 9);
+        assert_eq!(font.metadata.resolution, Coord::new(96, 96));
+        assert_eq!(
+            font.metadata.name,
+            "-unknown-Test-Medium-R-Normal--4-90-96-96-P-50-ISO10646-1"
+        );
+
+        let properties = &font.metadata.properties;
+        assert_eq!(
+            properties.try_get::<String>(Property::Copyright),
+            Ok(Some("Copyright".to_string()))
+        );
+        assert_eq!(
+            properties.try_get::<u32>(Property::DefaultChar),
+            Ok(Some(0x201A))
+        );
+    }
+
+    #[test]
+    fn fnt3_oem() {
+        let font = Font::parse_fnt(&fnt_file(0x0300, OEM_CHARSET)).unwrap();
+
+        assert_glyphs(&font, ['Ç', 'ü', 'é']);
+    }
+
+    #[test]
+    fn unknown_charset() {
+        let font = Font::parse_fnt(&fnt_file(0x0300, 128)).unwrap();
+
+        assert_eq!(
+            font.glyphs.iter().map(|g| g.encoding).collect::<Vec<_>>(),
+            [
+                Encoding::NonStandard(0x80),
+                Encoding::NonStandard(0x81),
+                Encoding::NonStandard(0x82)
+            ]
+        );
+    }
+
+    #[test]
+    fn fon() {
+        let fonts = Font::parse_fon(&fon_file(&[
+            fnt_file(0x0200, ANSI_CHARSET),
+            fnt_file(0x0300, OEM_CHARSET),
+        ]))
+        .unwrap();
+
+        assert_eq!(fonts.len(), 2);
+        assert_glyphs(&fonts[0], ['€', '\u{81}', '‚']);
+        assert_glyphs(&fonts[1], ['Ç', 'ü', 'é']);
+    }
+
+    #[test]
+    fn invalid_files() {
+        assert_eq!(
+            Font::parse_fnt(&[0x00, 0x01]),
+            Err(ParserError::new("unsupported FNT version 1.0"))
+        );
+        assert_eq!(
+            Font::parse_fon(b"STARTFONT 2.1"),
+            Err(ParserError::new("invalid FON header"))
+        );
+
+        let mut vector = fnt_file(0x0200, ANSI_CHARSET);
+        vector[66] = 1;
+        assert_eq!(
+            Font::parse_fnt(&vector),
+            Err(ParserError::new("vector fonts aren't supported"))
+        );
+    }
+}
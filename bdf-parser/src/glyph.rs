@@ -68,7 +68,7 @@ pub struct Glyph {
 }
 
 fn parse_bitmap_row(line: &Line<'_>, bitmap: &mut Vec<u8>) -> Result<(), ()> {
-    if !line.parameters.is_empty() || line.keyword.len() % 2 != 0 {
+    if !line.parameters.is_empty() || !line.keyword.len().is_multiple_of(2) {
         return Err(());
     }
 
@@ -85,8 +85,47 @@ fn parse_bitmap_row(line: &Line<'_>, bitmap: &mut Vec<u8>) -> Result<(), ()> {
     Ok(())
 }
 
+/// Creates bitmap data from a pixel getter.
+///
+/// The returned bitmap uses the same layout as [`Glyph::bitmap`], with each row
+/// padded to a whole number of bytes.
+pub(crate) fn pixels_to_bitmap(
+    width: usize,
+    height: usize,
+    pixel: impl Fn(usize, usize) -> bool,
+) -> Vec<u8> {
+    pixel_values_to_bitmap(width, height, 1, |x, y| u8::from(pixel(x, y)))
+}
+
+/// Creates bitmap data with the given number of bits per pixel from a pixel
+/// value getter.
+///
+/// Values which don't fit into `bits_per_pixel` bits are truncated.
+pub(crate) fn pixel_values_to_bitmap(
+    width: usize,
+    height: usize,
+    bits_per_pixel: u32,
+    pixel_value: impl Fn(usize, usize) -> u8,
+) -> Vec<u8> {
+    let bits_per_pixel = bits_per_pixel as usize;
+    let bytes_per_row = (width * bits_per_pixel).div_ceil(8);
+    let mask = (0xFF >> (8 - bits_per_pixel)) as u8;
+    let mut bitmap = vec![0; bytes_per_row * height];
+
+    for y in 0..height {
+        for x in 0..width {
+            let bit_offset = x * bits_per_pixel;
+            let shift = 8 - bits_per_pixel - bit_offset % 8;
+
+            bitmap[bit_offset / 8 + bytes_per_row * y] |= (pixel_value(x, y) & mask) << shift;
+        }
+    }
+
+    bitmap
+}
+
 /// Approximate SWIDTH based on DWIDTH and the font metadata.
-fn calculate_swidth(device_width: Coord, metadata: &Metadata) -> Coord {
+pub(crate) fn calculate_swidth(device_width: Coord, metadata: &Metadata) -> Coord {
     Coord {
         x: device_width.x * 1000 * 72 / metadata.point_size / metadata.resolution.x,
         y: device_width.y * 1000 * 72 / metadata.point_size / metadata.resolution.y,
@@ -234,20 +273,36 @@ impl Glyph {
     /// increase downwards.
     ///
     /// Returns `None` if the coordinates are outside the bitmap.
+    ///
+    /// This method can only be used for fonts with 1 bit per pixel. Use
+    /// [`pixel_value`](Self::pixel_value) for anti-aliased fonts.
     pub fn pixel(&self, x: usize, y: usize) -> Option<bool> {
+        self.pixel_value(x, y, 1).map(|value| value != 0)
+    }
+
+    /// Returns a pixel value from the bitmap of a font with the given bits per pixel.
+    ///
+    /// The coordinates are interpreted in the same way as in [`pixel`](Self::pixel). The returned
+    /// value is in the range `0..2^bits_per_pixel`.
+    ///
+    /// Returns `None` if the coordinates are outside the bitmap or if `bits_per_pixel` isn't
+    /// 1, 2, 4 or 8.
+    pub fn pixel_value(&self, x: usize, y: usize, bits_per_pixel: u32) -> Option<u8> {
         let width = usize::try_from(self.bounding_box.size.x).unwrap();
 
-        if x >= width {
+        if x >= width || !matches!(bits_per_pixel, 1 | 2 | 4 | 8) {
             return None;
         }
 
-        let bytes_per_row = width.div_ceil(8);
-        let byte_offset = x / 8;
-        let bit_mask = 0x80 >> (x % 8);
+        let bits_per_pixel = bits_per_pixel as usize;
+        let bytes_per_row = (width * bits_per_pixel).div_ceil(8);
+        let bit_offset = x * bits_per_pixel;
+        let shift = 8 - bits_per_pixel - bit_offset % 8;
+        let mask = (0xFF >> (8 - bits_per_pixel)) as u8;
 
         self.bitmap
-            .get(byte_offset + bytes_per_row * y)
-            .map(|v| v & bit_mask != 0)
+            .get(bit_offset / 8 + bytes_per_row * y)
+            .map(|v| (v >> shift) & mask)
     }
 
     /// Returns an iterator over the pixels in the glyph bitmap.
@@ -268,6 +323,18 @@ pub struct Glyphs {
     glyphs: Vec<Glyph>,
 }
 
+impl FromIterator<Glyph> for Glyphs {
+    /// Creates a glyphs collection.
+    ///
+    /// The glyphs are sorted by their encoding.
+    fn from_iter<T: IntoIterator<Item = Glyph>>(iter: T) -> Self {
+        let mut glyphs = iter.into_iter().collect::<Vec<_>>();
+        glyphs.sort_by_key(|glyph| glyph.encoding);
+
+        Self { glyphs }
+    }
+}
+
 impl Glyphs {
     pub(crate) fn parse(lines: &mut Lines<'_>, metadata: &Metadata) -> Result<Self, ParserError> {
         let mut glyphs = Vec::new();
@@ -320,6 +387,16 @@ impl Glyphs {
         self.glyphs.iter()
     }
 
+    /// Returns the number of glyphs.
+    pub fn len(&self) -> usize {
+        self.glyphs.len()
+    }
+
+    /// Returns `true` if the collection contains no glyphs.
+    pub fn is_empty(&self) -> bool {
+        self.glyphs.is_empty()
+    }
+
     /// Approximates the ascent.
     ///
     /// See section 8.2.1 FONT_ASCENT in https://www.x.org/docs/XLFD/xlfd.pdf.
@@ -359,6 +436,7 @@ mod tests {
             name: "test".to_string(),
             point_size: 16,
             resolution: Coord::new(100, 100),
+            bits_per_pixel: 1,
             bounding_box: BoundingBox::default(),
             metrics_set: crate::MetricsSet::Horizontal,
             properties: Properties::default(),
@@ -549,6 +627,54 @@ mod tests {
         assert_eq!(glyph.pixel(8, 16), None);
     }
 
+    #[test]
+    fn pixel_value() {
+        let glyph = Glyph {
+            name: "gray".to_string(),
+            encoding: Encoding::Unspecified,
+            width_horizontal: None,
+            width_vertical: None,
+            bounding_box: BoundingBox {
+                offset: Coord::new(0, 0),
+                size: Coord::new(3, 2),
+            },
+            origin_offset: None,
+            bitmap: vec![0x1B, 0x00, 0xC0, 0x00],
+        };
+
+        assert_eq!(glyph.pixel_value(0, 0, 4), Some(0x1));
+        assert_eq!(glyph.pixel_value(1, 0, 4), Some(0xB));
+        assert_eq!(glyph.pixel_value(2, 0, 4), Some(0x0));
+        assert_eq!(glyph.pixel_value(0, 1, 4), Some(0xC));
+        assert_eq!(glyph.pixel_value(3, 0, 4), None);
+
+        assert_eq!(glyph.pixel_value(0, 0, 2), Some(0));
+        assert_eq!(glyph.pixel_value(1, 0, 2), Some(1));
+        assert_eq!(glyph.pixel_value(2, 0, 2), Some(2));
+    }
+
+    #[test]
+    fn pixel_value_invalid_bits_per_pixel() {
+        let glyph = Glyph {
+            name: "gray".to_string(),
+            encoding: Encoding::Unspecified,
+            width_horizontal: None,
+            width_vertical: None,
+            bounding_box: BoundingBox {
+                offset: Coord::new(0, 0),
+                size: Coord::new(1, 1),
+            },
+            origin_offset: None,
+            bitmap: vec![0xFF],
+        };
+
+        assert_eq!(glyph.pixel_value(0, 0, 0), None);
+        assert_eq!(glyph.pixel_value(0, 0, 3), None);
+        assert_eq!(glyph.pixel_value(0, 0, 9), None);
+        assert_eq!(glyph.pixel_value(0, 0, u32::MAX), None);
+        assert_eq!(glyph.pixel_value(0, 0, 8), Some(0xFF));
+    }
+
     #[test]
     fn parse_glyph_with_no_encoding() {
         let chardata = indoc! {r#"
@@ -0,0 +1,452 @@
+use std::{collections::BTreeSet, fmt::Write};
+
+use thiserror::Error;
+
+use crate::{
+    diff::set_pixels,
+    glyph::{pixels_to_bitmap, GlyphWidth},
+    BoundingBox, Coord, Encoding, Font, Glyph, Glyphs, Metadata, Metrics, ParserError,
+};
+
+/// Glyph height in GNU Unifont `.hex` files.
+pub(crate) const HEX_GLYPH_HEIGHT: u32 = 16;
+
+/// GNU Unifont `.hex` export error.
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum HexError {
+    /// The font ascent is larger than the glyph height.
+    #[error("font ascent ({0}) is larger than the glyph height ({HEX_GLYPH_HEIGHT})")]
+    InvalidAscent(u32),
+
+    /// A glyph is wider than 16 pixels.
+    #[error("glyph \"{name}\" is {width} pixels wide, but must be at most 16 pixels wide")]
+    GlyphTooWide {
+        /// Glyph name.
+        name: String,
+        /// Glyph width in pixels.
+        width: i32,
+    },
+
+    /// A glyph extends above or below the 16 pixel high glyph cell.
+    #[error("glyph \"{0}\" doesn't fit into the 16 pixel high glyph cell")]
+    GlyphTooTall(String),
+
+    /// A glyph contains pixels to the left of the origin.
+    #[error("glyph \"{0}\" contains pixels to the left of the origin")]
+    NegativeBearing(String),
+
+    /// The font uses more than one bit per pixel.
+    #[error("unsupported bits per pixel value {0}, .hex fonts must be monochrome")]
+    UnsupportedBitsPerPixel(u32),
+}
+
+impl Font {
+    /// Parses a GNU Unifont `.hex` file.
+    ///
+    /// Each line in a `.hex` file contains a code point and the glyph bitmap,
+    /// separated by a colon. All glyphs are 16 pixels high and the width of the
+    /// glyph is determined by the length of the bitmap data, e.g. 32 hex digits
+    /// for 8×16 glyphs and 64 hex digits for 16×16 glyphs.
+    ///
+    /// Because `.hex` files don't contain any metrics, the ascent needs to be
+    /// specified. The descent is set to the remaining rows of the 16 pixel high
+    /// glyphs. GNU Unifont uses an ascent of 14 pixels.
+    pub fn parse_hex(input: &str, ascent: u32) -> Result<Self, ParserError> {
+        if ascent > HEX_GLYPH_HEIGHT {
+            return Err(ParserError::new(&format!(
+                "ascent must not be larger than the glyph height ({HEX_GLYPH_HEIGHT})"
+            )));
+        }
+
+        let metrics = Metrics {
+            ascent,
+            descent: HEX_GLYPH_HEIGHT - ascent,
+        };
+
+        let mut encodings = BTreeSet::new();
+        let mut glyphs = Vec::new();
+
+        for (index, line) in input.lines().enumerate() {
+            let line = line.trim();
+            let line_number = index + 1;
+
+            if line.is_empty() {
+                continue;
+            }
+
+            let glyph = parse_hex_glyph(line, &metrics)
+                .map_err(|message| ParserError::with_line_number(message, line_number))?;
+
+            if !encodings.insert(glyph.encoding) {
+                return Err(ParserError::with_line_number(
+                    "duplicate code point",
+                    line_number,
+                ));
+            }
+
+            glyphs.push(glyph);
+        }
+
+        if glyphs.is_empty() {
+            return Err(ParserError::new("no glyphs in hex file"));
+        }
+
+        let glyphs = glyphs.into_iter().collect::<Glyphs>();
+        let metadata = Metadata::for_imported_font(&glyphs, &metrics, HEX_GLYPH_HEIGHT, true);
+
+        Ok(Font {
+            metadata,
+            glyphs,
+            metrics,
+        })
+    }
+
+    /// Converts the font into the GNU Unifont `.hex` format.
+    ///
+    /// Glyphs are placed into 16 pixel high cells, with the font ascent above
+    /// the baseline and the remaining rows below it. Glyphs which are narrower
+    /// than 8 or 16 pixels are padded to the next valid width. The width of a
+    /// glyph is determined by its set pixels and its device width.
+    ///
+    /// Only glyphs with a standard encoding are included in the output and the
+    /// lines are sorted by code point. Only fonts with 1 bit per pixel can be
+    /// converted.
+    pub fn to_hex(&self) -> Result<String, HexError> {
+        if self.metadata.bits_per_pixel != 1 {
+            return Err(HexError::UnsupportedBitsPerPixel(
+                self.metadata.bits_per_pixel,
+            ));
+        }
+
+        if self.metrics.ascent > HEX_GLYPH_HEIGHT {
+            return Err(HexError::InvalidAscent(self.metrics.ascent));
+        }
+
+        let ascent = self.metrics.ascent as i32;
+        let descent = HEX_GLYPH_HEIGHT as i32 - ascent;
+
+        let mut glyphs = self
+            .glyphs
+            .iter()
+            .filter_map(|glyph| match glyph.encoding {
+                Encoding::Standard(code_point) => Some((code_point, glyph)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        glyphs.sort_by_key(|(code_point, _)| *code_point);
+
+        let mut output = String::new();
+
+        for (code_point, glyph) in glyphs {
+            let pixels = set_pixels(glyph);
+
+            if pixels.iter().any(|pixel| pixel.x < 0) {
+                return Err(HexError::NegativeBearing(glyph.name.clone()));
+            }
+            if pixels
+                .iter()
+                .any(|pixel| pixel.y >= ascent || pixel.y < -descent)
+            {
+                return Err(HexError::GlyphTooTall(glyph.name.clone()));
+            }
+
+            let width = pixels
+                .iter()
+                .map(|pixel| pixel.x + 1)
+                .chain(glyph.width_horizontal.map(|width| width.device.x))
+                .max()
+                .unwrap_or(0);
+
+            let cell_width = match width {
+                ..=8 => 8,
+                9..=16 => 16,
+                _ => {
+                    return Err(HexError::GlyphTooWide {
+                        name: glyph.name.clone(),
+                        width,
+                    })
+                }
+            };
+
+            let bitmap = pixels_to_bitmap(cell_width, HEX_GLYPH_HEIGHT as usize, |x, y| {
+                pixels.contains(&Coord::new(x as i32, ascent - 1 - y as i32))
+            });
+
+            write!(output, "{code_point:04X}:").unwrap();
+            for byte in bitmap {
+                write!(output, "{byte:02X}").unwrap();
+            }
+            output.push('\n');
+        }
+
+        Ok(output)
+    }
+}
+
+fn parse_hex_glyph(line: &str, metrics: &Metrics) -> Result<Glyph, &'static str> {
+    let (code_point, data) = line
+        .split_once(':')
+        .ok_or("expected \"<code point>:<bitmap>\"")?;
+
+    let code_point = u32::from_str_radix(code_point, 16).map_err(|_| "invalid code point")?;
+
+    // Each hex digit encodes 4 pixels and rows are padded to whole bytes.
+    let digits = data.len();
+    if digits == 0 || !digits.is_multiple_of(2 * HEX_GLYPH_HEIGHT as usize) {
+        return Err("invalid bitmap length");
+    }
+    let width = digits * 4 / HEX_GLYPH_HEIGHT as usize;
+
+    let bitmap = data
+        .as_bytes()
+        .chunks_exact(2)
+        .map(|hex| {
+            std::str::from_utf8(hex)
+                .ok()
+                .and_then(|s| u8::from_str_radix(s, 16).ok())
+        })
+        .collect::<Option<Vec<_>>>()
+        .ok_or("invalid hex data in bitmap")?;
+
+    let width = width as i32;
+    let device = Coord::new(width, 0);
+
+    Ok(Glyph {
+        name: format!("U+{code_point:04X}"),
+        encoding: Encoding::Standard(code_point),
+        width_horizontal: Some(GlyphWidth {
+            scalable: Coord::new(width * 1000 / HEX_GLYPH_HEIGHT as i32, 0),
+            device,
+        }),
+        width_vertical: None,
+        bounding_box: BoundingBox {
+            offset: Coord::new(0, -(metrics.descent as i32)),
+            size: Coord::new(width, HEX_GLYPH_HEIGHT as i32),
+        },
+        origin_offset: None,
+        bitmap,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+    use crate::Property;
+
+    const INVALID_LENGTH: &str = "\
+        0041:0000000018242442427E424242420000\n\
+        \n\
+        4E00:00000000000000000000FFFE00000000000000000000000000000000000000000\n";
+
+    const HEX: &str = "\
+        0041:0000000018242442427E424242420000\n\
+        4E00:0000000000000000000000000000FFFE00000000000000000000000000000000\n";
+
+    #[test]
+    fn parse_hex() {
+        let font = Font::parse_hex(HEX, 14).unwrap();
+
+        assert_eq!(
+            font.metrics,
+            Metrics {
+                ascent: 14,
+                descent: 2
+            }
+        );
+        assert_eq!(
+            font.metadata.bounding_box,
+            BoundingBox {
+                offset: Coord::new(0, -2),
+                size: Coord::new(16, 16),
+            }
+        );
+        assert_eq!(
+            font.metadata
+                .properties
+                .try_get::<u32>(Property::FontAscent),
+            Ok(Some(14))
+        );
+
+        let a = font.glyphs.get('A').unwrap();
+        assert_eq!(a.name, "U+0041");
+        assert_eq!(
+            a.bounding_box,
+            BoundingBox {
+                offset: Coord::new(0, -2),
+                size: Coord::new(8, 16),
+            }
+        );
+        assert_eq!(
+            a.width_horizontal,
+            Some(GlyphWidth {
+                scalable: Coord::new(500, 0),
+                device: Coord::new(8, 0),
+            })
+        );
+        assert_eq!(a.pixel(3, 4), Some(true));
+        assert_eq!(a.pixel(2, 4), Some(false));
+
+        let one = font.glyphs.get('一').unwrap();
+        assert_eq!(one.bounding_box.size, Coord::new(16, 16));
+        assert_eq!(one.width_horizontal.unwrap().device, Coord::new(16, 0));
+        assert_eq!(one.pixels().filter(|pixel| *pixel).count(), 15);
+    }
+
+    #[test]
+    fn invalid_bitmap_length() {
+        assert_eq!(
+            Font::parse_hex(INVALID_LENGTH, 14),
+            Err(ParserError::with_line_number("invalid bitmap length", 3))
+        );
+    }
+
+    #[test]
+    fn invalid_line() {
+        assert_eq!(
+            Font::parse_hex("0041\n", 14),
+            Err(ParserError::with_line_number(
+                "expected \"<code point>:<bitmap>\"",
+                1
+            ))
+        );
+        assert_eq!(
+            Font::parse_hex("XYZ:0000000018242442427E424242420000\n", 14),
+            Err(ParserError::with_line_number("invalid code point", 1))
+        );
+        assert_eq!(
+            Font::parse_hex("0041:0000000018242442427E4242424200XX\n", 14),
+            Err(ParserError::with_line_number(
+                "invalid hex data in bitmap",
+                1
+            ))
+        );
+    }
+
+    #[test]
+    fn duplicate_code_point() {
+        let input = "0041:0000000018242442427E424242420000\n\
+                     0041:0000000018242442427E424242420000\n";
+
+        assert_eq!(
+            Font::parse_hex(input, 14),
+            Err(ParserError::with_line_number("duplicate code point", 2))
+        );
+    }
+
+    #[test]
+    fn invalid_ascent() {
+        assert!(Font::parse_hex(HEX, 17).is_err());
+    }
+
+    const BDF: &str = indoc! {r#"
+        STARTFONT 2.1
+        FONT test
+        SIZE 8 75 75
+        FONTBOUNDINGBOX 9 10 0 -2
+        STARTPROPERTIES 2
+        FONT_ASCENT 8
+        FONT_DESCENT 2
+        ENDPROPERTIES
+        CHARS 3
+        STARTCHAR A
+        ENCODING 65
+        DWIDTH 6 0
+        BBX 5 5 0 0
+        BITMAP
+        20
+        50
+        88
+        F8
+        88
+        ENDCHAR
+        STARTCHAR g
+        ENCODING 103
+        DWIDTH 6 0
+        BBX 4 5 1 -2
+        BITMAP
+        70
+        90
+        70
+        10
+        60
+        ENDCHAR
+        STARTCHAR W
+        ENCODING 87
+        DWIDTH 10 0
+        BBX 9 3 0 0
+        BITMAP
+        8080
+        4900
+        3600
+        ENDCHAR
+        ENDFONT
+    "#};
+
+    #[test]
+    fn hex_round_trip() {
+        let font = Font::parse_hex(HEX, 14).unwrap();
+
+        assert_eq!(font.to_hex().unwrap(), HEX);
+    }
+
+    #[test]
+    fn bdf_round_trip() {
+        let font = Font::parse(BDF).unwrap();
+        let hex = font.to_hex().unwrap();
+
+        let lines = hex.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("0041:"));
+        assert_eq!(lines[0].len(), 5 + 32);
+        assert!(lines[1].starts_with("0057:"));
+        assert_eq!(lines[1].len(), 5 + 64);
+        assert!(lines[2].starts_with("0067:"));
+
+        let hex_font = Font::parse_hex(&hex, font.metrics.ascent).unwrap();
+        let diff = font.diff(&hex_font);
+
+        assert!(diff.added.is_empty());
+        assert!(diff.removed.is_empty());
+        assert!(diff.changed.iter().all(|glyph| !glyph.bitmap_changed()));
+    }
+
+    #[test]
+    fn glyph_too_wide() {
+        let font = Font::parse(&BDF.replace("DWIDTH 10 0", "DWIDTH 17 0")).unwrap();
+
+        assert_eq!(
+            font.to_hex(),
+            Err(HexError::GlyphTooWide {
+                name: "W".to_string(),
+                width: 17,
+            })
+        );
+    }
+
+    #[test]
+    fn glyph_too_tall() {
+        let font = Font::parse(&BDF.replace("FONT_ASCENT 8", "FONT_ASCENT 15")).unwrap();
+
+        assert_eq!(font.to_hex(), Err(HexError::GlyphTooTall("g".to_string())));
+    }
+
+    #[test]
+    fn negative_bearing() {
+        let font = Font::parse(&BDF.replace("BBX 4 5 1 -2", "BBX 4 5 -1 -2")).unwrap();
+
+        assert_eq!(
+            font.to_hex(),
+            Err(HexError::NegativeBearing("g".to_string()))
+        );
+    }
+
+    #[test]
+    fn unsupported_bits_per_pixel() {
+        let mut font = Font::parse(BDF).unwrap();
+        font.metadata.bits_per_pixel = 2;
+
+        assert_eq!(font.to_hex(), Err(HexError::UnsupportedBitsPerPixel(2)));
+    }
+}
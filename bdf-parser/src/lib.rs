@@ -4,15 +4,31 @@
 #![deny(unsafe_code)]
 #![deny(missing_debug_implementations)]
 
+mod diff;
+mod fnt;
 mod glyph;
+mod hex;
+mod merge;
 mod metadata;
 mod parser;
+mod pcf;
 mod properties;
-
-pub use glyph::{Encoding, Glyph, Glyphs};
+mod psf;
+mod subset;
+mod u8g2;
+mod writer;
+mod yaff;
+
+pub use diff::{FontDiff, GlyphDiff, PropertyDiff};
+pub use glyph::{Encoding, Glyph, GlyphWidth, Glyphs};
+pub use hex::HexError;
+pub use merge::{ConflictResolution, MergeError, MergePolicy, MetricsAdjustment};
 pub use metadata::{Metadata, MetricsSet};
 pub use parser::ParserError;
-pub use properties::{Properties, Property, PropertyType};
+pub use pcf::{ByteOrder, GlyphPadding, PcfError, PcfOptions};
+pub use properties::{Properties, Property, PropertyType, PropertyValue};
+pub use psf::{PsfError, PsfVersion};
+pub use yaff::YaffError;
 
 use crate::parser::{Line, Lines};
 
@@ -31,6 +47,8 @@ pub struct Font {
 
 impl Font {
     /// Parses a BDF file.
+    ///
+    /// BDF versions 2.1, 2.2 and 2.3 are supported.
     pub fn parse(input: &str) -> Result<Self, ParserError> {
         let mut lines = Lines::new(input);
 
@@ -38,9 +56,11 @@ impl Font {
             .next()
             .ok_or_else(|| ParserError::new("empty input"))?;
 
-        if first_line.keyword != "STARTFONT" || first_line.parameters != "2.1" {
+        if first_line.keyword != "STARTFONT"
+            || !["2.1", "2.2", "2.3"].contains(&first_line.parameters)
+        {
             return Err(ParserError::with_line(
-                "expected \"STARTFONT 2.1\"",
+                "expected \"STARTFONT 2.1\", \"STARTFONT 2.2\" or \"STARTFONT 2.3\"",
                 &first_line,
             ));
         }
@@ -231,6 +251,7 @@ mod tests {
                 name: String::from("\"test font\""),
                 point_size: 16,
                 resolution: Coord::new(75, 75),
+                bits_per_pixel: 1,
                 bounding_box: BoundingBox {
                     size: Coord::new(16, 24),
                     offset: Coord::new(0, 0),
@@ -360,7 +381,21 @@ mod tests {
     #[test]
     fn invalid_first_line() {
         let input = "\nSOMETHING 2.1";
-        assert_parser_error(input, "expected \"STARTFONT 2.1\"", Some(2));
+        assert_parser_error(
+            input,
+            "expected \"STARTFONT 2.1\", \"STARTFONT 2.2\" or \"STARTFONT 2.3\"",
+            Some(2),
+        );
+    }
+
+    #[test]
+    fn unsupported_version() {
+        let input = "STARTFONT 3.0\n";
+        assert_parser_error(
+            input,
+            "expected \"STARTFONT 2.1\", \"STARTFONT 2.2\" or \"STARTFONT 2.3\"",
+            Some(1),
+        );
     }
 
     #[test]
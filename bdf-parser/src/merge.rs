@@ -0,0 +1,496 @@
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+use crate::{
+    glyph::pixel_values_to_bitmap, properties::PropertyValue, BoundingBox, Coord, Encoding, Font,
+    Glyph, Metrics, Property,
+};
+
+/// Merge policy.
+///
+/// See [`Font::merge`] for more information.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MergePolicy {
+    /// Resolution for glyphs that are included in both fonts.
+    pub conflicts: ConflictResolution,
+
+    /// Adjustment for glyphs from the merged font if the metrics differ.
+    pub metrics: MetricsAdjustment,
+}
+
+/// Conflict resolution for glyphs with the same encoding.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictResolution {
+    /// Keeps the existing glyph.
+    #[default]
+    KeepExisting,
+    /// Replaces the existing glyph by the glyph from the merged font.
+    Replace,
+    /// Returns an error.
+    Error,
+}
+
+/// Metrics adjustment for merged glyphs.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MetricsAdjustment {
+    /// Glyphs are copied unchanged.
+    ///
+    /// The glyphs stay aligned to the common baseline and the metrics of the
+    /// merged font are extended to fit the ascent and descent of both fonts.
+    #[default]
+    None,
+    /// Glyphs are shifted vertically.
+    ///
+    /// The glyphs are moved to vertically center the line of the merged font
+    /// inside the line of the existing font.
+    Rebaseline,
+    /// Glyphs are scaled and shifted vertically.
+    ///
+    /// The glyphs are scaled by the ratio of the line heights and moved to align
+    /// the lines of both fonts.
+    Rescale,
+}
+
+/// Merge error.
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+pub enum MergeError {
+    /// A glyph is included in both fonts.
+    #[error("glyph with encoding {0:?} is included in both fonts")]
+    Conflict(Encoding),
+
+    /// The fonts use a different number of bits per pixel.
+    #[error("can't merge a font with {1} bits per pixel into a font with {0} bits per pixel")]
+    BitsPerPixelMismatch(u32, u32),
+}
+
+impl Font {
+    /// Merges the glyphs of another font into this font.
+    ///
+    /// The metadata of the returned font is based on this font. The font bounding
+    /// box and the `FONT_ASCENT` and `FONT_DESCENT` properties are updated to fit
+    /// the glyphs of both fonts.
+    ///
+    /// Glyphs with an unspecified encoding are always added and never conflict
+    /// with existing glyphs.
+    ///
+    /// Both fonts must use the same number of bits per pixel.
+    pub fn merge(&self, other: &Font, policy: MergePolicy) -> Result<Font, MergeError> {
+        let bits_per_pixel = self.metadata.bits_per_pixel;
+        if other.metadata.bits_per_pixel != bits_per_pixel {
+            return Err(MergeError::BitsPerPixelMismatch(
+                bits_per_pixel,
+                other.metadata.bits_per_pixel,
+            ));
+        }
+
+        let mut glyphs = self.glyphs.iter().cloned().collect::<Vec<_>>();
+        let mut indices = glyphs
+            .iter()
+            .enumerate()
+            .map(|(index, glyph)| (glyph.encoding, index))
+            .collect::<BTreeMap<_, _>>();
+        let mut bounding_box = self.metadata.bounding_box;
+        let mut metrics = self.metrics;
+
+        let (scale, other_metrics) = match policy.metrics {
+            MetricsAdjustment::Rescale if other.metrics.line_height() > 0 => {
+                let scale = Scale {
+                    numerator: self.metrics.line_height(),
+                    denominator: other.metrics.line_height(),
+                };
+                let ascent = scale.apply(other.metrics.ascent as i32) as u32;
+                let metrics = Metrics {
+                    ascent,
+                    descent: self.metrics.line_height() - ascent,
+                };
+
+                (Some(scale), metrics)
+            }
+            _ => (None, other.metrics),
+        };
+
+        let shift = match policy.metrics {
+            MetricsAdjustment::None => 0,
+            MetricsAdjustment::Rebaseline | MetricsAdjustment::Rescale => {
+                let center = self.metrics.ascent as i32 - self.metrics.descent as i32;
+                let other_center = other_metrics.ascent as i32 - other_metrics.descent as i32;
+
+                (center - other_center).div_euclid(2)
+            }
+        };
+
+        metrics.ascent = metrics
+            .ascent
+            .max((other_metrics.ascent as i32 + shift).max(0) as u32);
+        metrics.descent = metrics
+            .descent
+            .max((other_metrics.descent as i32 - shift).max(0) as u32);
+
+        for glyph in other.glyphs.iter() {
+            let mut glyph = match scale {
+                Some(scale) => scale_glyph(glyph, scale, bits_per_pixel),
+                None => glyph.clone(),
+            };
+            glyph.bounding_box.offset.y += shift;
+
+            let existing = if glyph.encoding == Encoding::Unspecified {
+                None
+            } else {
+                indices.get(&glyph.encoding).copied()
+            };
+
+            match (existing, policy.conflicts) {
+                (None, _) => {
+                    indices.insert(glyph.encoding, glyphs.len());
+                    glyphs.push(glyph);
+                }
+                (Some(_), ConflictResolution::KeepExisting) => continue,
+                (Some(index), ConflictResolution::Replace) => glyphs[index] = glyph,
+                (Some(_), ConflictResolution::Error) => {
+                    return Err(MergeError::Conflict(glyph.encoding))
+                }
+            }
+        }
+
+        for glyph in glyphs.iter() {
+            bounding_box = bounding_box.union(&glyph.bounding_box);
+        }
+
+        let mut metadata = self.metadata.clone();
+        metadata.bounding_box = bounding_box;
+        metadata.properties.set(
+            Property::FontAscent,
+            PropertyValue::Int(metrics.ascent as i32),
+        );
+        metadata.properties.set(
+            Property::FontDescent,
+            PropertyValue::Int(metrics.descent as i32),
+        );
+
+        Ok(Font {
+            metadata,
+            glyphs: glyphs.into_iter().collect(),
+            metrics,
+        })
+    }
+}
+
+/// Rational scale factor.
+#[derive(Debug, Clone, Copy)]
+struct Scale {
+    numerator: u32,
+    denominator: u32,
+}
+
+impl Scale {
+    /// Scales a value and rounds the result to the nearest integer.
+    fn apply(self, value: i32) -> i32 {
+        let numerator = self.numerator as i32;
+        let denominator = self.denominator as i32;
+
+        (2 * value * numerator + denominator).div_euclid(2 * denominator)
+    }
+
+    fn apply_coord(self, coord: Coord) -> Coord {
+        Coord::new(self.apply(coord.x), self.apply(coord.y))
+    }
+}
+
+/// Scales a glyph by using nearest neighbor interpolation.
+fn scale_glyph(glyph: &Glyph, scale: Scale, bits_per_pixel: u32) -> Glyph {
+    let size = scale.apply_coord(glyph.bounding_box.size);
+    let width = size.x.max(0) as usize;
+    let height = size.y.max(0) as usize;
+
+    let source = |v: usize| v * scale.denominator as usize / scale.numerator as usize;
+    let bitmap = pixel_values_to_bitmap(width, height, bits_per_pixel, |x, y| {
+        glyph
+            .pixel_value(source(x), source(y), bits_per_pixel)
+            .unwrap_or_default()
+    });
+
+    let mut width_horizontal = glyph.width_horizontal;
+    if let Some(width) = &mut width_horizontal {
+        width.device = scale.apply_coord(width.device);
+    }
+
+    let mut width_vertical = glyph.width_vertical;
+    if let Some(width) = &mut width_vertical {
+        width.device = scale.apply_coord(width.device);
+    }
+
+    Glyph {
+        bounding_box: BoundingBox {
+            offset: scale.apply_coord(glyph.bounding_box.offset),
+            size: Coord::new(width as i32, height as i32),
+        },
+        width_horizontal,
+        width_vertical,
+        origin_offset: glyph.origin_offset.map(|offset| scale.apply_coord(offset)),
+        bitmap,
+        ..glyph.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    const LATIN: &str = indoc! {r#"
+        STARTFONT 2.1
+        FONT latin
+        SIZE 4 75 75
+        FONTBOUNDINGBOX 2 4 0 -1
+        STARTPROPERTIES 2
+        FONT_ASCENT 3
+        FONT_DESCENT 1
+        ENDPROPERTIES
+        CHARS 2
+        STARTCHAR A
+        ENCODING 65
+        DWIDTH 3 0
+        BBX 2 3 0 0
+        BITMAP
+        40
+        C0
+        C0
+        ENDCHAR
+        STARTCHAR B
+        ENCODING 66
+        DWIDTH 3 0
+        BBX 2 3 0 0
+        BITMAP
+        80
+        C0
+        C0
+        ENDCHAR
+        ENDFONT
+    "#};
+
+    const SYMBOLS: &str = indoc! {r#"
+        STARTFONT 2.1
+        FONT symbols
+        SIZE 8 75 75
+        FONTBOUNDINGBOX 4 8 0 -2
+        STARTPROPERTIES 2
+        FONT_ASCENT 6
+        FONT_DESCENT 2
+        ENDPROPERTIES
+        CHARS 2
+        STARTCHAR B
+        ENCODING 66
+        DWIDTH 4 0
+        BBX 4 8 0 -2
+        BITMAP
+        F0
+        F0
+        F0
+        F0
+        F0
+        F0
+        F0
+        F0
+        ENDCHAR
+        STARTCHAR uni2605
+        ENCODING 9733
+        DWIDTH 5 0
+        BBX 4 8 0 -2
+        BITMAP
+        C0
+        C0
+        30
+        30
+        C0
+        C0
+        30
+        30
+        ENDCHAR
+        ENDFONT
+    "#};
+
+    fn fonts() -> (Font, Font) {
+        (Font::parse(LATIN).unwrap(), Font::parse(SYMBOLS).unwrap())
+    }
+
+    fn policy(conflicts: ConflictResolution, metrics: MetricsAdjustment) -> MergePolicy {
+        MergePolicy { conflicts, metrics }
+    }
+
+    fn ascent_and_descent(font: &Font) -> (Option<u32>, Option<u32>) {
+        let properties = &font.metadata.properties;
+
+        (
+            properties.try_get(Property::FontAscent).unwrap(),
+            properties.try_get(Property::FontDescent).unwrap(),
+        )
+    }
+
+    #[test]
+    fn keep_existing() {
+        let (latin, symbols) = fonts();
+        let merged = latin.merge(&symbols, MergePolicy::default()).unwrap();
+
+        assert_eq!(
+            merged.glyphs.iter().map(|g| &g.name).collect::<Vec<_>>(),
+            ["A", "B", "uni2605"]
+        );
+        assert_eq!(merged.glyphs.get('B'), latin.glyphs.get('B'));
+        assert_eq!(merged.glyphs.get('★'), symbols.glyphs.get('★'));
+
+        assert_eq!(merged.metadata.name, "latin");
+        assert_eq!(
+            merged.metadata.bounding_box,
+            BoundingBox {
+                offset: Coord::new(0, -2),
+                size: Coord::new(4, 8),
+            }
+        );
+        assert_eq!(
+            merged.metrics,
+            Metrics {
+                ascent: 6,
+                descent: 2
+            }
+        );
+        assert_eq!(ascent_and_descent(&merged), (Some(6), Some(2)));
+    }
+
+    #[test]
+    fn replace() {
+        let (latin, symbols) = fonts();
+        let merged = latin
+            .merge(
+                &symbols,
+                policy(ConflictResolution::Replace, MetricsAdjustment::None),
+            )
+            .unwrap();
+
+        assert_eq!(merged.glyphs.get('A'), latin.glyphs.get('A'));
+        assert_eq!(merged.glyphs.get('B'), symbols.glyphs.get('B'));
+    }
+
+    #[test]
+    fn conflict_error() {
+        let (latin, symbols) = fonts();
+
+        assert_eq!(
+            latin.merge(
+                &symbols,
+                policy(ConflictResolution::Error, MetricsAdjustment::None),
+            ),
+            Err(MergeError::Conflict(Encoding::Standard(66)))
+        );
+    }
+
+    #[test]
+    fn rebaseline() {
+        let (latin, symbols) = fonts();
+        let merged = latin
+            .merge(
+                &symbols,
+                policy(
+                    ConflictResolution::KeepExisting,
+                    MetricsAdjustment::Rebaseline,
+                ),
+            )
+            .unwrap();
+
+        // The center of the symbols line (6 - 2) is moved to the center of the
+        // latin line (3 - 1), which is a shift by one pixel downwards.
+        assert_eq!(
+            merged.glyphs.get('★').unwrap().bounding_box.offset,
+            Coord::new(0, -3)
+        );
+        assert_eq!(
+            merged.metrics,
+            Metrics {
+                ascent: 5,
+                descent: 3
+            }
+        );
+        assert_eq!(
+            merged.metadata.bounding_box,
+            BoundingBox {
+                offset: Coord::new(0, -3),
+                size: Coord::new(4, 8),
+            }
+        );
+    }
+
+    #[test]
+    fn rescale() {
+        let (latin, symbols) = fonts();
+        let merged = latin
+            .merge(
+                &symbols,
+                policy(ConflictResolution::KeepExisting, MetricsAdjustment::Rescale),
+            )
+            .unwrap();
+
+        let star = merged.glyphs.get('★').unwrap();
+        assert_eq!(
+            star.bounding_box,
+            BoundingBox {
+                offset: Coord::new(0, -1),
+                size: Coord::new(2, 4),
+            }
+        );
+        assert_eq!(star.width_horizontal.unwrap().device, Coord::new(3, 0));
+        assert_eq!(star.bitmap, vec![0x80, 0x40, 0x80, 0x40]);
+
+        assert_eq!(merged.metrics, latin.metrics);
+        assert_eq!(merged.metadata.bounding_box, latin.metadata.bounding_box);
+        assert_eq!(ascent_and_descent(&merged), (Some(3), Some(1)));
+    }
+
+    #[test]
+    fn unspecified_encodings_are_added() {
+        let (latin, _) = fonts();
+        let merged = latin.merge(&latin, MergePolicy::default()).unwrap();
+        assert_eq!(merged.glyphs.iter().count(), 2);
+
+        let mut glyph = latin.glyphs.get('A').unwrap().clone();
+        glyph.encoding = Encoding::Unspecified;
+
+        let other = Font {
+            glyphs: [glyph.clone(), glyph].into_iter().collect(),
+            ..latin.clone()
+        };
+        let merged = latin.merge(&other, MergePolicy::default()).unwrap();
+        assert_eq!(merged.glyphs.iter().count(), 4);
+    }
+
+    #[test]
+    fn rescale_grayscale() {
+        let latin = Font::parse(&LATIN.replace("SIZE 4 75 75", "SIZE 4 75 75 2")).unwrap();
+        let symbols = SYMBOLS
+            .replace("SIZE 8 75 75", "SIZE 8 75 75 2")
+            .replace("BBX 4 8 0 -2\nBITMAP\nC0", "BBX 4 8 0 -2\nBITMAP\nE4");
+        let symbols = Font::parse(&symbols).unwrap();
+
+        let merged = latin
+            .merge(
+                &symbols,
+                policy(ConflictResolution::KeepExisting, MetricsAdjustment::Rescale),
+            )
+            .unwrap();
+
+        // The pixel values 3, 2, 1, 0 in the first row are sampled at every
+        // second pixel.
+        let star = merged.glyphs.get('★').unwrap();
+        assert_eq!(star.bitmap, vec![0xD0, 0x00, 0xC0, 0x00]);
+    }
+
+    #[test]
+    fn bits_per_pixel_mismatch() {
+        let (latin, mut symbols) = fonts();
+        symbols.metadata.bits_per_pixel = 4;
+
+        assert_eq!(
+            latin.merge(&symbols, MergePolicy::default()),
+            Err(MergeError::BitsPerPixelMismatch(1, 4))
+        );
+    }
+}
@@ -1,8 +1,15 @@
 use crate::{
     parser::{Lines, ParserError},
-    BoundingBox, Coord, Properties,
+    properties::PropertyValue,
+    subset::average_width,
+    BoundingBox, Coord, Glyphs, Metrics, Properties, Property,
 };
 
+/// Resolution used for fonts which are imported from formats without a resolution.
+///
+/// At 72 DPI the point size is equal to the pixel size.
+const IMPORT_RESOLUTION: i32 = 72;
+
 /// Metrics set.
 ///
 /// The metrics set specifies for which writing directions the font includes metrics.
@@ -35,6 +42,12 @@ pub struct Metadata {
     /// X and Y resolution in DPI.
     pub resolution: Coord,
 
+    /// Bits per pixel.
+    ///
+    /// Anti-aliased fonts use 2, 4 or 8 bits for each pixel in the glyph
+    /// bitmaps. All other fonts use 1 bit per pixel.
+    pub bits_per_pixel: u32,
+
     /// Font bounding box.
     pub bounding_box: BoundingBox,
 
@@ -51,6 +64,7 @@ impl Metadata {
         let mut font_bounding_box = None;
         let mut point_size = None;
         let mut resolution = Coord::default();
+        let mut bits_per_pixel = 1;
         let mut metrics_set = MetricsSet::default();
         let mut properties = None;
 
@@ -65,12 +79,19 @@ impl Metadata {
                     })?);
                 }
                 "SIZE" => {
-                    let [point, x, y] = line
+                    // The bits per pixel parameter was added in BDF 2.3.
+                    let [point, x, y, bpp] = line
                         .parse_integer_parameters()
+                        .or_else(|| {
+                            line.parse_integer_parameters()
+                                .map(|[point, x, y]| [point, x, y, 1])
+                        })
+                        .filter(|[_, _, _, bpp]| [1, 2, 4, 8].contains(bpp))
                         .ok_or_else(|| ParserError::with_line("invalid \"SIZE\"", &line))?;
                     point_size = Some(point);
                     resolution.x = x;
                     resolution.y = y;
+                    bits_per_pixel = bpp as u32;
                 }
                 "METRICSSET" => {
                     let [index] = line
@@ -116,11 +137,86 @@ impl Metadata {
             name: name.unwrap(),
             point_size: point_size.unwrap(),
             resolution,
+            bits_per_pixel,
             bounding_box: font_bounding_box.unwrap(),
             metrics_set,
             properties: properties.unwrap_or_default(),
         })
     }
+
+    /// Returns `true` if the glyph encodings are Unicode code points.
+    ///
+    /// The encodings are Unicode code points if the `CHARSET_REGISTRY`
+    /// property is `ISO10646`.
+    pub(crate) fn is_unicode(&self) -> bool {
+        self.properties
+            .try_get::<String>(Property::CharsetRegistry)
+            .ok()
+            .flatten()
+            .is_some_and(|registry| registry.eq_ignore_ascii_case("ISO10646"))
+    }
+
+    /// Creates metadata for a font which was imported from a format without
+    /// font metadata, like GNU Unifont `.hex` or PSF files.
+    ///
+    /// The metadata contains a generic XLFD font name and the basic XLFD
+    /// properties, which can be derived from the glyphs. If `unicode` is
+    /// `true` the glyph encodings are Unicode code points and the charset
+    /// properties are set to `ISO10646-1`.
+    pub fn for_imported_font(
+        glyphs: &Glyphs,
+        metrics: &Metrics,
+        pixel_size: u32,
+        unicode: bool,
+    ) -> Self {
+        let pixel_size = pixel_size as i32;
+
+        let bounding_box = glyphs
+            .iter()
+            .fold(BoundingBox::default(), |bounding_box, glyph| {
+                bounding_box.union(&glyph.bounding_box)
+            });
+
+        let average_width = average_width(glyphs);
+
+        let mut properties = Properties::default();
+        for (property, value) in [
+            (Property::PixelSize, pixel_size),
+            (Property::PointSize, pixel_size * 10),
+            (Property::ResolutionX, IMPORT_RESOLUTION),
+            (Property::ResolutionY, IMPORT_RESOLUTION),
+            (Property::AverageWidth, average_width),
+            (Property::FontAscent, metrics.ascent as i32),
+            (Property::FontDescent, metrics.descent as i32),
+        ] {
+            properties.set(property, PropertyValue::Int(value));
+        }
+
+        let (registry, encoding) = if unicode {
+            ("ISO10646", "1")
+        } else {
+            ("FontSpecific", "0")
+        };
+        for (property, value) in [
+            (Property::CharsetRegistry, registry),
+            (Property::CharsetEncoding, encoding),
+        ] {
+            properties.set(property, PropertyValue::Text(value.to_string()));
+        }
+
+        Metadata {
+            name: format!(
+                "-unknown-unknown-medium-r-normal--{pixel_size}-{}-{IMPORT_RESOLUTION}-{IMPORT_RESOLUTION}-c-{average_width}-{registry}-{encoding}",
+                pixel_size * 10,
+            ),
+            point_size: pixel_size,
+            resolution: Coord::new(IMPORT_RESOLUTION, IMPORT_RESOLUTION),
+            bits_per_pixel: 1,
+            bounding_box,
+            metrics_set: MetricsSet::Horizontal,
+            properties,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -206,4 +302,82 @@ mod tests {
         let font = Font::parse(FONT).unwrap();
         assert_eq!(font.metadata.metrics_set, MetricsSet::Both);
     }
+
+    #[test]
+    fn bits_per_pixel() {
+        const FONT: &str = indoc! {r#"
+            STARTFONT 2.1
+            FONT "test font"
+            FONTBOUNDINGBOX 0 1 2 3
+            SIZE 1 2 3 4
+            CHARS 1
+            STARTCHAR 0
+            BITMAP
+            ENDCHAR
+            ENDFONT
+        "#};
+
+        let font = Font::parse(FONT).unwrap();
+        assert_eq!(font.metadata.bits_per_pixel, 4);
+
+        let font = Font::parse(&FONT.replace("SIZE 1 2 3 4", "SIZE 1 2 3")).unwrap();
+        assert_eq!(font.metadata.bits_per_pixel, 1);
+    }
+
+    #[test]
+    fn invalid_bits_per_pixel() {
+        const FONT: &str = indoc! {r#"
+            STARTFONT 2.1
+            FONT "test font"
+            FONTBOUNDINGBOX 0 1 2 3
+            SIZE 1 2 3 3
+            CHARS 0
+            ENDFONT
+        "#};
+
+        assert_parser_error(FONT, "invalid \"SIZE\"", Some(4));
+    }
+
+    #[test]
+    fn imported_font_average_width() {
+        const FONT: &str = indoc! {r#"
+            STARTFONT 2.1
+            FONT test
+            FONTBOUNDINGBOX 6 8 0 -2
+            SIZE 8 72 72
+            CHARS 3
+            STARTCHAR A
+            ENCODING 65
+            DWIDTH 6 0
+            BBX 3 1 0 0
+            BITMAP
+            E0
+            ENDCHAR
+            STARTCHAR B
+            ENCODING 66
+            DWIDTH 5 0
+            BBX 2 1 0 0
+            BITMAP
+            C0
+            ENDCHAR
+            STARTCHAR C
+            ENCODING 67
+            DWIDTH 5 0
+            BBX 1 1 0 0
+            BITMAP
+            80
+            ENDCHAR
+            ENDFONT
+        "#};
+
+        let font = Font::parse(FONT).unwrap();
+        let metadata = Metadata::for_imported_font(&font.glyphs, &font.metrics, 8, true);
+
+        // The average of the device widths is 5.33 pixels.
+        assert_eq!(
+            metadata.properties.try_get::<i32>(Property::AverageWidth),
+            Ok(Some(53))
+        );
+        assert!(metadata.name.contains("-c-53-"), "{}", metadata.name);
+    }
 }
@@ -16,9 +16,13 @@ impl ParserError {
     }
 
     pub(crate) fn with_line(message: &str, line: &Line<'_>) -> Self {
+        Self::with_line_number(message, line.line_number)
+    }
+
+    pub(crate) fn with_line_number(message: &str, line_number: usize) -> Self {
         Self {
             message: message.to_string(),
-            line_number: Some(line.line_number),
+            line_number: Some(line_number),
         }
     }
 }
@@ -0,0 +1,89 @@
+//! X11 Portable Compiled Format (PCF).
+//!
+//! See https://fontforge.org/docs/techref/pcf-format.html for a description of
+//! the file format.
+
+use crate::{BoundingBox, Coord};
+
+mod parse;
+mod write;
+
+pub use write::{ByteOrder, GlyphPadding, PcfError, PcfOptions};
+
+/// PCF file header.
+const PCF_MAGIC: &[u8; 4] = b"\x01fcp";
+
+const PCF_PROPERTIES: u32 = 1 << 0;
+const PCF_ACCELERATORS: u32 = 1 << 1;
+const PCF_METRICS: u32 = 1 << 2;
+const PCF_BITMAPS: u32 = 1 << 3;
+const PCF_BDF_ENCODINGS: u32 = 1 << 5;
+const PCF_SWIDTHS: u32 = 1 << 6;
+const PCF_GLYPH_NAMES: u32 = 1 << 7;
+const PCF_BDF_ACCELERATORS: u32 = 1 << 8;
+
+/// Glyph index used in the encodings table for missing glyphs.
+const NO_GLYPH: u16 = 0xFFFF;
+
+/// Table format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Format(u32);
+
+impl Format {
+    const DEFAULT: u32 = 0x000;
+    const ACCEL_W_INKBOUNDS: u32 = 0x100;
+    const COMPRESSED_METRICS: u32 = 0x100;
+
+    const FORMAT_MASK: u32 = 0xFFFF_FF00;
+    const GLYPH_PAD_MASK: u32 = 3;
+    const BYTE_MASK: u32 = 1 << 2;
+    const BIT_MASK: u32 = 1 << 3;
+    const SCAN_UNIT_SHIFT: u32 = 4;
+
+    fn main_format(self) -> u32 {
+        self.0 & Self::FORMAT_MASK
+    }
+
+    /// Returns `true` if integers and scan units are stored MSB first.
+    fn is_big_endian(self) -> bool {
+        self.0 & Self::BYTE_MASK != 0
+    }
+
+    /// Returns `true` if the first pixel in a byte is stored in the MSB.
+    fn is_msb_first(self) -> bool {
+        self.0 & Self::BIT_MASK != 0
+    }
+
+    /// Returns the number of bytes each bitmap row is padded to.
+    fn glyph_pad(self) -> usize {
+        1 << (self.0 & Self::GLYPH_PAD_MASK)
+    }
+
+    /// Returns the number of bytes in a scan unit.
+    fn scan_unit(self) -> usize {
+        1 << ((self.0 >> Self::SCAN_UNIT_SHIFT) & 3)
+    }
+}
+
+/// Character metrics.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct CharMetrics {
+    left_side_bearing: i16,
+    right_side_bearing: i16,
+    character_width: i16,
+    ascent: i16,
+    descent: i16,
+    attributes: u16,
+}
+
+impl CharMetrics {
+    fn bounding_box(&self) -> BoundingBox {
+        BoundingBox {
+            offset: Coord::new(self.left_side_bearing.into(), -i32::from(self.descent)),
+            size: Coord::new(
+                (i32::from(self.right_side_bearing) - i32::from(self.left_side_bearing)).max(0),
+                (i32::from(self.ascent) + i32::from(self.descent)).max(0),
+            ),
+        }
+    }
+}
@@ -0,0 +1,661 @@
+use std::collections::{BTreeMap, HashMap};
+
+use super::*;
+use crate::{
+    glyph::calculate_swidth, properties::PropertyValue, Encoding, Font, Glyph, GlyphWidth,
+    Metadata, Metrics, MetricsSet, ParserError, Properties, Property,
+};
+
+impl Font {
+    /// Parses a PCF file.
+    ///
+    /// PCF files are often compressed with gzip and need to be decompressed
+    /// before they are passed to this function.
+    ///
+    /// The font name is taken from the `FONT` property and the font metrics
+    /// are taken from the accelerators table. Glyphs which are included in the
+    /// encodings table multiple times are duplicated and glyphs which aren't
+    /// included in the encodings table use [`Encoding::Unspecified`].
+    pub fn parse_pcf(data: &[u8]) -> Result<Self, ParserError> {
+        let tables = Tables::parse(data)?;
+
+        let mut properties = match tables.get(PCF_PROPERTIES)? {
+            Some(mut table) => parse_properties(&mut table)?,
+            None => HashMap::new(),
+        };
+
+        let accelerators = match tables.get(PCF_BDF_ACCELERATORS)? {
+            Some(table) => Some(table),
+            None => tables.get(PCF_ACCELERATORS)?,
+        }
+        .map(|mut table| parse_accelerators(&mut table))
+        .transpose()?
+        .ok_or_else(|| ParserError::new("missing accelerators table"))?;
+
+        let metrics = parse_metrics(
+            &mut tables
+                .get(PCF_METRICS)?
+                .ok_or_else(|| ParserError::new("missing metrics table"))?,
+        )?;
+
+        let bitmaps = parse_bitmaps(
+            &mut tables
+                .get(PCF_BITMAPS)?
+                .ok_or_else(|| ParserError::new("missing bitmaps table"))?,
+            &metrics,
+        )?;
+
+        let encodings = match tables.get(PCF_BDF_ENCODINGS)? {
+            Some(mut table) => parse_encodings(&mut table, metrics.len())?,
+            None => BTreeMap::new(),
+        };
+
+        let swidths = tables
+            .get(PCF_SWIDTHS)?
+            .map(|mut table| parse_swidths(&mut table, metrics.len()))
+            .transpose()?;
+
+        let names = tables
+            .get(PCF_GLYPH_NAMES)?
+            .map(|mut table| parse_glyph_names(&mut table, metrics.len()))
+            .transpose()?;
+
+        let name = match properties.remove(&Property::Font.to_string()) {
+            Some(PropertyValue::Text(name)) => name,
+            _ => return Err(ParserError::new("missing FONT property")),
+        };
+        let properties = Properties::new(properties);
+
+        let resolution = Coord::new(
+            properties
+                .try_get::<i32>(Property::ResolutionX)
+                .ok()
+                .flatten()
+                .unwrap_or(75),
+            properties
+                .try_get::<i32>(Property::ResolutionY)
+                .ok()
+                .flatten()
+                .unwrap_or(75),
+        );
+
+        let font_metrics = Metrics {
+            ascent: accelerators.font_ascent.max(0) as u32,
+            descent: accelerators.font_descent.max(0) as u32,
+        };
+
+        let point_size = match properties.try_get::<i32>(Property::PointSize) {
+            Ok(Some(point_size)) => (point_size + 5) / 10,
+            _ => font_metrics.line_height() as i32,
+        };
+
+        let metadata = Metadata {
+            name,
+            point_size,
+            resolution,
+            bits_per_pixel: 1,
+            bounding_box: accelerators.bounding_box(),
+            metrics_set: MetricsSet::Horizontal,
+            properties,
+        };
+
+        let mut glyphs = Vec::with_capacity(metrics.len());
+        for (index, (char_metrics, bitmap)) in metrics.iter().zip(bitmaps).enumerate() {
+            let device = Coord::new(char_metrics.character_width.into(), 0);
+            let scalable = swidths
+                .as_ref()
+                .map(|swidths| Coord::new(swidths[index], 0))
+                .unwrap_or_else(|| calculate_swidth(device, &metadata));
+
+            let glyph = Glyph {
+                name: String::new(),
+                encoding: Encoding::Unspecified,
+                width_horizontal: Some(GlyphWidth { scalable, device }),
+                width_vertical: None,
+                bounding_box: char_metrics.bounding_box(),
+                origin_offset: None,
+                bitmap,
+            };
+
+            let glyph_name = |encoding: Option<u32>| match (&names, encoding) {
+                (Some(names), _) => names[index].clone(),
+                (None, Some(encoding)) => format!("char{encoding}"),
+                (None, None) => format!("glyph{index}"),
+            };
+
+            match encodings.get(&index) {
+                Some(encodings) => {
+                    for encoding in encodings {
+                        glyphs.push(Glyph {
+                            name: glyph_name(Some(*encoding)),
+                            encoding: Encoding::Standard(*encoding),
+                            ..glyph.clone()
+                        });
+                    }
+                }
+                None => glyphs.push(Glyph {
+                    name: glyph_name(None),
+                    ..glyph
+                }),
+            }
+        }
+
+        Ok(Font {
+            metadata,
+            glyphs: glyphs.into_iter().collect(),
+            metrics: font_metrics,
+        })
+    }
+}
+
+fn unexpected_end() -> ParserError {
+    ParserError::new("unexpected end of file")
+}
+
+/// Reader for binary table data.
+struct Reader<'a> {
+    data: &'a [u8],
+    position: usize,
+    format: Format,
+}
+
+impl<'a> Reader<'a> {
+    /// Creates a reader for a table and reads the table format.
+    fn new(data: &'a [u8]) -> Result<Self, ParserError> {
+        let mut reader = Self {
+            data,
+            position: 0,
+            format: Format(Format::DEFAULT),
+        };
+        reader.format = Format(u32::from_le_bytes(reader.array()?));
+
+        Ok(reader)
+    }
+
+    fn bytes(&mut self, length: usize) -> Result<&'a [u8], ParserError> {
+        let bytes = self
+            .position
+            .checked_add(length)
+            .and_then(|end| self.data.get(self.position..end))
+            .ok_or_else(unexpected_end)?;
+        self.position += length;
+
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], ParserError> {
+        Ok(self.bytes(N)?.try_into().unwrap())
+    }
+
+    fn skip(&mut self, length: usize) -> Result<(), ParserError> {
+        self.bytes(length).map(|_| ())
+    }
+
+    fn u8(&mut self) -> Result<u8, ParserError> {
+        self.array::<1>().map(|[byte]| byte)
+    }
+
+    fn u16(&mut self) -> Result<u16, ParserError> {
+        let bytes = self.array()?;
+        Ok(if self.format.is_big_endian() {
+            u16::from_be_bytes(bytes)
+        } else {
+            u16::from_le_bytes(bytes)
+        })
+    }
+
+    fn i16(&mut self) -> Result<i16, ParserError> {
+        self.u16().map(|value| value as i16)
+    }
+
+    fn i32(&mut self) -> Result<i32, ParserError> {
+        let bytes = self.array()?;
+        Ok(if self.format.is_big_endian() {
+            i32::from_be_bytes(bytes)
+        } else {
+            i32::from_le_bytes(bytes)
+        })
+    }
+
+    /// Reads a count or size, which must not be negative.
+    fn count(&mut self) -> Result<usize, ParserError> {
+        usize::try_from(self.i32()?).map_err(|_| ParserError::new("negative count in table"))
+    }
+
+    fn metrics(&mut self) -> Result<CharMetrics, ParserError> {
+        Ok(CharMetrics {
+            left_side_bearing: self.i16()?,
+            right_side_bearing: self.i16()?,
+            character_width: self.i16()?,
+            ascent: self.i16()?,
+            descent: self.i16()?,
+            attributes: self.u16()?,
+        })
+    }
+
+    fn compressed_metrics(&mut self) -> Result<CharMetrics, ParserError> {
+        let mut value = || self.u8().map(|value| i16::from(value) - 0x80);
+
+        Ok(CharMetrics {
+            left_side_bearing: value()?,
+            right_side_bearing: value()?,
+            character_width: value()?,
+            ascent: value()?,
+            descent: value()?,
+            attributes: 0,
+        })
+    }
+}
+
+/// Table of contents.
+struct Tables<'a> {
+    data: &'a [u8],
+    entries: Vec<TableEntry>,
+}
+
+struct TableEntry {
+    table_type: u32,
+    size: usize,
+    offset: usize,
+}
+
+impl<'a> Tables<'a> {
+    fn parse(data: &'a [u8]) -> Result<Self, ParserError> {
+        if !data.starts_with(PCF_MAGIC) {
+            return Err(ParserError::new("invalid PCF header"));
+        }
+
+        // The table of contents is always stored LSB first.
+        let mut reader = Reader {
+            data,
+            position: PCF_MAGIC.len(),
+            format: Format(Format::DEFAULT),
+        };
+
+        let count = reader.count()?;
+        let mut entries = Vec::new();
+        for _ in 0..count {
+            let table_type = reader.i32()? as u32;
+            let _format = reader.i32()?;
+            let size = reader.count()?;
+            let offset = reader.count()?;
+
+            entries.push(TableEntry {
+                table_type,
+                size,
+                offset,
+            });
+        }
+
+        Ok(Self { data, entries })
+    }
+
+    /// Returns a reader for the table with the given type.
+    fn get(&self, table_type: u32) -> Result<Option<Reader<'a>>, ParserError> {
+        let Some(entry) = self
+            .entries
+            .iter()
+            .find(|entry| entry.table_type == table_type)
+        else {
+            return Ok(None);
+        };
+
+        let data = entry
+            .offset
+            .checked_add(entry.size)
+            .and_then(|end| self.data.get(entry.offset..end))
+            .ok_or_else(|| ParserError::new("table extends past the end of file"))?;
+
+        Reader::new(data).map(Some)
+    }
+}
+
+fn parse_properties(table: &mut Reader<'_>) -> Result<HashMap<String, PropertyValue>, ParserError> {
+    let count = table.count()?;
+
+    let mut entries = Vec::new();
+    for _ in 0..count {
+        let name_offset = table.count()?;
+        let is_string = table.u8()? != 0;
+        let value = table.i32()?;
+
+        entries.push((name_offset, is_string, value));
+    }
+
+    // The property entries are padded to a multiple of 4 bytes.
+    if count % 4 != 0 {
+        table.skip(4 - count % 4)?;
+    }
+
+    let strings_size = table.count()?;
+    let strings = table.bytes(strings_size)?;
+
+    let string = |offset: usize| {
+        strings
+            .get(offset..)
+            .map(|bytes| {
+                let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+                String::from_utf8_lossy(&bytes[..end]).into_owned()
+            })
+            .ok_or_else(|| ParserError::new("invalid string offset in properties table"))
+    };
+
+    entries
+        .into_iter()
+        .map(|(name_offset, is_string, value)| {
+            let value = if is_string {
+                let offset = usize::try_from(value)
+                    .map_err(|_| ParserError::new("invalid string offset in properties table"))?;
+                PropertyValue::Text(string(offset)?)
+            } else {
+                PropertyValue::Int(value)
+            };
+
+            Ok((string(name_offset)?, value))
+        })
+        .collect()
+}
+
+/// Accelerators table.
+struct Accelerators {
+    font_ascent: i32,
+    font_descent: i32,
+    min_bounds: CharMetrics,
+    max_bounds: CharMetrics,
+}
+
+impl Accelerators {
+    fn bounding_box(&self) -> BoundingBox {
+        CharMetrics {
+            left_side_bearing: self.min_bounds.left_side_bearing,
+            right_side_bearing: self.max_bounds.right_side_bearing,
+            ascent: self.max_bounds.ascent,
+            descent: self.max_bounds.descent,
+            ..CharMetrics::default()
+        }
+        .bounding_box()
+    }
+}
+
+fn parse_accelerators(table: &mut Reader<'_>) -> Result<Accelerators, ParserError> {
+    // Skip the flags (noOverlap, constantMetrics, terminalFont, constantWidth,
+    // inkInside, inkMetrics, drawDirection and padding).
+    table.skip(8)?;
+
+    let font_ascent = table.i32()?;
+    let font_descent = table.i32()?;
+    let _max_overlap = table.i32()?;
+    let min_bounds = table.metrics()?;
+    let max_bounds = table.metrics()?;
+
+    Ok(Accelerators {
+        font_ascent,
+        font_descent,
+        min_bounds,
+        max_bounds,
+    })
+}
+
+fn parse_metrics(table: &mut Reader<'_>) -> Result<Vec<CharMetrics>, ParserError> {
+    if table.format.main_format() == Format::COMPRESSED_METRICS {
+        let count = usize::try_from(table.i16()?)
+            .map_err(|_| ParserError::new("negative count in table"))?;
+
+        (0..count).map(|_| table.compressed_metrics()).collect()
+    } else {
+        let count = table.count()?;
+
+        (0..count).map(|_| table.metrics()).collect()
+    }
+}
+
+fn parse_bitmaps(
+    table: &mut Reader<'_>,
+    metrics: &[CharMetrics],
+) -> Result<Vec<Vec<u8>>, ParserError> {
+    let format = table.format;
+
+    let count = table.count()?;
+    if count != metrics.len() {
+        return Err(ParserError::new(
+            "number of bitmaps doesn't match number of glyphs",
+        ));
+    }
+
+    let offsets = (0..count)
+        .map(|_| table.count())
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut sizes = [0; 4];
+    for size in &mut sizes {
+        *size = table.count()?;
+    }
+    let data = table.bytes(sizes[format.0 as usize & 3])?;
+
+    let swap_bytes = format.is_big_endian() != format.is_msb_first();
+    if swap_bytes && format.scan_unit() > format.glyph_pad() {
+        return Err(ParserError::new("scan unit is larger than glyph padding"));
+    }
+
+    metrics
+        .iter()
+        .zip(offsets)
+        .map(|(metrics, offset)| {
+            let size = metrics.bounding_box().size;
+            let width = size.x as usize;
+            let height = size.y as usize;
+
+            let bytes_per_row = width.div_ceil(8);
+            let stride = bytes_per_row.next_multiple_of(format.glyph_pad());
+
+            let glyph_data = offset
+                .checked_add(stride * height)
+                .and_then(|end| data.get(offset..end))
+                .ok_or_else(|| ParserError::new("invalid bitmap offset"))?;
+
+            let unit = format.scan_unit();
+            let mut bitmap = Vec::with_capacity(bytes_per_row * height);
+            for row in glyph_data.chunks_exact(stride) {
+                for x in 0..bytes_per_row {
+                    let index = if swap_bytes {
+                        x - x % unit + unit - 1 - x % unit
+                    } else {
+                        x
+                    };
+
+                    let byte = row[index];
+                    bitmap.push(if format.is_msb_first() {
+                        byte
+                    } else {
+                        byte.reverse_bits()
+                    });
+                }
+            }
+
+            Ok(bitmap)
+        })
+        .collect()
+}
+
+/// Parses the encodings table.
+///
+/// Returns a map from glyph index to all encodings which use the glyph.
+fn parse_encodings(
+    table: &mut Reader<'_>,
+    glyph_count: usize,
+) -> Result<BTreeMap<usize, Vec<u32>>, ParserError> {
+    let min_byte2 = table.i16()?;
+    let max_byte2 = table.i16()?;
+    let min_byte1 = table.i16()?;
+    let max_byte1 = table.i16()?;
+    let _default_char = table.i16()?;
+
+    let byte_range = |min: i16, max: i16| {
+        u8::try_from(min)
+            .ok()
+            .zip(u8::try_from(max).ok())
+            .filter(|(min, max)| min <= max)
+            .map(|(min, max)| min..=max)
+            .ok_or_else(|| ParserError::new("invalid range in encodings table"))
+    };
+    let byte1_range = byte_range(min_byte1, max_byte1)?;
+    let byte2_range = byte_range(min_byte2, max_byte2)?;
+
+    let mut encodings = BTreeMap::<_, Vec<_>>::new();
+    for byte1 in byte1_range {
+        for byte2 in byte2_range.clone() {
+            let index = table.u16()?;
+            if index == NO_GLYPH {
+                continue;
+            }
+
+            let index = usize::from(index);
+            if index >= glyph_count {
+                return Err(ParserError::new("invalid glyph index in encodings table"));
+            }
+
+            encodings
+                .entry(index)
+                .or_default()
+                .push(u32::from(byte1) << 8 | u32::from(byte2));
+        }
+    }
+
+    Ok(encodings)
+}
+
+fn parse_swidths(table: &mut Reader<'_>, glyph_count: usize) -> Result<Vec<i32>, ParserError> {
+    let count = table.count()?;
+    if count != glyph_count {
+        return Err(ParserError::new(
+            "number of scalable widths doesn't match number of glyphs",
+        ));
+    }
+
+    (0..count).map(|_| table.i32()).collect()
+}
+
+fn parse_glyph_names(
+    table: &mut Reader<'_>,
+    glyph_count: usize,
+) -> Result<Vec<String>, ParserError> {
+    let count = table.count()?;
+    if count != glyph_count {
+        return Err(ParserError::new(
+            "number of glyph names doesn't match number of glyphs",
+        ));
+    }
+
+    let offsets = (0..count)
+        .map(|_| table.count())
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let strings_size = table.count()?;
+    let strings = table.bytes(strings_size)?;
+
+    offsets
+        .into_iter()
+        .map(|offset| {
+            let bytes = strings
+                .get(offset..)
+                .ok_or_else(|| ParserError::new("invalid glyph name offset"))?;
+            let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+
+            Ok(String::from_utf8_lossy(&bytes[..end]).into_owned())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bdf(file: &str) -> Font {
+        let bdf = std::fs::read(format!("../eg-bdf-examples/examples/{file}")).unwrap();
+        Font::parse(&String::from_utf8_lossy(&bdf)).unwrap()
+    }
+
+    fn pcf(file: &str) -> Font {
+        let pcf = std::fs::read(format!("tests/pcf/{file}")).unwrap();
+        Font::parse_pcf(&pcf).unwrap()
+    }
+
+    /// Asserts that a PCF fixture is equivalent to the BDF file it was
+    /// generated from.
+    ///
+    /// The fixtures only contain a subset of the glyphs in the BDF files, to
+    /// keep the file size small. All fixtures were written by
+    /// `tests/pcf/generate.py` and not by `bdftopcf`.
+    #[track_caller]
+    fn assert_equivalent(pcf_file: &str, bdf_file: &str, chars: impl IntoIterator<Item = char>) {
+        let pcf = pcf(pcf_file);
+        let bdf = bdf(bdf_file).subset(chars);
+
+        assert_eq!(pcf.metadata.name, bdf.metadata.name);
+        assert_eq!(pcf.metadata.point_size, bdf.metadata.point_size);
+        assert_eq!(pcf.metadata.resolution, bdf.metadata.resolution);
+        assert_eq!(pcf.metrics, bdf.metrics);
+        assert_eq!(pcf.glyphs, bdf.glyphs);
+
+        let diff = bdf.diff(&pcf);
+        assert!(diff.is_empty(), "{diff:?}");
+    }
+
+    fn chars_6x10() -> impl Iterator<Item = char> {
+        std::iter::once('\0')
+            .chain(' '..='~')
+            .chain('\u{100}'..='\u{17F}')
+    }
+
+    #[test]
+    fn msb_pad4_compressed_metrics() {
+        // Written by generate.py, same options as `bdftopcf -M -m -p4 -u1`.
+        assert_equivalent("6x10.pcf", "6x10.bdf", chars_6x10());
+    }
+
+    #[test]
+    fn lsb_pad1_uncompressed_metrics() {
+        // Written by generate.py, `bdftopcf` can't write uncompressed metrics.
+        assert_equivalent("6x10_lsb_pad1.pcf", "6x10.bdf", chars_6x10());
+    }
+
+    #[test]
+    fn lsb_byte_msb_bit_pad2_unit2() {
+        // Written by generate.py, same options as `bdftopcf -L -m -p2 -u2`.
+        assert_equivalent("6x10_pad2_unit2.pcf", "6x10.bdf", chars_6x10());
+    }
+
+    #[test]
+    fn msb_byte_lsb_bit_pad8_unit4() {
+        // Written by generate.py, same options as `bdftopcf -M -l -p8 -u4`.
+        assert_equivalent("10x20_pad8_unit4.pcf", "10x20.bdf", ' '..='~');
+    }
+
+    #[test]
+    fn lsb_pad4_unit4_uncompressed_metrics() {
+        // Written by generate.py, `bdftopcf` can't write uncompressed metrics.
+        assert_equivalent("10x20_lsb_pad4_unit4.pcf", "10x20.bdf", ' '..='~');
+    }
+
+    #[test]
+    fn invalid_header() {
+        assert_eq!(
+            Font::parse_pcf(b"STARTFONT 2.1\n"),
+            Err(ParserError::new("invalid PCF header"))
+        );
+    }
+
+    #[test]
+    fn truncated_file() {
+        let pcf = std::fs::read("tests/pcf/6x10.pcf").unwrap();
+
+        assert_eq!(
+            Font::parse_pcf(&pcf[0..pcf.len() / 2]),
+            Err(ParserError::new("table extends past the end of file"))
+        );
+        assert_eq!(
+            Font::parse_pcf(&pcf[0..10]),
+            Err(ParserError::new("unexpected end of file"))
+        );
+    }
+}
@@ -0,0 +1,684 @@
+use std::collections::BTreeMap;
+
+use thiserror::Error;
+
+use super::*;
+use crate::{properties::PropertyValue, Encoding, Font, Glyph, Property};
+
+/// Byte order used in PCF files.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ByteOrder {
+    /// Most significant byte first.
+    ///
+    /// Bitmaps are also stored with the most significant bit first.
+    #[default]
+    MsbFirst,
+
+    /// Least significant byte first.
+    ///
+    /// Bitmaps are also stored with the least significant bit first.
+    LsbFirst,
+}
+
+/// Glyph bitmap row padding used in PCF files.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum GlyphPadding {
+    /// Rows are padded to whole bytes.
+    Byte,
+
+    /// Rows are padded to multiples of 2 bytes.
+    Short,
+
+    /// Rows are padded to multiples of 4 bytes.
+    #[default]
+    Int,
+
+    /// Rows are padded to multiples of 8 bytes.
+    Long,
+}
+
+/// PCF writer options.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PcfOptions {
+    /// Byte order.
+    pub byte_order: ByteOrder,
+
+    /// Glyph padding.
+    pub glyph_padding: GlyphPadding,
+}
+
+/// PCF export error.
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum PcfError {
+    /// The font contains more glyphs than can be stored in a PCF file.
+    #[error("the font contains more than 65534 glyphs")]
+    TooManyGlyphs,
+
+    /// The glyph metrics don't fit into the 16 bit values used in PCF files.
+    #[error("metrics of glyph \"{0}\" are out of range")]
+    MetricsOutOfRange(String),
+
+    /// The encoding of a glyph is larger than 0xFFFF.
+    #[error("encoding of glyph \"{0}\" is out of range")]
+    EncodingOutOfRange(String),
+
+    /// The font uses more than one bit per pixel.
+    #[error("unsupported bits per pixel value {0}, PCF fonts must be monochrome")]
+    UnsupportedBitsPerPixel(u32),
+}
+
+impl Font {
+    /// Converts the font into the X11 Portable Compiled Format (PCF).
+    ///
+    /// The PCF file includes the properties, accelerators, metrics, bitmaps,
+    /// BDF encodings, scalable widths and glyph names tables. The font name is
+    /// stored in the `FONT` property and the `POINT_SIZE`, `RESOLUTION_X` and
+    /// `RESOLUTION_Y` properties are added if they are missing.
+    ///
+    /// Glyphs with a non standard or unspecified encoding are included in the
+    /// file, but can't be accessed by their encoding. Grayscale fonts with more
+    /// than 1 bit per pixel can't be stored in a PCF file.
+    pub fn to_pcf(&self, options: PcfOptions) -> Result<Vec<u8>, PcfError> {
+        if self.metadata.bits_per_pixel != 1 {
+            return Err(PcfError::UnsupportedBitsPerPixel(
+                self.metadata.bits_per_pixel,
+            ));
+        }
+
+        if self.glyphs.len() >= usize::from(NO_GLYPH) {
+            return Err(PcfError::TooManyGlyphs);
+        }
+
+        let format = options.format();
+
+        let metrics = self
+            .glyphs
+            .iter()
+            .map(CharMetrics::from_glyph)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // The glyph count of compressed metrics tables is stored in an INT16.
+        let metrics_format = if metrics.len() <= i16::MAX as usize
+            && metrics.iter().all(CharMetrics::is_compressible)
+        {
+            Format(format.0 | Format::COMPRESSED_METRICS)
+        } else {
+            format
+        };
+
+        let tables = [
+            (PCF_PROPERTIES, self.properties_table(format)),
+            (PCF_ACCELERATORS, self.accelerators_table(format, &metrics)),
+            (PCF_METRICS, metrics_table(metrics_format, &metrics)),
+            (PCF_BITMAPS, self.bitmaps_table(format)),
+            (PCF_BDF_ENCODINGS, self.encodings_table(format)?),
+            (PCF_SWIDTHS, self.swidths_table(format)),
+            (PCF_GLYPH_NAMES, self.glyph_names_table(format)),
+            (
+                PCF_BDF_ACCELERATORS,
+                self.accelerators_table(Format(format.0 | Format::ACCEL_W_INKBOUNDS), &metrics),
+            ),
+        ];
+
+        let mut data = PCF_MAGIC.to_vec();
+        data.extend_from_slice(&(tables.len() as u32).to_le_bytes());
+
+        let mut offset = data.len() + tables.len() * 16;
+        for (table_type, table) in &tables {
+            for value in [
+                table_type,
+                &table.format.0,
+                &(table.data.len() as u32),
+                &(offset as u32),
+            ] {
+                data.extend_from_slice(&value.to_le_bytes());
+            }
+            offset += table.data.len();
+        }
+
+        for (_, table) in tables {
+            data.extend(table.data);
+        }
+
+        Ok(data)
+    }
+
+    fn properties_table(&self, format: Format) -> Writer {
+        let mut properties = self
+            .metadata
+            .properties
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.clone()))
+            .collect::<BTreeMap<_, _>>();
+
+        properties.insert(
+            Property::Font.to_string(),
+            PropertyValue::Text(self.metadata.name.clone()),
+        );
+        for (property, value) in [
+            (Property::PointSize, self.metadata.point_size * 10),
+            (Property::ResolutionX, self.metadata.resolution.x),
+            (Property::ResolutionY, self.metadata.resolution.y),
+        ] {
+            properties
+                .entry(property.to_string())
+                .or_insert(PropertyValue::Int(value));
+        }
+
+        let mut strings = Vec::new();
+        let mut add_string = |string: &str| {
+            let offset = strings.len() as i32;
+            strings.extend_from_slice(string.as_bytes());
+            strings.push(0);
+            offset
+        };
+
+        let mut table = Writer::new(format);
+        table.i32(properties.len() as i32);
+        for (name, value) in &properties {
+            table.i32(add_string(name));
+            match value {
+                PropertyValue::Text(text) => {
+                    table.u8(1);
+                    table.i32(add_string(text));
+                }
+                PropertyValue::Int(value) => {
+                    table.u8(0);
+                    table.i32(*value);
+                }
+            }
+        }
+        table.pad();
+        table.i32(strings.len() as i32);
+        table.bytes(&strings);
+        table.pad();
+
+        table
+    }
+
+    fn accelerators_table(&self, format: Format, metrics: &[CharMetrics]) -> Writer {
+        let font_ascent = self.metrics.ascent as i32;
+        let font_descent = self.metrics.descent as i32;
+
+        let min_bounds = bounds(metrics, Ord::min);
+        let max_bounds = bounds(metrics, Ord::max);
+        let max_overlap = metrics
+            .iter()
+            .map(|m| i32::from(m.right_side_bearing) - i32::from(m.character_width))
+            .max()
+            .unwrap_or(0);
+
+        let constant_metrics = min_bounds == max_bounds;
+        let ink_inside = min_bounds.left_side_bearing >= 0
+            && max_overlap <= 0
+            && i32::from(min_bounds.ascent) >= -font_descent
+            && i32::from(max_bounds.ascent) <= font_ascent
+            && -i32::from(min_bounds.descent) <= font_ascent
+            && i32::from(max_bounds.descent) <= font_descent;
+        let terminal_font = ink_inside
+            && constant_metrics
+            && min_bounds.left_side_bearing == 0
+            && min_bounds.right_side_bearing == min_bounds.character_width
+            && i32::from(min_bounds.ascent) == font_ascent
+            && i32::from(min_bounds.descent) == font_descent;
+
+        let mut table = Writer::new(format);
+        for flag in [
+            max_overlap <= i32::from(min_bounds.left_side_bearing),
+            constant_metrics,
+            terminal_font,
+            min_bounds.character_width == max_bounds.character_width,
+            ink_inside,
+            false, // ink metrics
+            false, // draw direction: left to right
+            false, // padding
+        ] {
+            table.u8(flag.into());
+        }
+        table.i32(font_ascent);
+        table.i32(font_descent);
+        table.i32(max_overlap);
+        table.metrics(&min_bounds);
+        table.metrics(&max_bounds);
+
+        // The ink bounds are identical to the bounds, because no ink metrics
+        // table is written.
+        if format.main_format() == Format::ACCEL_W_INKBOUNDS {
+            table.metrics(&min_bounds);
+            table.metrics(&max_bounds);
+        }
+        table.pad();
+
+        table
+    }
+
+    fn bitmaps_table(&self, format: Format) -> Writer {
+        let glyph_pad = format.glyph_pad();
+
+        let mut bitmaps = Vec::new();
+        let mut offsets = Vec::new();
+        let mut sizes = [0; 4];
+        for glyph in self.glyphs.iter() {
+            let width = glyph.bounding_box.size.x.max(0) as usize;
+            let height = glyph.bounding_box.size.y.max(0) as usize;
+            let bytes_per_row = width.div_ceil(8);
+
+            offsets.push(bitmaps.len() as i32);
+            for (index, size) in sizes.iter_mut().enumerate() {
+                *size += (bytes_per_row.next_multiple_of(1 << index) * height) as i32;
+            }
+
+            for y in 0..height {
+                for x in 0..bytes_per_row.next_multiple_of(glyph_pad) {
+                    let byte = if x < bytes_per_row {
+                        glyph
+                            .bitmap
+                            .get(y * bytes_per_row + x)
+                            .copied()
+                            .unwrap_or(0)
+                    } else {
+                        0
+                    };
+
+                    bitmaps.push(if format.is_msb_first() {
+                        byte
+                    } else {
+                        byte.reverse_bits()
+                    });
+                }
+            }
+        }
+
+        let mut table = Writer::new(format);
+        table.i32(offsets.len() as i32);
+        for offset in offsets {
+            table.i32(offset);
+        }
+        for size in sizes {
+            table.i32(size);
+        }
+        table.bytes(&bitmaps);
+        table.pad();
+
+        table
+    }
+
+    fn encodings_table(&self, format: Format) -> Result<Writer, PcfError> {
+        let mut encodings = BTreeMap::new();
+        for (index, glyph) in self.glyphs.iter().enumerate() {
+            if let Encoding::Standard(encoding) = glyph.encoding {
+                let encoding = u16::try_from(encoding)
+                    .map_err(|_| PcfError::EncodingOutOfRange(glyph.name.clone()))?;
+
+                encodings.entry(encoding).or_insert(index as u16);
+            }
+        }
+
+        let byte_range = |byte: fn(&u16) -> u8| {
+            let min = encodings.keys().map(byte).min().unwrap_or(0);
+            let max = encodings.keys().map(byte).max().unwrap_or(0);
+            min..=max
+        };
+        let byte1_range = byte_range(|encoding| (encoding >> 8) as u8);
+        let byte2_range = byte_range(|encoding| *encoding as u8);
+
+        let default_char = self
+            .metadata
+            .properties
+            .try_get::<u32>(Property::DefaultChar)
+            .ok()
+            .flatten()
+            .and_then(|default_char| u16::try_from(default_char).ok())
+            .unwrap_or(NO_GLYPH);
+
+        let mut table = Writer::new(format);
+        for value in [
+            *byte2_range.start(),
+            *byte2_range.end(),
+            *byte1_range.start(),
+            *byte1_range.end(),
+        ] {
+            table.u16(value.into());
+        }
+        table.u16(default_char);
+        for byte1 in byte1_range {
+            for byte2 in byte2_range.clone() {
+                let encoding = u16::from(byte1) << 8 | u16::from(byte2);
+                table.u16(encodings.get(&encoding).copied().unwrap_or(NO_GLYPH));
+            }
+        }
+        table.pad();
+
+        Ok(table)
+    }
+
+    fn swidths_table(&self, format: Format) -> Writer {
+        let mut table = Writer::new(format);
+        table.i32(self.glyphs.len() as i32);
+        for glyph in self.glyphs.iter() {
+            table.i32(glyph.width_horizontal.map_or(0, |width| width.scalable.x));
+        }
+
+        table
+    }
+
+    fn glyph_names_table(&self, format: Format) -> Writer {
+        let mut strings = Vec::new();
+
+        let mut table = Writer::new(format);
+        table.i32(self.glyphs.len() as i32);
+        for glyph in self.glyphs.iter() {
+            table.i32(strings.len() as i32);
+            strings.extend_from_slice(glyph.name.as_bytes());
+            strings.push(0);
+        }
+        table.i32(strings.len() as i32);
+        table.bytes(&strings);
+        table.pad();
+
+        table
+    }
+}
+
+impl PcfOptions {
+    fn format(&self) -> Format {
+        let byte_order = match self.byte_order {
+            ByteOrder::MsbFirst => Format::BYTE_MASK | Format::BIT_MASK,
+            ByteOrder::LsbFirst => 0,
+        };
+        let glyph_pad = match self.glyph_padding {
+            GlyphPadding::Byte => 0,
+            GlyphPadding::Short => 1,
+            GlyphPadding::Int => 2,
+            GlyphPadding::Long => 3,
+        };
+
+        Format(Format::DEFAULT | byte_order | glyph_pad)
+    }
+}
+
+impl CharMetrics {
+    fn from_glyph(glyph: &Glyph) -> Result<Self, PcfError> {
+        let BoundingBox { offset, size } = glyph.bounding_box;
+        let width = glyph.width_horizontal.map_or(0, |width| width.device.x);
+
+        let value = |value: i32| {
+            i16::try_from(value).map_err(|_| PcfError::MetricsOutOfRange(glyph.name.clone()))
+        };
+
+        Ok(Self {
+            left_side_bearing: value(offset.x)?,
+            right_side_bearing: value(offset.x + size.x)?,
+            character_width: value(width)?,
+            ascent: value(offset.y + size.y)?,
+            descent: value(-offset.y)?,
+            attributes: 0,
+        })
+    }
+
+    /// Returns `true` if the metrics can be stored in the compressed format.
+    fn is_compressible(&self) -> bool {
+        [
+            self.left_side_bearing,
+            self.right_side_bearing,
+            self.character_width,
+            self.ascent,
+            self.descent,
+        ]
+        .iter()
+        .all(|value| i8::try_from(*value).is_ok())
+    }
+}
+
+/// Calculates the minimum or maximum of all metrics fields.
+fn bounds(metrics: &[CharMetrics], f: fn(i16, i16) -> i16) -> CharMetrics {
+    let Some((first, rest)) = metrics.split_first() else {
+        return CharMetrics::default();
+    };
+
+    rest.iter().fold(*first, |a, b| CharMetrics {
+        left_side_bearing: f(a.left_side_bearing, b.left_side_bearing),
+        right_side_bearing: f(a.right_side_bearing, b.right_side_bearing),
+        character_width: f(a.character_width, b.character_width),
+        ascent: f(a.ascent, b.ascent),
+        descent: f(a.descent, b.descent),
+        attributes: 0,
+    })
+}
+
+fn metrics_table(format: Format, metrics: &[CharMetrics]) -> Writer {
+    let mut table = Writer::new(format);
+
+    if format.main_format() == Format::COMPRESSED_METRICS {
+        table.u16(metrics.len() as u16);
+        for metrics in metrics {
+            for value in [
+                metrics.left_side_bearing,
+                metrics.right_side_bearing,
+                metrics.character_width,
+                metrics.ascent,
+                metrics.descent,
+            ] {
+                table.u8((value + 0x80) as u8);
+            }
+        }
+    } else {
+        table.i32(metrics.len() as i32);
+        for metrics in metrics {
+            table.metrics(metrics);
+        }
+    }
+    table.pad();
+
+    table
+}
+
+/// Writer for binary table data.
+struct Writer {
+    data: Vec<u8>,
+    format: Format,
+}
+
+impl Writer {
+    /// Creates a writer for a table and writes the table format.
+    fn new(format: Format) -> Self {
+        Self {
+            data: format.0.to_le_bytes().to_vec(),
+            format,
+        }
+    }
+
+    fn bytes(&mut self, bytes: &[u8]) {
+        self.data.extend_from_slice(bytes);
+    }
+
+    fn u8(&mut self, value: u8) {
+        self.data.push(value);
+    }
+
+    fn u16(&mut self, value: u16) {
+        if self.format.is_big_endian() {
+            self.bytes(&value.to_be_bytes());
+        } else {
+            self.bytes(&value.to_le_bytes());
+        }
+    }
+
+    fn i16(&mut self, value: i16) {
+        self.u16(value as u16);
+    }
+
+    fn i32(&mut self, value: i32) {
+        if self.format.is_big_endian() {
+            self.bytes(&value.to_be_bytes());
+        } else {
+            self.bytes(&value.to_le_bytes());
+        }
+    }
+
+    fn metrics(&mut self, metrics: &CharMetrics) {
+        self.i16(metrics.left_side_bearing);
+        self.i16(metrics.right_side_bearing);
+        self.i16(metrics.character_width);
+        self.i16(metrics.ascent);
+        self.i16(metrics.descent);
+        self.u16(metrics.attributes);
+    }
+
+    /// Pads the table to a multiple of 4 bytes.
+    fn pad(&mut self) {
+        self.data.resize(self.data.len().next_multiple_of(4), 0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BoundingBox, Coord};
+
+    fn font() -> Font {
+        let bdf = std::fs::read("../eg-bdf-examples/examples/6x10.bdf").unwrap();
+        Font::parse(&String::from_utf8_lossy(&bdf)).unwrap()
+    }
+
+    #[track_caller]
+    fn assert_round_trip(font: &Font, options: PcfOptions) {
+        let pcf = Font::parse_pcf(&font.to_pcf(options).unwrap()).unwrap();
+
+        assert_eq!(pcf.metadata.name, font.metadata.name);
+        assert_eq!(pcf.metadata.point_size, font.metadata.point_size);
+        assert_eq!(pcf.metadata.resolution, font.metadata.resolution);
+        assert_eq!(pcf.metadata.bounding_box, font.metadata.bounding_box);
+        assert_eq!(pcf.metrics, font.metrics);
+        assert_eq!(pcf.glyphs, font.glyphs);
+
+        let diff = font.diff(&pcf);
+        assert!(diff.is_empty(), "{diff:?}");
+    }
+
+    #[test]
+    fn round_trip() {
+        let font = font();
+
+        for byte_order in [ByteOrder::MsbFirst, ByteOrder::LsbFirst] {
+            for glyph_padding in [
+                GlyphPadding::Byte,
+                GlyphPadding::Short,
+                GlyphPadding::Int,
+                GlyphPadding::Long,
+            ] {
+                assert_round_trip(
+                    &font,
+                    PcfOptions {
+                        byte_order,
+                        glyph_padding,
+                    },
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn read_fixture() {
+        // The fixture was written by `tests/pcf/generate.py`. Writing the parsed
+        // fixture and parsing the output again must result in the same font.
+        let fixture = Font::parse_pcf(&std::fs::read("tests/pcf/6x10.pcf").unwrap()).unwrap();
+        let pcf = Font::parse_pcf(&fixture.to_pcf(PcfOptions::default()).unwrap()).unwrap();
+
+        assert_eq!(pcf, fixture);
+    }
+
+    #[test]
+    fn uncompressed_metrics() {
+        let mut font = font().subset(['A', 'B']);
+        font.glyphs = font
+            .glyphs
+            .iter()
+            .cloned()
+            .map(|mut glyph| {
+                glyph.width_horizontal.as_mut().unwrap().device.x = 200;
+                glyph
+            })
+            .collect();
+
+        let pcf = font.to_pcf(PcfOptions::default()).unwrap();
+        assert_round_trip(&font, PcfOptions::default());
+
+        // The format of the metrics table doesn't include the compressed metrics flag.
+        let metrics_entry = &pcf[8 + 2 * 16..8 + 3 * 16];
+        assert_eq!(metrics_entry[0..4], PCF_METRICS.to_le_bytes());
+        assert_eq!(metrics_entry[4..8], 0x0Eu32.to_le_bytes());
+    }
+
+    #[test]
+    fn uncompressed_metrics_for_many_glyphs() {
+        let glyph = font().glyphs.get('A').unwrap().clone();
+        let mut font = font().subset(['A']);
+        font.glyphs = (0..0x8000)
+            .map(|encoding| Glyph {
+                name: format!("glyph{encoding}"),
+                encoding: Encoding::Standard(encoding),
+                ..glyph.clone()
+            })
+            .collect();
+
+        let pcf = font.to_pcf(PcfOptions::default()).unwrap();
+        assert_round_trip(&font, PcfOptions::default());
+
+        let metrics_entry = &pcf[8 + 2 * 16..8 + 3 * 16];
+        assert_eq!(metrics_entry[0..4], PCF_METRICS.to_le_bytes());
+        assert_eq!(metrics_entry[4..8], 0x0Eu32.to_le_bytes());
+    }
+
+    #[test]
+    fn encoding_out_of_range() {
+        let mut font = font().subset(['A']);
+        font.glyphs = font
+            .glyphs
+            .iter()
+            .cloned()
+            .map(|glyph| Glyph {
+                encoding: Encoding::Standard(0x10000),
+                ..glyph
+            })
+            .collect();
+
+        assert_eq!(
+            font.to_pcf(PcfOptions::default()),
+            Err(PcfError::EncodingOutOfRange("A".to_string()))
+        );
+    }
+
+    #[test]
+    fn metrics_out_of_range() {
+        let mut font = font().subset(['A']);
+        font.glyphs = font
+            .glyphs
+            .iter()
+            .cloned()
+            .map(|glyph| Glyph {
+                bounding_box: BoundingBox {
+                    offset: Coord::new(40000, 0),
+                    size: Coord::new(6, 10),
+                },
+                ..glyph
+            })
+            .collect();
+
+        assert_eq!(
+            font.to_pcf(PcfOptions::default()),
+            Err(PcfError::MetricsOutOfRange("A".to_string()))
+        );
+    }
+
+    #[test]
+    fn unsupported_bits_per_pixel() {
+        let mut font = font().subset(['A']);
+        font.metadata.bits_per_pixel = 8;
+
+        assert_eq!(
+            font.to_pcf(PcfOptions::default()),
+            Err(PcfError::UnsupportedBitsPerPixel(8))
+        );
+    }
+}
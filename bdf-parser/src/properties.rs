@@ -134,7 +134,6 @@ pub struct Properties {
 }
 
 impl Properties {
-    #[cfg(test)]
     pub(crate) fn new(properties: HashMap<String, PropertyValue>) -> Self {
         Self { properties }
     }
@@ -197,6 +196,32 @@ impl Properties {
             .transpose()
     }
 
+    /// Sets a property.
+    ///
+    /// Existing values are replaced.
+    pub fn set(&mut self, property: Property, value: PropertyValue) {
+        self.properties.insert(property.to_string(), value);
+    }
+
+    /// Sets a property by name.
+    pub(crate) fn set_by_name(&mut self, name: &str, value: PropertyValue) {
+        self.properties.insert(name.to_string(), value);
+    }
+
+    /// Removes a property.
+    pub(crate) fn remove(&mut self, property: Property) {
+        self.properties.remove(&property.to_string());
+    }
+
+    /// Returns an iterator over all properties.
+    ///
+    /// The properties are returned in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &PropertyValue)> {
+        self.properties
+            .iter()
+            .map(|(name, value)| (name.as_str(), value))
+    }
+
     /// Returns `true` if no properties exist.
     pub fn is_empty(&self) -> bool {
         self.properties.is_empty()
@@ -214,12 +239,24 @@ impl PropertyType for String {}
 impl PropertyType for i32 {}
 impl PropertyType for u32 {}
 
+/// Property value.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum PropertyValue {
+    /// Text value.
     Text(String),
+    /// Integer value.
     Int(i32),
 }
 
+impl std::fmt::Display for PropertyValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PropertyValue::Text(text) => write!(f, "\"{}\"", text.replace('"', "\"\"")),
+            PropertyValue::Int(int) => write!(f, "{int}"),
+        }
+    }
+}
+
 impl TryFrom<&PropertyValue> for String {
     type Error = PropertyTypeError;
 
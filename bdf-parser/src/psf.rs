@@ -0,0 +1,720 @@
+use std::collections::HashMap;
+
+use thiserror::Error;
+
+use crate::{
+    diff::set_pixels,
+    glyph::{pixels_to_bitmap, GlyphWidth},
+    BoundingBox, Coord, Encoding, Font, Glyph, Glyphs, Metadata, Metrics, ParserError,
+};
+
+const PSF1_MAGIC: [u8; 2] = [0x36, 0x04];
+const PSF1_MODE512: u8 = 0x01;
+const PSF1_MODEHASTAB: u8 = 0x02;
+const PSF1_MODESEQ: u8 = 0x04;
+const PSF1_SEPARATOR: u16 = 0xFFFF;
+const PSF1_STARTSEQ: u16 = 0xFFFE;
+
+const PSF2_MAGIC: [u8; 4] = [0x72, 0xB5, 0x4A, 0x86];
+const PSF2_HEADER_SIZE: u32 = 32;
+const PSF2_HAS_UNICODE_TABLE: u32 = 0x01;
+const PSF2_SEPARATOR: u8 = 0xFF;
+const PSF2_STARTSEQ: u8 = 0xFE;
+
+/// PSF version.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PsfVersion {
+    /// PSF version 1.
+    ///
+    /// PSF1 fonts are always 8 pixels wide and contain 256 or 512 glyphs.
+    Psf1,
+
+    /// PSF version 2.
+    #[default]
+    Psf2,
+}
+
+/// PSF export error.
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum PsfError {
+    /// The glyphs don't have the same width.
+    #[error("PSF fonts must be monospaced")]
+    NotMonospaced,
+
+    /// The glyph width isn't supported.
+    #[error("unsupported glyph width {0}")]
+    InvalidWidth(i32),
+
+    /// The glyph height isn't supported.
+    #[error("unsupported glyph height {0}")]
+    InvalidHeight(u32),
+
+    /// The font contains more glyphs than can be stored in a PSF1 file.
+    #[error("PSF1 fonts can contain at most 512 glyphs")]
+    TooManyGlyphs,
+
+    /// A glyph doesn't fit into the fixed size glyph cell.
+    #[error("glyph \"{0}\" doesn't fit into the glyph cell")]
+    GlyphOutsideCell(String),
+
+    /// The encoding of a glyph can't be stored in the Unicode table.
+    #[error("encoding of glyph \"{0}\" can't be stored in the Unicode table")]
+    EncodingOutOfRange(String),
+
+    /// The font uses more than one bit per pixel.
+    #[error("unsupported bits per pixel value {0}, PSF fonts must be monochrome")]
+    UnsupportedBitsPerPixel(u32),
+}
+
+impl Font {
+    /// Parses a Linux console PSF1 or PSF2 font.
+    ///
+    /// PSF files don't contain a baseline and the ascent needs to be specified.
+    /// The descent is set to the remaining rows of the glyph cell.
+    ///
+    /// If the font contains a Unicode table, a glyph is added for each code
+    /// point in the table and glyphs which are used for multiple code points
+    /// are duplicated. Sequences of code points and glyphs without an entry in
+    /// the Unicode table are ignored. Fonts without a Unicode table use the
+    /// glyph index as the encoding.
+    pub fn parse_psf(data: &[u8], ascent: u32) -> Result<Self, ParserError> {
+        let psf = if data.starts_with(&PSF1_MAGIC) {
+            Psf::parse_psf1(data)?
+        } else if data.starts_with(&PSF2_MAGIC) {
+            Psf::parse_psf2(data)?
+        } else {
+            return Err(ParserError::new("invalid PSF header"));
+        };
+
+        if ascent > psf.height {
+            return Err(ParserError::new(&format!(
+                "ascent must not be larger than the glyph height ({})",
+                psf.height
+            )));
+        }
+
+        let metrics = Metrics {
+            ascent,
+            descent: psf.height - ascent,
+        };
+
+        let width = psf.width as i32;
+        let height = psf.height as i32;
+
+        let glyph = |bitmap: &[u8]| Glyph {
+            name: String::new(),
+            encoding: Encoding::Unspecified,
+            width_horizontal: Some(GlyphWidth {
+                scalable: Coord::new(width * 1000 / height, 0),
+                device: Coord::new(width, 0),
+            }),
+            width_vertical: None,
+            bounding_box: BoundingBox {
+                offset: Coord::new(0, -(metrics.descent as i32)),
+                size: Coord::new(width, height),
+            },
+            origin_offset: None,
+            bitmap: bitmap.to_vec(),
+        };
+
+        let mut glyphs = Vec::new();
+        for (index, bitmap) in psf.bitmaps().enumerate() {
+            match &psf.unicode_table {
+                Some(table) => {
+                    for c in &table[index] {
+                        glyphs.push(Glyph {
+                            name: format!("U+{:04X}", u32::from(*c)),
+                            encoding: Encoding::Standard(u32::from(*c)),
+                            ..glyph(bitmap)
+                        });
+                    }
+                }
+                None => glyphs.push(Glyph {
+                    name: format!("char{index}"),
+                    encoding: Encoding::Standard(index as u32),
+                    ..glyph(bitmap)
+                }),
+            }
+        }
+
+        let glyphs = glyphs.into_iter().collect::<Glyphs>();
+        let metadata =
+            Metadata::for_imported_font(&glyphs, &metrics, psf.height, psf.unicode_table.is_some());
+
+        Ok(Font {
+            metadata,
+            glyphs,
+            metrics,
+        })
+    }
+
+    /// Converts the font into the Linux console PSF format.
+    ///
+    /// PSF fonts use a fixed size glyph cell. The cell width is the width of
+    /// the glyphs, which must be the same for all glyphs, and the cell height
+    /// is the line height of the font. All glyphs must fit into the cell.
+    ///
+    /// A Unicode table is only included in the output if the
+    /// `CHARSET_REGISTRY` property is `ISO10646`. In this case glyphs with
+    /// identical bitmaps are only stored once and the Unicode table entry of
+    /// the stored glyph contains the encodings of all these glyphs. Glyphs with
+    /// a non standard or unspecified encoding don't add an entry to the Unicode
+    /// table.
+    ///
+    /// Without a Unicode table the encoding of a glyph is used as the glyph
+    /// index and glyphs with a non standard or unspecified encoding are
+    /// skipped.
+    ///
+    /// PSF glyphs are monochrome and fonts with more than 1 bit per pixel
+    /// result in an error.
+    pub fn to_psf(&self, version: PsfVersion) -> Result<Vec<u8>, PsfError> {
+        if self.metadata.bits_per_pixel != 1 {
+            return Err(PsfError::UnsupportedBitsPerPixel(
+                self.metadata.bits_per_pixel,
+            ));
+        }
+
+        let width = self.cell_width()?;
+        let height = self.metrics.line_height();
+        let ascent = self.metrics.ascent as i32;
+
+        match version {
+            PsfVersion::Psf1 if width != 8 => return Err(PsfError::InvalidWidth(width)),
+            PsfVersion::Psf1 if height == 0 || height > 255 => {
+                return Err(PsfError::InvalidHeight(height))
+            }
+            _ if height == 0 => return Err(PsfError::InvalidHeight(height)),
+            _ => {}
+        }
+
+        let bitmap = |glyph: &Glyph| {
+            let pixels = set_pixels(glyph);
+
+            if pixels.iter().any(|pixel| {
+                pixel.x < 0
+                    || pixel.x >= width
+                    || pixel.y >= ascent
+                    || pixel.y < ascent - height as i32
+            }) {
+                return Err(PsfError::GlyphOutsideCell(glyph.name.clone()));
+            }
+
+            Ok(pixels_to_bitmap(width as usize, height as usize, |x, y| {
+                pixels.contains(&Coord::new(x as i32, ascent - 1 - y as i32))
+            }))
+        };
+
+        let unicode_table = self.metadata.is_unicode();
+
+        let mut psf_glyphs: Vec<PsfGlyph<'_>> = Vec::new();
+        if unicode_table {
+            let mut indices = HashMap::<Vec<u8>, usize>::new();
+            for glyph in self.glyphs.iter() {
+                let bitmap = bitmap(glyph)?;
+
+                match indices.get(&bitmap) {
+                    Some(&index) => psf_glyphs[index].glyphs.push(glyph),
+                    None => {
+                        indices.insert(bitmap.clone(), psf_glyphs.len());
+                        psf_glyphs.push(PsfGlyph {
+                            bitmap,
+                            glyphs: vec![glyph],
+                        });
+                    }
+                }
+            }
+        } else {
+            let bytes_per_glyph = (width as usize).div_ceil(8) * height as usize;
+
+            for glyph in self.glyphs.iter() {
+                let Encoding::Standard(encoding) = glyph.encoding else {
+                    continue;
+                };
+                let index = usize::from(
+                    u16::try_from(encoding)
+                        .map_err(|_| PsfError::EncodingOutOfRange(glyph.name.clone()))?,
+                );
+
+                // Unused glyphs are filled with empty bitmaps.
+                if index >= psf_glyphs.len() {
+                    psf_glyphs.resize_with(index + 1, || PsfGlyph {
+                        bitmap: vec![0; bytes_per_glyph],
+                        glyphs: Vec::new(),
+                    });
+                }
+                psf_glyphs[index] = PsfGlyph {
+                    bitmap: bitmap(glyph)?,
+                    glyphs: vec![glyph],
+                };
+            }
+        }
+
+        match version {
+            PsfVersion::Psf1 if psf_glyphs.len() > 512 => Err(PsfError::TooManyGlyphs),
+            PsfVersion::Psf1 => psf1(height, &psf_glyphs, unicode_table),
+            PsfVersion::Psf2 => psf2(width, height, &psf_glyphs, unicode_table),
+        }
+    }
+
+    /// Returns the common width of all glyphs.
+    fn cell_width(&self) -> Result<i32, PsfError> {
+        let mut widths = self
+            .glyphs
+            .iter()
+            .map(|glyph| match glyph.width_horizontal {
+                Some(width) => width.device.x,
+                None => glyph.bounding_box.size.x,
+            });
+
+        let width = widths.next().unwrap_or(8);
+        if widths.any(|w| w != width) {
+            return Err(PsfError::NotMonospaced);
+        }
+        if width <= 0 {
+            return Err(PsfError::InvalidWidth(width));
+        }
+
+        Ok(width)
+    }
+}
+
+/// Glyph in a PSF file and the font glyphs which use its bitmap.
+struct PsfGlyph<'a> {
+    bitmap: Vec<u8>,
+    glyphs: Vec<&'a Glyph>,
+}
+
+fn psf1(
+    height: u32,
+    psf_glyphs: &[PsfGlyph<'_>],
+    unicode_table: bool,
+) -> Result<Vec<u8>, PsfError> {
+    let (mut mode, glyph_count) = if psf_glyphs.len() > 256 {
+        (PSF1_MODE512, 512)
+    } else {
+        (0, 256)
+    };
+    if unicode_table {
+        mode |= PSF1_MODEHASTAB;
+    }
+
+    let mut data = PSF1_MAGIC.to_vec();
+    data.extend([mode, height as u8]);
+    for psf_glyph in psf_glyphs {
+        data.extend(&psf_glyph.bitmap);
+    }
+    // Unused glyphs are filled with empty bitmaps.
+    data.resize(4 + glyph_count * height as usize, 0);
+
+    if !unicode_table {
+        return Ok(data);
+    }
+
+    for psf_glyph in psf_glyphs {
+        for glyph in &psf_glyph.glyphs {
+            if let Encoding::Standard(encoding) = glyph.encoding {
+                let encoding = u16::try_from(encoding)
+                    .ok()
+                    .filter(|encoding| *encoding < PSF1_STARTSEQ)
+                    .ok_or_else(|| PsfError::EncodingOutOfRange(glyph.name.clone()))?;
+
+                data.extend(encoding.to_le_bytes());
+            }
+        }
+        data.extend(PSF1_SEPARATOR.to_le_bytes());
+    }
+    for _ in psf_glyphs.len()..glyph_count {
+        data.extend(PSF1_SEPARATOR.to_le_bytes());
+    }
+
+    Ok(data)
+}
+
+fn psf2(
+    width: i32,
+    height: u32,
+    psf_glyphs: &[PsfGlyph<'_>],
+    unicode_table: bool,
+) -> Result<Vec<u8>, PsfError> {
+    let bytes_per_glyph = (width as u32).div_ceil(8) * height;
+    let flags = if unicode_table {
+        PSF2_HAS_UNICODE_TABLE
+    } else {
+        0
+    };
+
+    let mut data = PSF2_MAGIC.to_vec();
+    for value in [
+        0,
+        PSF2_HEADER_SIZE,
+        flags,
+        psf_glyphs.len() as u32,
+        bytes_per_glyph,
+        height,
+        width as u32,
+    ] {
+        data.extend(value.to_le_bytes());
+    }
+    for psf_glyph in psf_glyphs {
+        data.extend(&psf_glyph.bitmap);
+    }
+
+    if !unicode_table {
+        return Ok(data);
+    }
+
+    for psf_glyph in psf_glyphs {
+        for glyph in &psf_glyph.glyphs {
+            if let Encoding::Standard(encoding) = glyph.encoding {
+                let c = char::from_u32(encoding)
+                    .ok_or_else(|| PsfError::EncodingOutOfRange(glyph.name.clone()))?;
+
+                data.extend(c.to_string().as_bytes());
+            }
+        }
+        data.push(PSF2_SEPARATOR);
+    }
+
+    Ok(data)
+}
+
+/// Parsed PSF file.
+struct Psf<'a> {
+    width: u32,
+    height: u32,
+    glyph_count: usize,
+    bytes_per_glyph: usize,
+    data: &'a [u8],
+    unicode_table: Option<Vec<Vec<char>>>,
+}
+
+impl<'a> Psf<'a> {
+    fn parse_psf1(data: &'a [u8]) -> Result<Self, ParserError> {
+        let [_, _, mode, height] = *data.first_chunk::<4>().ok_or_else(unexpected_end)?;
+        if height == 0 {
+            return Err(ParserError::new("invalid glyph size in PSF1 header"));
+        }
+
+        let glyph_count = if mode & PSF1_MODE512 != 0 { 512 } else { 256 };
+        let bytes_per_glyph = usize::from(height);
+
+        let glyph_data = data
+            .get(4..4 + glyph_count * bytes_per_glyph)
+            .ok_or_else(unexpected_end)?;
+
+        let unicode_table = if mode & (PSF1_MODEHASTAB | PSF1_MODESEQ) != 0 {
+            let mut entries = data[4 + glyph_data.len()..]
+                .chunks_exact(2)
+                .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]));
+
+            let mut table = Vec::with_capacity(glyph_count);
+            for _ in 0..glyph_count {
+                let mut chars = Vec::new();
+                let mut in_sequence = false;
+
+                loop {
+                    match entries.next().ok_or_else(unexpected_end)? {
+                        PSF1_SEPARATOR => break,
+                        PSF1_STARTSEQ => in_sequence = true,
+                        _ if in_sequence => {}
+                        value => chars.extend(char::from_u32(value.into())),
+                    }
+                }
+
+                table.push(chars);
+            }
+
+            Some(table)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            width: 8,
+            height: height.into(),
+            glyph_count,
+            bytes_per_glyph,
+            data: glyph_data,
+            unicode_table,
+        })
+    }
+
+    fn parse_psf2(data: &'a [u8]) -> Result<Self, ParserError> {
+        let header = data
+            .get(4..PSF2_HEADER_SIZE as usize)
+            .ok_or_else(unexpected_end)?;
+
+        let mut values = header
+            .chunks_exact(4)
+            .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()));
+        let mut value = || values.next().unwrap();
+
+        let _version = value();
+        let header_size = value() as usize;
+        let flags = value();
+        let glyph_count = value() as usize;
+        let bytes_per_glyph = value() as usize;
+        let height = value();
+        let width = value();
+
+        if width == 0
+            || height == 0
+            || bytes_per_glyph != width.div_ceil(8) as usize * height as usize
+        {
+            return Err(ParserError::new("invalid glyph size in PSF2 header"));
+        }
+
+        let glyph_data_end = glyph_count
+            .checked_mul(bytes_per_glyph)
+            .and_then(|size| size.checked_add(header_size))
+            .ok_or_else(unexpected_end)?;
+        let glyph_data = data
+            .get(header_size..glyph_data_end)
+            .ok_or_else(unexpected_end)?;
+
+        let unicode_table = if flags & PSF2_HAS_UNICODE_TABLE != 0 {
+            let mut entries = data[glyph_data_end..].split(|byte| *byte == PSF2_SEPARATOR);
+
+            let mut table = Vec::with_capacity(glyph_count);
+            for _ in 0..glyph_count {
+                let entry = entries.next().ok_or_else(unexpected_end)?;
+
+                // Sequences of code points start after the first `PSF2_STARTSEQ`.
+                let chars = entry
+                    .split(|byte| *byte == PSF2_STARTSEQ)
+                    .next()
+                    .unwrap_or_default();
+                let chars = std::str::from_utf8(chars)
+                    .map_err(|_| ParserError::new("invalid UTF-8 in unicode table"))?;
+
+                table.push(chars.chars().collect());
+            }
+
+            Some(table)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            width,
+            height,
+            glyph_count,
+            bytes_per_glyph,
+            data: glyph_data,
+            unicode_table,
+        })
+    }
+
+    fn bitmaps(&self) -> impl Iterator<Item = &'a [u8]> {
+        self.data
+            .chunks_exact(self.bytes_per_glyph.max(1))
+            .take(self.glyph_count)
+    }
+}
+
+fn unexpected_end() -> ParserError {
+    ParserError::new("unexpected end of file")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEX: &str = "\
+        0041:0000000018242442427E424242420000\n\
+        0042:00000000007C4242427C424242427C00\n\
+        00C4:24240018242442427E42424242420000\n";
+
+    fn font_6x10() -> Font {
+        let bdf = std::fs::read("../eg-bdf-examples/examples/6x10.bdf").unwrap();
+        Font::parse(&String::from_utf8_lossy(&bdf)).unwrap()
+    }
+
+    #[test]
+    fn psf1_round_trip() {
+        let font = Font::parse_hex(HEX, 14).unwrap();
+
+        let psf = font.to_psf(PsfVersion::Psf1).unwrap();
+        assert_eq!(psf[0..4], [0x36, 0x04, PSF1_MODEHASTAB, 16]);
+
+        let parsed = Font::parse_psf(&psf, 14).unwrap();
+        assert_eq!(parsed.glyphs, font.glyphs);
+        assert_eq!(parsed.metrics, font.metrics);
+    }
+
+    #[test]
+    fn psf2_round_trip() {
+        let font = font_6x10().subset((' '..='~').chain('\u{A0}'..='\u{FF}'));
+
+        let psf = font.to_psf(PsfVersion::Psf2).unwrap();
+        let parsed = Font::parse_psf(&psf, 8).unwrap();
+
+        assert_eq!(parsed.metrics, font.metrics);
+
+        let diff = font.diff(&parsed);
+        assert!(diff.added.is_empty());
+        assert!(diff.removed.is_empty());
+        assert!(diff.changed.iter().all(|glyph| !glyph.bitmap_changed()));
+        assert_eq!(parsed.glyphs.get('A').unwrap().name, "U+0041");
+    }
+
+    #[test]
+    fn psf1_round_trip_multiple_code_points() {
+        let mut data = vec![0x36, 0x04, PSF1_MODEHASTAB, 1];
+        data.extend((0..=255).map(|index| index as u8));
+
+        // Each glyph is used for two code points.
+        for index in 0..256 {
+            for value in [index, 0x400 + index, PSF1_SEPARATOR] {
+                data.extend(u16::to_le_bytes(value));
+            }
+        }
+
+        let font = Font::parse_psf(&data, 1).unwrap();
+        assert_eq!(font.glyphs.len(), 512);
+
+        let psf = font.to_psf(PsfVersion::Psf1).unwrap();
+        assert_eq!(psf, data);
+
+        let parsed = Font::parse_psf(&psf, 1).unwrap();
+        assert_eq!(parsed.glyphs, font.glyphs);
+    }
+
+    #[test]
+    fn unicode_table_psf1() {
+        let mut data = vec![0x36, 0x04, PSF1_MODEHASTAB, 1];
+        data.extend((0..=255).map(|index| index as u8));
+
+        // Glyph 0 is used for 'A', 'Α' and the sequence "A\u{30A}".
+        for value in [0x41, 0x391, PSF1_STARTSEQ, 0x41, 0x30A, PSF1_SEPARATOR] {
+            data.extend(u16::to_le_bytes(value));
+        }
+        for _ in 1..256 {
+            data.extend(PSF1_SEPARATOR.to_le_bytes());
+        }
+
+        let font = Font::parse_psf(&data, 1).unwrap();
+
+        assert_eq!(font.glyphs.len(), 2);
+        assert_eq!(font.glyphs.get('A').unwrap().bitmap, [0]);
+        assert_eq!(font.glyphs.get('Α').unwrap().bitmap, [0]);
+        assert_eq!(font.glyphs.get('\u{30A}'), None);
+    }
+
+    #[test]
+    fn without_unicode_table() {
+        let mut data = vec![0x72, 0xB5, 0x4A, 0x86];
+        for value in [0, 32, 0, 3, 2, 2, 4] {
+            data.extend(u32::to_le_bytes(value));
+        }
+        data.extend([0x10, 0x20, 0x30, 0x40, 0x50, 0x60]);
+
+        let font = Font::parse_psf(&data, 2).unwrap();
+
+        assert_eq!(font.glyphs.len(), 3);
+        let glyph = font.glyphs.get('\u{2}').unwrap();
+        assert_eq!(glyph.name, "char2");
+        assert_eq!(glyph.bitmap, [0x50, 0x60]);
+        assert_eq!(
+            font.metadata
+                .properties
+                .try_get::<String>(crate::Property::CharsetRegistry),
+            Ok(Some("FontSpecific".to_string()))
+        );
+    }
+
+    #[test]
+    fn without_unicode_table_round_trip() {
+        let mut data = vec![0x72, 0xB5, 0x4A, 0x86];
+        for value in [0, 32, 0, 3, 2, 2, 4] {
+            data.extend(u32::to_le_bytes(value));
+        }
+        data.extend([0x10, 0x20, 0x30, 0x40, 0x50, 0x60]);
+
+        let font = Font::parse_psf(&data, 2).unwrap();
+
+        assert_eq!(font.to_psf(PsfVersion::Psf2).unwrap(), data);
+    }
+
+    #[test]
+    fn zero_glyph_size() {
+        for (height, width) in [(0, 8), (8, 0)] {
+            let mut data = vec![0x72, 0xB5, 0x4A, 0x86];
+            for value in [0, 32, 0, u32::MAX, 0, height, width] {
+                data.extend(u32::to_le_bytes(value));
+            }
+
+            assert_eq!(
+                Font::parse_psf(&data, 0),
+                Err(ParserError::new("invalid glyph size in PSF2 header"))
+            );
+        }
+
+        assert_eq!(
+            Font::parse_psf(&[0x36, 0x04, 0, 0], 0),
+            Err(ParserError::new("invalid glyph size in PSF1 header"))
+        );
+    }
+
+    #[test]
+    fn invalid_header() {
+        assert_eq!(
+            Font::parse_psf(b"STARTFONT 2.1", 8),
+            Err(ParserError::new("invalid PSF header"))
+        );
+        assert_eq!(
+            Font::parse_psf(&PSF2_MAGIC, 8),
+            Err(ParserError::new("unexpected end of file"))
+        );
+    }
+
+    #[test]
+    fn psf1_invalid_width() {
+        let font = font_6x10().subset('A'..='Z');
+
+        assert_eq!(
+            font.to_psf(PsfVersion::Psf1),
+            Err(PsfError::InvalidWidth(6))
+        );
+    }
+
+    #[test]
+    fn not_monospaced() {
+        let mut font = font_6x10().subset('A'..='B');
+        font.glyphs = font
+            .glyphs
+            .iter()
+            .cloned()
+            .map(|mut glyph| {
+                if glyph.name == "B" {
+                    glyph.width_horizontal.as_mut().unwrap().device.x = 7;
+                }
+                glyph
+            })
+            .collect();
+
+        assert_eq!(font.to_psf(PsfVersion::Psf2), Err(PsfError::NotMonospaced));
+    }
+
+    #[test]
+    fn glyph_outside_cell() {
+        let mut font = font_6x10().subset('A'..='B');
+        font.metrics.ascent = 5;
+
+        assert_eq!(
+            font.to_psf(PsfVersion::Psf2),
+            Err(PsfError::GlyphOutsideCell("A".to_string()))
+        );
+    }
+
+    #[test]
+    fn unsupported_bits_per_pixel() {
+        let mut font = font_6x10().subset('A'..='B');
+        font.metadata.bits_per_pixel = 4;
+
+        assert_eq!(
+            font.to_psf(PsfVersion::Psf2),
+            Err(PsfError::UnsupportedBitsPerPixel(4))
+        );
+    }
+}
@@ -0,0 +1,198 @@
+use std::collections::BTreeSet;
+
+use crate::{properties::PropertyValue, BoundingBox, Encoding, Font, Glyphs, Property};
+
+impl Font {
+    /// Returns a subset of this font, which only contains the given characters.
+    ///
+    /// Characters which aren't included in this font are ignored.
+    ///
+    /// The font bounding box of the returned font is recalculated from the
+    /// remaining glyphs. The ascent and descent are kept unchanged and are
+    /// stored in the `FONT_ASCENT` and `FONT_DESCENT` properties. If the font
+    /// contains the `AVERAGE_WIDTH` property it is updated to match the
+    /// remaining glyphs and the `DEFAULT_CHAR` property is removed if the
+    /// default character isn't included in the subset.
+    pub fn subset(&self, chars: impl IntoIterator<Item = char>) -> Font {
+        let chars = chars.into_iter().collect::<BTreeSet<_>>();
+
+        let glyphs = chars
+            .iter()
+            .filter_map(|c| self.glyphs.get(*c))
+            .cloned()
+            .collect::<Glyphs>();
+
+        let mut metadata = self.metadata.clone();
+        metadata.bounding_box = glyphs
+            .iter()
+            .fold(BoundingBox::default(), |bounding_box, glyph| {
+                bounding_box.union(&glyph.bounding_box)
+            });
+
+        let properties = &mut metadata.properties;
+        properties.set(
+            Property::FontAscent,
+            PropertyValue::Int(self.metrics.ascent as i32),
+        );
+        properties.set(
+            Property::FontDescent,
+            PropertyValue::Int(self.metrics.descent as i32),
+        );
+
+        if let Ok(Some(default_char)) = properties.try_get::<u32>(Property::DefaultChar) {
+            let encoding = Encoding::Standard(default_char);
+
+            if !glyphs.iter().any(|glyph| glyph.encoding == encoding) {
+                properties.remove(Property::DefaultChar);
+            }
+        }
+
+        if let Ok(Some(_)) = properties.try_get::<i32>(Property::AverageWidth) {
+            properties.set(
+                Property::AverageWidth,
+                PropertyValue::Int(average_width(&glyphs)),
+            );
+        }
+
+        Font {
+            metadata,
+            glyphs,
+            metrics: self.metrics,
+        }
+    }
+}
+
+/// Calculates the average width in tenths of pixels.
+///
+/// See section 3.2.21 AVERAGE_WIDTH in https://www.x.org/docs/XLFD/xlfd.pdf.
+pub(crate) fn average_width(glyphs: &Glyphs) -> i32 {
+    if glyphs.is_empty() {
+        return 0;
+    }
+
+    let sum = glyphs
+        .iter()
+        .map(|glyph| glyph.width_horizontal.map_or(0, |width| width.device.x))
+        .sum::<i32>();
+    let count = glyphs.len() as i32;
+
+    (sum * 10 + count / 2).div_euclid(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+    use crate::{Coord, Metrics};
+
+    const FONT: &str = indoc! {r#"
+        STARTFONT 2.1
+        FONT test
+        SIZE 8 75 75
+        FONTBOUNDINGBOX 6 8 0 -2
+        STARTPROPERTIES 4
+        FONT_ASCENT 6
+        FONT_DESCENT 2
+        AVERAGE_WIDTH 50
+        DEFAULT_CHAR 66
+        ENDPROPERTIES
+        CHARS 3
+        STARTCHAR A
+        ENCODING 65
+        DWIDTH 4 0
+        BBX 3 4 0 0
+        BITMAP
+        40
+        A0
+        E0
+        A0
+        ENDCHAR
+        STARTCHAR B
+        ENCODING 66
+        DWIDTH 5 0
+        BBX 4 4 0 0
+        BITMAP
+        E0
+        F0
+        90
+        E0
+        ENDCHAR
+        STARTCHAR g
+        ENCODING 103
+        DWIDTH 6 0
+        BBX 6 6 0 -2
+        BITMAP
+        7C
+        84
+        84
+        7C
+        04
+        78
+        ENDCHAR
+        ENDFONT
+    "#};
+
+    #[test]
+    fn subset() {
+        let font = Font::parse(FONT).unwrap();
+        let subset = font.subset("AXA".chars());
+
+        assert_eq!(subset.glyphs.len(), 1);
+        assert_eq!(subset.glyphs.get('A'), font.glyphs.get('A'));
+        assert_eq!(
+            subset.metadata.bounding_box,
+            BoundingBox {
+                offset: Coord::new(0, 0),
+                size: Coord::new(3, 4),
+            }
+        );
+        assert_eq!(
+            subset.metrics,
+            Metrics {
+                ascent: 6,
+                descent: 2,
+            }
+        );
+
+        let properties = &subset.metadata.properties;
+        assert_eq!(properties.try_get::<u32>(Property::FontAscent), Ok(Some(6)));
+        assert_eq!(
+            properties.try_get::<u32>(Property::FontDescent),
+            Ok(Some(2))
+        );
+        assert_eq!(
+            properties.try_get::<i32>(Property::AverageWidth),
+            Ok(Some(40))
+        );
+        assert_eq!(properties.try_get::<u32>(Property::DefaultChar), Ok(None));
+    }
+
+    #[test]
+    fn subset_keeps_default_char() {
+        let font = Font::parse(FONT).unwrap();
+        let subset = font.subset(['A', 'B']);
+
+        assert_eq!(subset.glyphs.len(), 2);
+
+        let properties = &subset.metadata.properties;
+        assert_eq!(
+            properties.try_get::<i32>(Property::AverageWidth),
+            Ok(Some(45))
+        );
+        assert_eq!(
+            properties.try_get::<u32>(Property::DefaultChar),
+            Ok(Some(66))
+        );
+    }
+
+    #[test]
+    fn empty_subset() {
+        let font = Font::parse(FONT).unwrap();
+        let subset = font.subset([]);
+
+        assert!(subset.glyphs.is_empty());
+        assert_eq!(subset.metadata.bounding_box, BoundingBox::default());
+        assert_eq!(subset.metrics, font.metrics);
+    }
+}
@@ -0,0 +1,726 @@
+//! u8g2 font decoder.
+//!
+//! u8g2 fonts start with a 23 byte header, which contains the number of bits
+//! used for each value in the glyph data, followed by a list of glyphs with
+//! 8 bit encodings and a list of glyphs with 16 bit Unicode encodings. The
+//! glyph bitmaps are run length encoded.
+
+use crate::{
+    glyph::{pixels_to_bitmap, GlyphWidth},
+    BoundingBox, Coord, Encoding, Font, Glyph, Glyphs, Metadata, Metrics, ParserError,
+};
+
+const HEADER_SIZE: usize = 23;
+
+/// u8g2 font header.
+#[derive(Debug)]
+struct Header {
+    bits_per_0: u32,
+    bits_per_1: u32,
+    bits_per_char_width: u32,
+    bits_per_char_height: u32,
+    bits_per_char_x: u32,
+    bits_per_char_y: u32,
+    bits_per_delta_x: u32,
+    max_char_height: i32,
+    y_offset: i32,
+    start_pos_unicode: usize,
+}
+
+impl Header {
+    fn parse(data: &[u8]) -> Result<Self, ParserError> {
+        let header = data
+            .get(..HEADER_SIZE)
+            .ok_or_else(|| ParserError::new("u8g2 font header is too short"))?;
+
+        let bits = |index: usize| -> Result<u32, ParserError> {
+            let bits = u32::from(header[index]);
+            if bits > 8 {
+                return Err(ParserError::new("invalid bit width in u8g2 font header"));
+            }
+            Ok(bits)
+        };
+
+        Ok(Self {
+            bits_per_0: bits(2)?,
+            bits_per_1: bits(3)?,
+            bits_per_char_width: bits(4)?,
+            bits_per_char_height: bits(5)?,
+            bits_per_char_x: bits(6)?,
+            bits_per_char_y: bits(7)?,
+            bits_per_delta_x: bits(8)?,
+            max_char_height: i32::from(header[10]),
+            y_offset: i32::from(header[12] as i8),
+            start_pos_unicode: usize::from(u16::from_be_bytes([header[21], header[22]])),
+        })
+    }
+}
+
+/// Bit reader for the glyph data.
+///
+/// Values are stored with the least significant bit first.
+#[derive(Debug)]
+struct BitReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, position: 0 }
+    }
+
+    fn unsigned(&mut self, bits: u32) -> Result<u32, ParserError> {
+        let mut value = 0;
+        for bit in 0..bits {
+            let byte = self
+                .data
+                .get(self.position / 8)
+                .ok_or_else(|| ParserError::new("unexpected end of u8g2 glyph data"))?;
+            value |= u32::from((byte >> (self.position % 8)) & 1) << bit;
+            self.position += 1;
+        }
+
+        Ok(value)
+    }
+
+    fn signed(&mut self, bits: u32) -> Result<i32, ParserError> {
+        if bits == 0 {
+            return Ok(0);
+        }
+
+        Ok(self.unsigned(bits)? as i32 - (1 << (bits - 1)))
+    }
+}
+
+impl Font {
+    /// Parses a u8g2 font.
+    ///
+    /// The font data is the content of a `u8g2_font_*` array. u8g2 fonts don't
+    /// contain glyph names and font metadata. The glyphs are named after their
+    /// Unicode code point and the metadata is generated by
+    /// [`Metadata::for_imported_font`]. The ascent and descent are derived
+    /// from the font bounding box in the header.
+    pub fn parse_u8g2(data: &[u8]) -> Result<Self, ParserError> {
+        let header = Header::parse(data)?;
+        let font = &data[HEADER_SIZE..];
+
+        let mut glyphs = Vec::new();
+
+        // Glyphs with 8 bit encodings: encoding, size, data
+        let mut position = 0;
+        loop {
+            let entry = font
+                .get(position..position + 2)
+                .ok_or_else(|| ParserError::new("unexpected end of u8g2 font data"))?;
+            let (encoding, size) = (u32::from(entry[0]), usize::from(entry[1]));
+            if size == 0 {
+                break;
+            }
+            let glyph_data = glyph_data(font, position, 2, size)?;
+            glyphs.push(decode_glyph(&header, encoding, glyph_data)?);
+            position += size;
+        }
+
+        // Glyphs with 16 bit encodings, which are preceded by a lookup table.
+        // The first entry in the lookup table contains the offset to the first glyph.
+        let lookup_table = header.start_pos_unicode;
+        if let Some(first_offset) = font.get(lookup_table..lookup_table + 2) {
+            let first_offset = usize::from(u16::from_be_bytes([first_offset[0], first_offset[1]]));
+
+            let mut position = lookup_table + first_offset;
+            while let Some(entry) = font.get(position..position + 3) {
+                let encoding = u32::from(u16::from_be_bytes([entry[0], entry[1]]));
+                let size = usize::from(entry[2]);
+                if encoding == 0 || size == 0 {
+                    break;
+                }
+                let glyph_data = glyph_data(font, position, 3, size)?;
+                glyphs.push(decode_glyph(&header, encoding, glyph_data)?);
+                position += size;
+            }
+        }
+
+        let glyphs = glyphs.into_iter().collect::<Glyphs>();
+
+        let metrics = Metrics {
+            ascent: (header.max_char_height + header.y_offset).max(0) as u32,
+            descent: (-header.y_offset).max(0) as u32,
+        };
+        let metadata = Metadata::for_imported_font(&glyphs, &metrics, metrics.line_height(), true);
+
+        Ok(Font {
+            metadata,
+            glyphs,
+            metrics,
+        })
+    }
+
+    /// Parses all u8g2 fonts in C source code.
+    ///
+    /// Fonts are `uint8_t` arrays, which are initialized by string literals,
+    /// like the fonts in the u8g2 repository, or by lists of integer
+    /// literals. The fonts are returned together with the array names in the
+    /// order they appear in the source.
+    pub fn parse_u8g2_source(source: &str) -> Result<Vec<(String, Self)>, ParserError> {
+        c_arrays(source)?
+            .into_iter()
+            .map(|(name, data)| {
+                let font = Self::parse_u8g2(&data)
+                    .map_err(|e| ParserError::new(&format!("{name}: {e}")))?;
+                Ok((name, font))
+            })
+            .collect()
+    }
+}
+
+/// Returns the data of a glyph, excluding the glyph header.
+fn glyph_data(
+    font: &[u8],
+    position: usize,
+    header_size: usize,
+    size: usize,
+) -> Result<&[u8], ParserError> {
+    if size < header_size {
+        return Err(ParserError::new("invalid glyph size in u8g2 font data"));
+    }
+
+    font.get(position + header_size..position + size)
+        .ok_or_else(|| ParserError::new("unexpected end of u8g2 font data"))
+}
+
+fn decode_glyph(header: &Header, encoding: u32, data: &[u8]) -> Result<Glyph, ParserError> {
+    let line_height = header.max_char_height.max(1);
+
+    let mut reader = BitReader::new(data);
+
+    let width = reader.unsigned(header.bits_per_char_width)? as usize;
+    let height = reader.unsigned(header.bits_per_char_height)? as usize;
+    let x = reader.signed(header.bits_per_char_x)?;
+    let y = reader.signed(header.bits_per_char_y)?;
+    let device_width = reader.signed(header.bits_per_delta_x)?;
+
+    let mut pixels = vec![false; width * height];
+    if width > 0 && height > 0 {
+        let mut index = 0;
+        while index < pixels.len() {
+            let zeros = reader.unsigned(header.bits_per_0)? as usize;
+            let ones = reader.unsigned(header.bits_per_1)? as usize;
+
+            loop {
+                index += zeros;
+                for _ in 0..ones {
+                    if let Some(pixel) = pixels.get_mut(index) {
+                        *pixel = true;
+                    }
+                    index += 1;
+                }
+
+                // The run is repeated while the next bit is set.
+                if reader.unsigned(1)? == 0 {
+                    break;
+                }
+            }
+
+            if zeros == 0 && ones == 0 {
+                return Err(ParserError::new("invalid run length in u8g2 glyph data"));
+            }
+        }
+    }
+
+    let (bounding_box, bitmap) = if width > 0 && height > 0 {
+        let bounding_box = BoundingBox {
+            offset: Coord::new(x, y),
+            size: Coord::new(width as i32, height as i32),
+        };
+        let bitmap = pixels_to_bitmap(width, height, |x, y| pixels[x + y * width]);
+
+        (bounding_box, bitmap)
+    } else {
+        (BoundingBox::default(), Vec::new())
+    };
+
+    Ok(Glyph {
+        name: format!("U+{encoding:04X}"),
+        encoding: Encoding::Standard(encoding),
+        width_horizontal: Some(GlyphWidth {
+            scalable: Coord::new(device_width * 1000 / line_height, 0),
+            device: Coord::new(device_width, 0),
+        }),
+        width_vertical: None,
+        bounding_box,
+        origin_offset: None,
+        bitmap,
+    })
+}
+
+/// Extracts the names and contents of all `uint8_t` arrays in C source code.
+fn c_arrays(source: &str) -> Result<Vec<(String, Vec<u8>)>, ParserError> {
+    let source = strip_comments(source);
+    let mut arrays = Vec::new();
+
+    let mut rest = source.as_str();
+    while let Some(start) = rest.find("uint8_t") {
+        rest = &rest[start + "uint8_t".len()..];
+
+        // Declaration: name[size] optional attributes = initializer;
+        let Some(bracket) = rest.find('[') else {
+            break;
+        };
+        let name = rest[..bracket].trim();
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            continue;
+        }
+        let Some(equals) = rest.find('=') else {
+            break;
+        };
+        if rest[..equals].contains(';') {
+            continue;
+        }
+        let end = find_initializer_end(&rest[equals + 1..])
+            .ok_or_else(|| ParserError::new(&format!("{name}: missing \";\"")))?;
+        let initializer = rest[equals + 1..equals + 1 + end].trim();
+
+        let data = parse_initializer(initializer)
+            .map_err(|e| ParserError::new(&format!("{name}: {e}")))?;
+        arrays.push((name.to_string(), data));
+
+        rest = &rest[equals + 1 + end..];
+    }
+
+    if arrays.is_empty() {
+        return Err(ParserError::new("no uint8_t arrays found"));
+    }
+
+    Ok(arrays)
+}
+
+/// Removes all comments outside of string and character literals.
+fn strip_comments(source: &str) -> String {
+    let mut output = String::with_capacity(source.len());
+    let mut chars = source.chars().peekable();
+    let mut quote = None;
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(_), '\\') => {
+                output.push(c);
+                output.extend(chars.next());
+            }
+            (Some(q), _) if c == q => {
+                quote = None;
+                output.push(c);
+            }
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                output.push(c);
+            }
+            (None, '/') if chars.peek() == Some(&'/') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        output.push('\n');
+                        break;
+                    }
+                }
+            }
+            (None, '/') if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+                output.push(' ');
+            }
+            _ => output.push(c),
+        }
+    }
+
+    output
+}
+
+/// Returns the index of the `;` which terminates an initializer.
+fn find_initializer_end(source: &str) -> Option<usize> {
+    let mut quote = None;
+    let mut escaped = false;
+
+    for (index, c) in source.char_indices() {
+        match quote {
+            Some(_) if escaped => escaped = false,
+            Some(_) if c == '\\' => escaped = true,
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == ';' => return Some(index),
+            None => {}
+        }
+    }
+
+    None
+}
+
+fn parse_initializer(initializer: &str) -> Result<Vec<u8>, String> {
+    if let Some(list) = initializer
+        .strip_prefix('{')
+        .and_then(|list| list.strip_suffix('}'))
+    {
+        list.split(',')
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(parse_integer)
+            .collect()
+    } else {
+        parse_string_literals(initializer)
+    }
+}
+
+/// Parses an integer or character literal.
+fn parse_integer(literal: &str) -> Result<u8, String> {
+    let error = || format!("invalid value \"{literal}\"");
+
+    if let Some(char_literal) = literal
+        .strip_prefix('\'')
+        .and_then(|literal| literal.strip_suffix('\''))
+    {
+        let bytes = unescape(char_literal)?;
+        return match bytes.as_slice() {
+            [byte] => Ok(*byte),
+            _ => Err(error()),
+        };
+    }
+
+    let digits = literal.trim_end_matches(['u', 'U', 'l', 'L']);
+    let value = if let Some(hex) = digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+    {
+        u32::from_str_radix(hex, 16)
+    } else if let Some(binary) = digits
+        .strip_prefix("0b")
+        .or_else(|| digits.strip_prefix("0B"))
+    {
+        u32::from_str_radix(binary, 2)
+    } else if digits.len() > 1 && digits.starts_with('0') {
+        u32::from_str_radix(&digits[1..], 8)
+    } else {
+        digits.parse()
+    };
+
+    value
+        .ok()
+        .and_then(|value| u8::try_from(value).ok())
+        .ok_or_else(error)
+}
+
+/// Parses a sequence of adjacent string literals.
+///
+/// The terminating null byte is included in the returned data.
+fn parse_string_literals(mut input: &str) -> Result<Vec<u8>, String> {
+    let mut data = Vec::new();
+
+    loop {
+        input = input.trim_start();
+        if input.is_empty() {
+            break;
+        }
+
+        let literal = input
+            .strip_prefix('"')
+            .ok_or_else(|| "expected string literal or initializer list".to_string())?;
+
+        let mut escaped = false;
+        let end = literal
+            .char_indices()
+            .find(|(_, c)| {
+                let end = !escaped && *c == '"';
+                escaped = !escaped && *c == '\\';
+                end
+            })
+            .map(|(index, _)| index)
+            .ok_or_else(|| "unterminated string literal".to_string())?;
+
+        data.extend(unescape(&literal[..end])?);
+        input = &literal[end + 1..];
+    }
+
+    data.push(0);
+
+    Ok(data)
+}
+
+/// Converts the content of a string or character literal into bytes.
+fn unescape(literal: &str) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    let mut chars = literal.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            let mut buffer = [0; 4];
+            bytes.extend(c.encode_utf8(&mut buffer).as_bytes());
+            continue;
+        }
+
+        let c = chars
+            .next()
+            .ok_or_else(|| "invalid escape sequence".to_string())?;
+        let byte = match c {
+            'n' => b'\n',
+            't' => b'\t',
+            'r' => b'\r',
+            'a' => 0x07,
+            'b' => 0x08,
+            'f' => 0x0C,
+            'v' => 0x0B,
+            '\\' | '\'' | '"' | '?' => c as u8,
+            '0'..='7' => {
+                let mut value = c.to_digit(8).unwrap();
+                for _ in 0..2 {
+                    match chars.peek().and_then(|c| c.to_digit(8)) {
+                        Some(digit) => {
+                            value = value * 8 + digit;
+                            chars.next();
+                        }
+                        None => break,
+                    }
+                }
+                u8::try_from(value).map_err(|_| "octal escape out of range".to_string())?
+            }
+            'x' => {
+                let mut value = 0u32;
+                let mut digits = 0;
+                while let Some(digit) = chars.peek().and_then(|c| c.to_digit(16)) {
+                    value = value * 16 + digit;
+                    digits += 1;
+                    chars.next();
+                }
+                if digits == 0 {
+                    return Err("invalid hex escape".to_string());
+                }
+                u8::try_from(value).map_err(|_| "hex escape out of range".to_string())?
+            }
+            _ => return Err(format!("unknown escape sequence \"\\{c}\"")),
+        };
+        bytes.push(byte);
+    }
+
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Property;
+
+    /// Packs bits with the least significant bit first.
+    fn pack(bits: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![0; bits.len().div_ceil(8)];
+        for (index, bit) in bits.iter().enumerate() {
+            bytes[index / 8] |= bit << (index % 8);
+        }
+        bytes
+    }
+
+    /// Builds a font with the glyphs ` `, `A` and `→`.
+    ///
+    /// Bits: m0 2, m1 2, width 3, height 3, x 2, y 2, delta x 4
+    fn test_font() -> Vec<u8> {
+        #[rustfmt::skip]
+        let space = pack(&[
+            0, 0, 0, // width 0
+            0, 0, 0, // height 0
+            0, 1, // x 0
+            0, 1, // y 0
+            0, 1, 0, 1, // delta x 2
+        ]);
+
+        #[rustfmt::skip]
+        let a = pack(&[
+            1, 1, 0, // width 3
+            1, 1, 0, // height 3
+            0, 1, // x 0
+            0, 1, // y 0
+            0, 0, 1, 1, // delta x 4
+            1, 0, 1, 0, 1, 0, // 1 zero, 1 one, repeated once
+            1, 0, 1, 1, 0, // 1 zero, 3 ones
+            0, 0, 1, 0, 0, // 0 zeros, 1 one
+        ]);
+
+        #[rustfmt::skip]
+        let arrow = pack(&[
+            0, 0, 1, // width 4
+            1, 0, 0, // height 1
+            0, 1, // x 0
+            1, 1, // y 1
+            1, 0, 1, 1, // delta x 5
+            0, 0, 1, 1, 0, // 0 zeros, 3 ones
+            0, 0, 1, 0, 0, // 0 zeros, 1 one
+        ]);
+
+        let mut font = vec![
+            3, 0, 2, 2, 3, 3, 2, 2, 4, // glyph count, mode, bit widths
+            4, 5, 0, 0xFF, // max width, max height, x offset, y offset
+            3, 0xFF, 3, 0xFF, // ascent and descent of `A`, `g` and `(`
+            0, 0, 0, 0, 0, 0, // start positions
+        ];
+
+        for (encoding, data) in [(b' ', space), (b'A', a)] {
+            font.extend([encoding, data.len() as u8 + 2]);
+            font.extend(data);
+        }
+        font.extend([0, 0]);
+
+        let unicode = font.len() - HEADER_SIZE;
+        font[21..23].copy_from_slice(&(unicode as u16).to_be_bytes());
+        font.extend([0, 4, 0xFF, 0xFF]);
+        font.extend(0x2192u16.to_be_bytes());
+        font.push(arrow.len() as u8 + 3);
+        font.extend(arrow);
+        font.extend([0, 0]);
+
+        font
+    }
+
+    #[test]
+    fn parse() {
+        let font = Font::parse_u8g2(&test_font()).unwrap();
+
+        assert_eq!(
+            font.metrics,
+            Metrics {
+                ascent: 4,
+                descent: 1
+            }
+        );
+        assert_eq!(font.glyphs.len(), 3);
+
+        let a = font.glyphs.get('A').unwrap();
+        assert_eq!(a.name, "U+0041");
+        assert_eq!(
+            a.bounding_box,
+            BoundingBox {
+                offset: Coord::new(0, 0),
+                size: Coord::new(3, 3),
+            }
+        );
+        assert_eq!(a.bitmap, [0b0100_0000, 0b1010_0000, 0b1110_0000]);
+        assert_eq!(
+            a.width_horizontal,
+            Some(GlyphWidth {
+                scalable: Coord::new(800, 0),
+                device: Coord::new(4, 0),
+            })
+        );
+
+        let space = font.glyphs.get(' ').unwrap();
+        assert_eq!(space.bounding_box, BoundingBox::default());
+        assert!(space.bitmap.is_empty());
+        assert_eq!(space.width_horizontal.unwrap().device, Coord::new(2, 0));
+
+        let arrow = font.glyphs.get('→').unwrap();
+        assert_eq!(
+            arrow.bounding_box,
+            BoundingBox {
+                offset: Coord::new(0, 1),
+                size: Coord::new(4, 1),
+            }
+        );
+        assert_eq!(arrow.bitmap, [0xF0]);
+        assert_eq!(arrow.width_horizontal.unwrap().device, Coord::new(5, 0));
+
+        assert_eq!(
+            font.metadata.properties.try_get::<i32>(Property::PixelSize),
+            Ok(Some(5))
+        );
+        assert_eq!(
+            font.metadata
+                .properties
+                .try_get::<String>(Property::CharsetRegistry),
+            Ok(Some("ISO10646".to_string()))
+        );
+        assert_eq!(Font::parse(&font.to_bdf()).unwrap(), font);
+    }
+
+    #[test]
+    fn parse_source() {
+        let data = test_font();
+
+        let octal = |bytes: &[u8]| {
+            bytes
+                .iter()
+                .map(|byte| format!("\\{byte:o}"))
+                .collect::<String>()
+        };
+        let (first, second) = data.split_at(data.len() / 2);
+        let (first, second) = (octal(first), octal(second));
+        let hex = data
+            .iter()
+            .map(|byte| format!("0x{byte:02x}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let source = format!(
+            "#include \"u8g2.h\"\n\
+            /* uint8_t commented_out[] = {{ 0 }}; */\n\
+            static const uint8_t SCALE = 2;\n\
+            const uint8_t u8g2_font_test_tf[{}] U8G2_FONT_SECTION(\"u8g2_font_test_tf\") =\n  \
+            \"{first}\"\n  \"{second}\";\n\
+            // Initializer list\n\
+            const uint8_t font_list[] = {{ {hex}, }};\n",
+            data.len() + 1
+        );
+
+        let fonts = Font::parse_u8g2_source(&source).unwrap();
+        let names = fonts
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["u8g2_font_test_tf", "font_list"]);
+
+        let expected = Font::parse_u8g2(&data).unwrap();
+        for (_, font) in fonts {
+            assert_eq!(font, expected);
+        }
+    }
+
+    #[test]
+    fn escape_sequences() {
+        assert_eq!(
+            parse_string_literals(r#""a\n\0\377\x41\"" "?""#),
+            Ok(vec![b'a', b'\n', 0, 0xFF, 0x41, b'"', b'?', 0])
+        );
+        assert_eq!(
+            parse_initializer("{ 'A', '\\n', 017, 0b11, 255u }"),
+            Ok(vec![b'A', b'\n', 15, 3, 255])
+        );
+        assert!(parse_initializer("{ 256 }").is_err());
+        assert!(parse_string_literals("\"unterminated").is_err());
+    }
+
+    #[test]
+    fn invalid_fonts() {
+        for (data, message) in [
+            (vec![0; 10], "u8g2 font header is too short"),
+            (vec![0; HEADER_SIZE], "unexpected end of u8g2 font data"),
+        ] {
+            assert_eq!(
+                Font::parse_u8g2(&data),
+                Err(ParserError::new(message)),
+                "{message}"
+            );
+        }
+
+        let mut data = test_font();
+        data[HEADER_SIZE + 1] = 3;
+        assert_eq!(
+            Font::parse_u8g2(&data),
+            Err(ParserError::new("unexpected end of u8g2 glyph data"))
+        );
+
+        assert_eq!(
+            Font::parse_u8g2_source("int x = 1;"),
+            Err(ParserError::new("no uint8_t arrays found"))
+        );
+    }
+}
@@ -0,0 +1,209 @@
+use std::{collections::BTreeMap, fmt::Write};
+
+use crate::{BoundingBox, Coord, Encoding, Font, MetricsSet, Property, PropertyValue};
+
+impl Font {
+    /// Converts the font into a BDF file.
+    ///
+    /// The `FONT_ASCENT` and `FONT_DESCENT` properties are set to the values
+    /// in [`metrics`](Self::metrics) and all other properties are written in
+    /// alphabetical order. Fonts with more than 1 bit per pixel are written as
+    /// BDF 2.3 files, which include the bits per pixel in the `SIZE` line.
+    pub fn to_bdf(&self) -> String {
+        let mut output = String::new();
+        let metadata = &self.metadata;
+
+        let version = if metadata.bits_per_pixel != 1 {
+            "2.3"
+        } else {
+            "2.1"
+        };
+        writeln!(output, "STARTFONT {version}").unwrap();
+        writeln!(output, "FONT {}", metadata.name).unwrap();
+        write!(
+            output,
+            "SIZE {} {} {}",
+            metadata.point_size, metadata.resolution.x, metadata.resolution.y
+        )
+        .unwrap();
+        if metadata.bits_per_pixel != 1 {
+            write!(output, " {}", metadata.bits_per_pixel).unwrap();
+        }
+        output.push('\n');
+        write_bounding_box(&mut output, "FONTBOUNDINGBOX", &metadata.bounding_box);
+        match metadata.metrics_set {
+            MetricsSet::Horizontal => {}
+            MetricsSet::Vertical => writeln!(output, "METRICSSET 1").unwrap(),
+            MetricsSet::Both => writeln!(output, "METRICSSET 2").unwrap(),
+        }
+
+        let mut properties = metadata
+            .properties
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.clone()))
+            .collect::<BTreeMap<_, _>>();
+        for (property, value) in [
+            (Property::FontAscent, self.metrics.ascent),
+            (Property::FontDescent, self.metrics.descent),
+        ] {
+            properties.insert(property.to_string(), PropertyValue::Int(value as i32));
+        }
+
+        writeln!(output, "STARTPROPERTIES {}", properties.len()).unwrap();
+        for (name, value) in &properties {
+            writeln!(output, "{name} {value}").unwrap();
+        }
+        writeln!(output, "ENDPROPERTIES").unwrap();
+
+        writeln!(output, "CHARS {}", self.glyphs.len()).unwrap();
+        for glyph in self.glyphs.iter() {
+            writeln!(output, "STARTCHAR {}", glyph.name).unwrap();
+            match glyph.encoding {
+                Encoding::Standard(code_point) => {
+                    writeln!(output, "ENCODING {code_point}").unwrap()
+                }
+                Encoding::NonStandard(code_point) => {
+                    writeln!(output, "ENCODING -1 {code_point}").unwrap()
+                }
+                Encoding::Unspecified => writeln!(output, "ENCODING -1").unwrap(),
+            }
+            if let Some(width) = glyph.width_horizontal {
+                write_coord(&mut output, "SWIDTH", width.scalable);
+                write_coord(&mut output, "DWIDTH", width.device);
+            }
+            if let Some(width) = glyph.width_vertical {
+                write_coord(&mut output, "SWIDTH1", width.scalable);
+                write_coord(&mut output, "DWIDTH1", width.device);
+            }
+            if let Some(origin_offset) = glyph.origin_offset {
+                write_coord(&mut output, "VVECTOR", origin_offset);
+            }
+            write_bounding_box(&mut output, "BBX", &glyph.bounding_box);
+
+            writeln!(output, "BITMAP").unwrap();
+            let height = glyph.bounding_box.size.y.max(0) as usize;
+            if height > 0 {
+                for row in glyph
+                    .bitmap
+                    .chunks(glyph.bitmap.len().div_ceil(height).max(1))
+                {
+                    for byte in row {
+                        write!(output, "{byte:02X}").unwrap();
+                    }
+                    output.push('\n');
+                }
+            }
+            writeln!(output, "ENDCHAR").unwrap();
+        }
+
+        writeln!(output, "ENDFONT").unwrap();
+
+        output
+    }
+}
+
+fn write_coord(output: &mut String, keyword: &str, coord: Coord) {
+    writeln!(output, "{keyword} {} {}", coord.x, coord.y).unwrap();
+}
+
+fn write_bounding_box(output: &mut String, keyword: &str, bounding_box: &BoundingBox) {
+    writeln!(
+        output,
+        "{keyword} {} {} {} {}",
+        bounding_box.size.x, bounding_box.size.y, bounding_box.offset.x, bounding_box.offset.y
+    )
+    .unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    const FONT: &str = indoc! {r#"
+        STARTFONT 2.1
+        FONT -test-Test-Medium-R-Normal--8-80-75-75-C-60-ISO10646-1
+        SIZE 8 75 75
+        FONTBOUNDINGBOX 6 8 0 -2
+        STARTPROPERTIES 4
+        COPYRIGHT "Some ""quoted"" text"
+        FONT_ASCENT 6
+        FONT_DESCENT 2
+        PIXEL_SIZE 8
+        ENDPROPERTIES
+        CHARS 3
+        STARTCHAR A
+        ENCODING 65
+        SWIDTH 750 0
+        DWIDTH 6 0
+        BBX 5 6 0 0
+        BITMAP
+        20
+        50
+        88
+        F8
+        88
+        88
+        ENDCHAR
+        STARTCHAR space
+        ENCODING -1 200
+        SWIDTH 750 0
+        DWIDTH 6 0
+        BBX 0 0 0 0
+        BITMAP
+        ENDCHAR
+        STARTCHAR wide
+        ENCODING -1
+        SWIDTH 1500 0
+        DWIDTH 12 0
+        BBX 10 2 1 -2
+        BITMAP
+        FFC0
+        8040
+        ENDCHAR
+        ENDFONT
+    "#};
+
+    #[test]
+    fn round_trip() {
+        let font = Font::parse(FONT).unwrap();
+        let output = font.to_bdf();
+
+        assert_eq!(Font::parse(&output).unwrap(), font);
+    }
+
+    #[test]
+    fn ascent_and_descent_are_taken_from_metrics() {
+        let mut font = Font::parse(FONT).unwrap();
+        font.metrics.ascent = 7;
+
+        let output = font.to_bdf();
+        assert!(output.contains("FONT_ASCENT 7\n"), "{output}");
+        assert_eq!(Font::parse(&output).unwrap().metrics, font.metrics);
+    }
+
+    #[test]
+    fn vertical_metrics_and_bits_per_pixel() {
+        let mut font = Font::parse(FONT).unwrap();
+        font.metadata.metrics_set = MetricsSet::Both;
+        font.metadata.bits_per_pixel = 2;
+        font.glyphs = font
+            .glyphs
+            .iter()
+            .cloned()
+            .map(|mut glyph| {
+                glyph.width_vertical = glyph.width_horizontal;
+                glyph.origin_offset = Some(Coord::new(3, 7));
+                glyph
+            })
+            .collect();
+
+        let output = font.to_bdf();
+        assert!(output.starts_with("STARTFONT 2.3\n"), "{output}");
+        assert!(output.contains("SIZE 8 75 75 2\n"), "{output}");
+        assert!(output.contains("METRICSSET 2\n"), "{output}");
+        assert!(output.contains("VVECTOR 3 7\n"), "{output}");
+        assert_eq!(Font::parse(&output).unwrap(), font);
+    }
+}
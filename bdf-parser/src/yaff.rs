@@ -0,0 +1,1226 @@
+//! monobit YAFF fonts.
+//!
+//! See https://github.com/robhagemans/monobit/blob/master/YAFF.md for a
+//! description of the file format.
+
+use std::{collections::BTreeMap, fmt::Write};
+
+use thiserror::Error;
+
+use crate::{
+    glyph::{calculate_swidth, pixels_to_bitmap},
+    properties::PropertyValue,
+    BoundingBox, Coord, Encoding, Font, Glyph, GlyphWidth, Glyphs, Metadata, Metrics, ParserError,
+    Property,
+};
+
+/// YAFF export error.
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum YaffError {
+    /// A glyph name can't be stored in a tag label.
+    ///
+    /// Tag labels can't contain double quotes or control characters and
+    /// can't end with a colon.
+    #[error("glyph name \"{0}\" can't be stored in a YAFF tag label")]
+    InvalidTagName(String),
+
+    /// The font uses more than one bit per pixel.
+    #[error("unsupported bits per pixel value {0}, YAFF fonts must be monochrome")]
+    UnsupportedBitsPerPixel(u32),
+}
+
+/// YAFF properties which are stored in BDF properties.
+const PROPERTIES: &[(&str, Property)] = &[
+    ("foundry", Property::Foundry),
+    ("family", Property::FamilyName),
+    ("weight", Property::WeightName),
+    ("slant", Property::Slant),
+    ("setwidth", Property::SetwidthName),
+    ("style", Property::AddStyleName),
+    ("spacing", Property::Spacing),
+    ("pixel-size", Property::PixelSize),
+    ("cap-height", Property::CapHeight),
+    ("x-height", Property::XHeight),
+    ("default-char", Property::DefaultChar),
+    ("copyright", Property::Copyright),
+    ("notice", Property::Notice),
+];
+
+/// BDF properties which are derived from other YAFF properties.
+const DERIVED_PROPERTIES: &[Property] = &[
+    Property::PointSize,
+    Property::ResolutionX,
+    Property::ResolutionY,
+    Property::FontAscent,
+    Property::FontDescent,
+    Property::CharsetRegistry,
+    Property::CharsetEncoding,
+];
+
+/// BDF and YAFF values of the slant and spacing properties.
+const VALUES: &[(Property, &str, &str)] = &[
+    (Property::Slant, "R", "roman"),
+    (Property::Slant, "I", "italic"),
+    (Property::Slant, "O", "oblique"),
+    (Property::Spacing, "P", "proportional"),
+    (Property::Spacing, "M", "monospace"),
+    (Property::Spacing, "C", "character-cell"),
+];
+
+/// Indentation of glyph definitions in the output.
+const INDENT: &str = "    ";
+
+impl Font {
+    /// Parses a monobit YAFF file.
+    ///
+    /// Glyphs with character labels (`'A':`) or Unicode labels (`u+0041:`) use
+    /// [`Encoding::Standard`]. Codepoint labels (`0x41:`) are interpreted as
+    /// Unicode code points if the `encoding` property of the font is `unicode`.
+    /// If the font uses another encoding, codepoint labels take precedence over
+    /// character labels and use [`Encoding::Standard`]. Fonts without an
+    /// `encoding` property use [`Encoding::NonStandard`] for codepoint labels,
+    /// if a glyph has no character label. The tag label
+    /// (`"A":`) of a glyph is used as the glyph name. A glyph with multiple
+    /// character labels is added to the font once for each character.
+    ///
+    /// The global and per-glyph `shift-up`, `left-bearing` and `right-bearing`
+    /// metrics are converted into the glyph bounding boxes and device widths.
+    /// The XLFD related YAFF properties are converted into the corresponding
+    /// BDF properties and all other properties are converted into BDF
+    /// properties by changing the name to uppercase and replacing dashes with
+    /// underscores. Unknown per-glyph properties are ignored.
+    pub fn parse_yaff(input: &str) -> Result<Self, ParserError> {
+        let mut file = YaffFile::parse(input)?;
+
+        let shift_up = file.take_int("shift-up")?.unwrap_or(0);
+        let left_bearing = file.take_int("left-bearing")?.unwrap_or(0);
+        let right_bearing = file.take_int("right-bearing")?.unwrap_or(0);
+
+        let encoding = file.take("encoding");
+        let unicode = encoding.as_ref().is_some_and(|(value, _)| {
+            ["unicode", "ucs", "iso10646", "iso10646-1"].contains(&value.to_lowercase().as_str())
+        });
+
+        let mut glyphs = BTreeMap::new();
+        for (index, glyph) in file.glyphs.iter().enumerate() {
+            let chars = glyph
+                .labels
+                .iter()
+                .filter_map(|label| match label {
+                    Label::Char(c) => Some(Encoding::Standard(*c)),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            let codepoints = glyph
+                .labels
+                .iter()
+                .filter_map(|label| match label {
+                    Label::Codepoint(c) if encoding.is_some() => Some(Encoding::Standard(*c)),
+                    Label::Codepoint(c) => Some(Encoding::NonStandard(*c)),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+
+            let (preferred, fallback) = if encoding.is_some() && !unicode {
+                (codepoints, chars)
+            } else {
+                (chars, codepoints)
+            };
+            let mut encodings = if preferred.is_empty() {
+                fallback
+            } else {
+                preferred
+            };
+            if encodings.is_empty() {
+                encodings.push(Encoding::Unspecified);
+            }
+
+            let tag = glyph.labels.iter().find_map(|label| match label {
+                Label::Tag(tag) => Some(tag),
+                _ => None,
+            });
+
+            let width = glyph.width as i32;
+            let left_bearing = glyph.left_bearing.unwrap_or(left_bearing);
+            let right_bearing = glyph.right_bearing.unwrap_or(right_bearing);
+
+            for &encoding in &encodings {
+                let name = match tag {
+                    Some(tag) if encodings.len() == 1 => tag.clone(),
+                    _ => default_name(encoding, index),
+                };
+
+                let key = match encoding {
+                    Encoding::Unspecified => Err(name.clone()),
+                    _ => Ok(encoding),
+                };
+                if glyphs.contains_key(&key) {
+                    return Err(ParserError::with_line_number(
+                        "duplicate glyph label",
+                        glyph.line_number,
+                    ));
+                }
+
+                glyphs.insert(
+                    key,
+                    Glyph {
+                        name,
+                        encoding,
+                        width_horizontal: Some(GlyphWidth {
+                            scalable: Coord::default(),
+                            device: Coord::new(left_bearing + width + right_bearing, 0),
+                        }),
+                        width_vertical: None,
+                        bounding_box: BoundingBox {
+                            offset: Coord::new(left_bearing, glyph.shift_up.unwrap_or(shift_up)),
+                            size: Coord::new(width, glyph.height as i32),
+                        },
+                        origin_offset: None,
+                        bitmap: pixels_to_bitmap(glyph.width, glyph.height, |x, y| {
+                            glyph.pixels[x + y * glyph.width]
+                        }),
+                    },
+                );
+            }
+        }
+        let glyphs = glyphs.into_values().collect::<Glyphs>();
+
+        let visible_glyphs = || {
+            glyphs
+                .iter()
+                .filter(|glyph| glyph.bounding_box.size.x > 0 && glyph.bounding_box.size.y > 0)
+        };
+        let ascent = match file.take_int("ascent")? {
+            Some(ascent) => ascent,
+            None => visible_glyphs()
+                .map(|glyph| glyph.bounding_box.offset.y + glyph.bounding_box.size.y)
+                .max()
+                .unwrap_or_default(),
+        };
+        let descent = match file.take_int("descent")? {
+            Some(descent) => descent,
+            None => visible_glyphs()
+                .map(|glyph| -glyph.bounding_box.offset.y)
+                .max()
+                .unwrap_or_default(),
+        };
+        let metrics = Metrics {
+            ascent: ascent.max(0) as u32,
+            descent: descent.max(0) as u32,
+        };
+
+        let pixel_size = match file
+            .get("pixel-size")
+            .map(|(value, _)| value.parse::<u32>())
+        {
+            Some(Ok(pixel_size)) => pixel_size,
+            _ => metrics.ascent + metrics.descent,
+        };
+        let all_unicode = glyphs
+            .iter()
+            .all(|glyph| matches!(glyph.encoding, Encoding::Standard(_)));
+
+        let mut metadata =
+            Metadata::for_imported_font(&glyphs, &metrics, pixel_size.max(1), all_unicode);
+        let properties = &mut metadata.properties;
+
+        if let Some((name, _)) = file.take("name") {
+            metadata.name = name;
+        }
+
+        if let Some((point_size, line_number)) = file.take("point-size") {
+            metadata.point_size = point_size
+                .parse::<i32>()
+                .ok()
+                .filter(|point_size| *point_size > 0)
+                .ok_or_else(|| ParserError::with_line_number("invalid point size", line_number))?;
+            properties.set(
+                Property::PointSize,
+                PropertyValue::Int(metadata.point_size * 10),
+            );
+        }
+
+        if let Some((dpi, line_number)) = file.take("dpi") {
+            let values = dpi
+                .split_whitespace()
+                .map(|value| value.parse::<i32>().ok().filter(|value| *value > 0))
+                .collect::<Option<Vec<_>>>();
+            metadata.resolution = match values.as_deref() {
+                Some(&[dpi]) => Coord::new(dpi, dpi),
+                Some(&[x, y]) => Coord::new(x, y),
+                _ => {
+                    return Err(ParserError::with_line_number(
+                        "invalid resolution",
+                        line_number,
+                    ))
+                }
+            };
+            properties.set(
+                Property::ResolutionX,
+                PropertyValue::Int(metadata.resolution.x),
+            );
+            properties.set(
+                Property::ResolutionY,
+                PropertyValue::Int(metadata.resolution.y),
+            );
+        }
+
+        if let Some((encoding, _)) = encoding {
+            let (registry, encoding) = if unicode {
+                ("ISO10646".to_string(), Some("1"))
+            } else if let Some((registry, encoding)) = encoding.rsplit_once('-') {
+                (registry.to_uppercase(), Some(encoding))
+            } else {
+                (encoding.to_uppercase(), None)
+            };
+
+            properties.set(Property::CharsetRegistry, PropertyValue::Text(registry));
+            match encoding {
+                Some(encoding) => properties.set(
+                    Property::CharsetEncoding,
+                    PropertyValue::Text(encoding.to_string()),
+                ),
+                None => properties.remove(Property::CharsetEncoding),
+            }
+        }
+
+        for (key, property) in PROPERTIES {
+            let Some((value, _)) = file.take(key) else {
+                continue;
+            };
+
+            let value = match property {
+                Property::Slant | Property::Spacing => {
+                    let value = VALUES
+                        .iter()
+                        .find(|(p, _, yaff)| p == property && value.eq_ignore_ascii_case(yaff))
+                        .map_or(value, |(_, bdf, _)| bdf.to_string());
+                    PropertyValue::Text(value)
+                }
+                Property::DefaultChar => match Label::parse(&value) {
+                    Ok(Some(Label::Char(c) | Label::Codepoint(c))) => PropertyValue::Int(c as i32),
+                    _ => parse_value(&value),
+                },
+                _ => parse_value(&value),
+            };
+
+            properties.set(*property, value);
+        }
+
+        for (key, value, _) in file.properties {
+            properties.set_by_name(&key.to_uppercase().replace('-', "_"), parse_value(&value));
+        }
+
+        let glyphs = glyphs
+            .iter()
+            .cloned()
+            .map(|mut glyph| {
+                if let Some(width) = &mut glyph.width_horizontal {
+                    width.scalable = calculate_swidth(width.device, &metadata);
+                }
+                glyph
+            })
+            .collect();
+
+        Ok(Font {
+            metadata,
+            glyphs,
+            metrics,
+        })
+    }
+
+    /// Converts the font into the monobit YAFF format.
+    ///
+    /// If the `CHARSET_REGISTRY` property is `ISO10646`, glyphs with a standard
+    /// encoding are labeled with a character label or, for control and
+    /// whitespace characters, with a Unicode label. All other glyphs with a
+    /// standard or non standard encoding use a codepoint label. The glyph name is
+    /// added as a tag label, unless it matches the name which would be
+    /// generated by [`parse_yaff`](Self::parse_yaff).
+    ///
+    /// The most common `shift-up`, `left-bearing` and `right-bearing` values
+    /// are stored as global metrics and glyphs which use different metrics
+    /// contain per-glyph metrics.
+    ///
+    /// Glyph names which contain double quotes or control characters or end
+    /// with a colon can't be stored in a tag label and result in an error.
+    /// Grayscale fonts aren't supported, because YAFF glyphs only store
+    /// monochrome bitmaps.
+    pub fn to_yaff(&self) -> Result<String, YaffError> {
+        if self.metadata.bits_per_pixel != 1 {
+            return Err(YaffError::UnsupportedBitsPerPixel(
+                self.metadata.bits_per_pixel,
+            ));
+        }
+
+        let mut output = String::new();
+
+        write_property(&mut output, "name", &self.metadata.name);
+        write_property(
+            &mut output,
+            "point-size",
+            &self.metadata.point_size.to_string(),
+        );
+        let resolution = self.metadata.resolution;
+        let dpi = if resolution.x == resolution.y {
+            resolution.x.to_string()
+        } else {
+            format!("{} {}", resolution.x, resolution.y)
+        };
+        write_property(&mut output, "dpi", &dpi);
+        write_property(&mut output, "ascent", &self.metrics.ascent.to_string());
+        write_property(&mut output, "descent", &self.metrics.descent.to_string());
+
+        let properties = &self.metadata.properties;
+        let values = properties.iter().collect::<BTreeMap<_, _>>();
+
+        if let Ok(Some(registry)) = properties.try_get::<String>(Property::CharsetRegistry) {
+            let encoding = properties
+                .try_get::<String>(Property::CharsetEncoding)
+                .ok()
+                .flatten();
+            let encoding = match encoding {
+                _ if registry == "ISO10646" => "unicode".to_string(),
+                Some(encoding) => format!("{registry}-{encoding}").to_lowercase(),
+                None => registry.to_lowercase(),
+            };
+            write_property(&mut output, "encoding", &encoding);
+        }
+
+        for (key, property) in PROPERTIES {
+            let Some(value) = values.get(property.to_string().as_str()) else {
+                continue;
+            };
+
+            let value = match (property, value) {
+                (Property::Slant | Property::Spacing, PropertyValue::Text(text)) => VALUES
+                    .iter()
+                    .find(|(p, bdf, _)| p == property && text == bdf)
+                    .map_or_else(|| format_value(value), |(_, _, yaff)| yaff.to_string()),
+                _ => format_value(value),
+            };
+
+            write_property(&mut output, key, &value);
+        }
+
+        let other_properties = values.iter().filter(|(name, _)| {
+            !PROPERTIES
+                .iter()
+                .map(|(_, property)| property)
+                .chain(DERIVED_PROPERTIES)
+                .any(|property| property.to_string() == **name)
+        });
+        for (name, value) in other_properties {
+            write_property(
+                &mut output,
+                &name.to_lowercase().replace('_', "-"),
+                &format_value(value),
+            );
+        }
+
+        let metrics = self
+            .glyphs
+            .iter()
+            .map(GlyphMetrics::new)
+            .collect::<Vec<_>>();
+        let global = GlyphMetrics {
+            shift_up: most_common(metrics.iter().map(|metrics| metrics.shift_up)),
+            left_bearing: most_common(metrics.iter().map(|metrics| metrics.left_bearing)),
+            right_bearing: most_common(metrics.iter().map(|metrics| metrics.right_bearing)),
+        };
+        global.write(&mut output, "", &GlyphMetrics::default());
+
+        let unicode = self.metadata.is_unicode();
+        for (index, (glyph, metrics)) in self.glyphs.iter().zip(&metrics).enumerate() {
+            output.push('\n');
+
+            match glyph.encoding {
+                Encoding::Standard(code_point) if !unicode => {
+                    writeln!(output, "0x{code_point:02x}:").unwrap()
+                }
+                Encoding::Standard(code_point) => match char::from_u32(code_point) {
+                    Some(c) if !c.is_control() && !c.is_whitespace() => {
+                        writeln!(output, "'{c}':").unwrap()
+                    }
+                    _ => writeln!(output, "u+{code_point:04x}:").unwrap(),
+                },
+                Encoding::NonStandard(code_point) => {
+                    writeln!(output, "0x{code_point:02x}:").unwrap()
+                }
+                Encoding::Unspecified => {}
+            }
+            if glyph.encoding == Encoding::Unspecified
+                || glyph.name != default_name(glyph.encoding, index)
+            {
+                if glyph.name.contains(|c: char| c == '"' || c.is_control())
+                    || glyph.name.ends_with(':')
+                {
+                    return Err(YaffError::InvalidTagName(glyph.name.clone()));
+                }
+
+                writeln!(output, "\"{}\":", glyph.name).unwrap();
+            }
+
+            let size = glyph.bounding_box.size;
+            if size.x > 0 && size.y > 0 {
+                for y in 0..size.y as usize {
+                    output.push_str(INDENT);
+                    for x in 0..size.x as usize {
+                        let pixel = glyph.pixel(x, y).unwrap_or_default();
+                        output.push(if pixel { '@' } else { '.' });
+                    }
+                    output.push('\n');
+                }
+            } else {
+                writeln!(output, "{INDENT}-").unwrap();
+            }
+
+            metrics.write(&mut output, INDENT, &global);
+        }
+
+        Ok(output)
+    }
+}
+
+/// YAFF glyph metrics.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct GlyphMetrics {
+    shift_up: i32,
+    left_bearing: i32,
+    right_bearing: i32,
+}
+
+impl GlyphMetrics {
+    fn new(glyph: &Glyph) -> Self {
+        let bounding_box = glyph.bounding_box;
+
+        // Empty glyphs are written without a bitmap.
+        let width = if bounding_box.size.x > 0 && bounding_box.size.y > 0 {
+            bounding_box.size.x
+        } else {
+            0
+        };
+        let device_width = glyph
+            .width_horizontal
+            .map_or(bounding_box.offset.x + width, |width| width.device.x);
+
+        Self {
+            shift_up: bounding_box.offset.y,
+            left_bearing: bounding_box.offset.x,
+            right_bearing: device_width - bounding_box.offset.x - width,
+        }
+    }
+
+    /// Writes all metrics which differ from the defaults.
+    fn write(&self, output: &mut String, indent: &str, defaults: &Self) {
+        for (key, value, default) in [
+            ("shift-up", self.shift_up, defaults.shift_up),
+            ("left-bearing", self.left_bearing, defaults.left_bearing),
+            ("right-bearing", self.right_bearing, defaults.right_bearing),
+        ] {
+            if value != default {
+                writeln!(output, "{indent}{key}: {value}").unwrap();
+            }
+        }
+    }
+}
+
+/// Returns the most common value or the smallest value if there is a tie.
+fn most_common(values: impl Iterator<Item = i32>) -> i32 {
+    let mut counts = BTreeMap::new();
+    for value in values {
+        *counts.entry(value).or_insert(0) += 1;
+    }
+
+    counts
+        .into_iter()
+        .rev()
+        .max_by_key(|(_, count)| *count)
+        .map(|(value, _)| value)
+        .unwrap_or_default()
+}
+
+/// Returns the glyph name which is used for glyphs without a tag.
+fn default_name(encoding: Encoding, index: usize) -> String {
+    match encoding {
+        Encoding::Standard(code_point) => format!("U+{code_point:04X}"),
+        Encoding::NonStandard(code_point) => format!("char{code_point}"),
+        Encoding::Unspecified => format!("glyph{index}"),
+    }
+}
+
+fn write_property(output: &mut String, key: &str, value: &str) {
+    if value.contains('\n') {
+        writeln!(output, "{key}:").unwrap();
+        for line in value.lines() {
+            writeln!(output, "{INDENT}{line}").unwrap();
+        }
+    } else {
+        writeln!(output, "{key}: {value}").unwrap();
+    }
+}
+
+/// Formats a property value.
+///
+/// Text values are quoted if they would otherwise be parsed as an integer or
+/// if leading or trailing whitespace would be lost.
+fn format_value(value: &PropertyValue) -> String {
+    match value {
+        PropertyValue::Int(value) => value.to_string(),
+        PropertyValue::Text(text) => {
+            if text.is_empty()
+                || text.trim() != text
+                || text.starts_with('"')
+                || text.parse::<i32>().is_ok()
+            {
+                format!("\"{text}\"")
+            } else {
+                text.clone()
+            }
+        }
+    }
+}
+
+fn parse_value(value: &str) -> PropertyValue {
+    if let Some(text) = value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+    {
+        PropertyValue::Text(text.to_string())
+    } else if let Ok(value) = value.parse::<i32>() {
+        PropertyValue::Int(value)
+    } else {
+        PropertyValue::Text(value.to_string())
+    }
+}
+
+/// Parses an integer with an optional `0x`, `0o` or `0b` prefix.
+fn parse_int(value: &str) -> Option<u32> {
+    let (digits, radix) = if let Some(digits) = value.strip_prefix("0x") {
+        (digits, 16)
+    } else if let Some(digits) = value.strip_prefix("0o") {
+        (digits, 8)
+    } else if let Some(digits) = value.strip_prefix("0b") {
+        (digits, 2)
+    } else {
+        (value, 10)
+    };
+
+    u32::from_str_radix(digits, radix).ok()
+}
+
+/// Glyph label.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Label {
+    /// Unicode character.
+    Char(u32),
+    /// Codepoint in the font encoding.
+    Codepoint(u32),
+    /// Tag.
+    Tag(String),
+}
+
+impl Label {
+    /// Parses a label without the trailing colon.
+    ///
+    /// Returns `None` if the text isn't a label.
+    fn parse(label: &str) -> Result<Option<Self>, &'static str> {
+        if let Some(text) = label
+            .strip_prefix('\'')
+            .and_then(|label| label.strip_suffix('\''))
+        {
+            let mut chars = text.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Ok(Some(Self::Char(c.into()))),
+                _ => Err("character labels must contain a single character"),
+            }
+        } else if let Some(tag) = label
+            .strip_prefix('"')
+            .and_then(|label| label.strip_suffix('"'))
+        {
+            Ok(Some(Self::Tag(tag.to_string())))
+        } else if let Some(code_point) = label
+            .strip_prefix("u+")
+            .or_else(|| label.strip_prefix("U+"))
+        {
+            u32::from_str_radix(code_point, 16)
+                .ok()
+                .filter(|code_point| char::from_u32(*code_point).is_some())
+                .map(|code_point| Some(Self::Char(code_point)))
+                .ok_or("invalid unicode label")
+        } else if label.starts_with(|c: char| c.is_ascii_digit()) {
+            parse_int(&label.to_lowercase())
+                .map(|code_point| Some(Self::Codepoint(code_point)))
+                .ok_or("invalid codepoint label")
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+/// Glyph definition in a YAFF file.
+#[derive(Debug)]
+struct YaffGlyph {
+    labels: Vec<Label>,
+    width: usize,
+    height: usize,
+    pixels: Vec<bool>,
+    shift_up: Option<i32>,
+    left_bearing: Option<i32>,
+    right_bearing: Option<i32>,
+    line_number: usize,
+}
+
+/// Unprocessed content of a YAFF file.
+#[derive(Debug)]
+struct YaffFile {
+    /// Global properties with their line numbers.
+    properties: Vec<(String, String, usize)>,
+    glyphs: Vec<YaffGlyph>,
+}
+
+impl YaffFile {
+    fn parse(input: &str) -> Result<Self, ParserError> {
+        let mut lines = input
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line))
+            .filter(|(_, line)| !line.trim().is_empty() && !line.starts_with('#'))
+            .peekable();
+
+        let is_indented = |line: &str| line.starts_with([' ', '\t']);
+
+        let mut properties = Vec::new();
+        let mut glyphs = Vec::new();
+        let mut labels = Vec::new();
+
+        while let Some((line_number, line)) = lines.next() {
+            let mut block = Vec::new();
+            while let Some((line_number, line)) = lines.next_if(|(_, line)| is_indented(line)) {
+                block.push((line_number, line.trim()));
+            }
+
+            if is_indented(line) {
+                return Err(ParserError::with_line_number(
+                    "unexpected indentation",
+                    line_number,
+                ));
+            }
+
+            if let Some(label) = line.trim_end().strip_suffix(':') {
+                if let Some(label) = Label::parse(label)
+                    .map_err(|message| ParserError::with_line_number(message, line_number))?
+                {
+                    labels.push(label);
+
+                    if !block.is_empty() {
+                        glyphs.push(parse_glyph(
+                            std::mem::take(&mut labels),
+                            &block,
+                            line_number,
+                        )?);
+                    }
+                    continue;
+                }
+            }
+
+            if !labels.is_empty() {
+                return Err(ParserError::with_line_number(
+                    "expected glyph definition",
+                    line_number,
+                ));
+            }
+
+            let (key, value) = line.split_once(':').ok_or_else(|| {
+                ParserError::with_line_number("expected property or label", line_number)
+            })?;
+            let value = value.trim();
+
+            let value = if value.is_empty() {
+                block
+                    .iter()
+                    .map(|(_, line)| *line)
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            } else if block.is_empty() {
+                value.to_string()
+            } else {
+                return Err(ParserError::with_line_number(
+                    "unexpected indentation",
+                    block[0].0,
+                ));
+            };
+
+            properties.push((key.trim().to_lowercase(), value, line_number));
+        }
+
+        if !labels.is_empty() {
+            return Err(ParserError::new("expected glyph definition"));
+        }
+
+        Ok(Self { properties, glyphs })
+    }
+
+    /// Returns the value and line number of a global property.
+    fn get(&self, key: &str) -> Option<(&str, usize)> {
+        self.properties
+            .iter()
+            .find(|(k, _, _)| k == key)
+            .map(|(_, value, line_number)| (value.as_str(), *line_number))
+    }
+
+    /// Removes a global property and returns the value and line number.
+    fn take(&mut self, key: &str) -> Option<(String, usize)> {
+        let index = self.properties.iter().position(|(k, _, _)| k == key)?;
+        let (_, value, line_number) = self.properties.remove(index);
+
+        Some((value, line_number))
+    }
+
+    /// Removes a global integer property and returns the value.
+    fn take_int(&mut self, key: &str) -> Result<Option<i32>, ParserError> {
+        self.take(key)
+            .map(|(value, line_number)| {
+                value.parse().map_err(|_| {
+                    ParserError::with_line_number("expected integer value", line_number)
+                })
+            })
+            .transpose()
+    }
+}
+
+/// Parses the indented lines of a glyph definition.
+fn parse_glyph(
+    labels: Vec<Label>,
+    block: &[(usize, &str)],
+    line_number: usize,
+) -> Result<YaffGlyph, ParserError> {
+    let mut glyph = YaffGlyph {
+        labels,
+        width: 0,
+        height: 0,
+        pixels: Vec::new(),
+        shift_up: None,
+        left_bearing: None,
+        right_bearing: None,
+        line_number,
+    };
+
+    let rows = block
+        .iter()
+        .take_while(|(_, line)| !line.contains(':'))
+        .count();
+
+    match &block[..rows] {
+        [] => {
+            return Err(ParserError::with_line_number(
+                "expected glyph bitmap",
+                block[0].0,
+            ))
+        }
+        [(_, "-")] => {}
+        rows => {
+            glyph.width = rows[0].1.len();
+            for (line_number, row) in rows {
+                if row.len() != glyph.width {
+                    return Err(ParserError::with_line_number(
+                        "inconsistent bitmap width",
+                        *line_number,
+                    ));
+                }
+
+                for c in row.chars() {
+                    glyph.pixels.push(match c {
+                        '@' => true,
+                        '.' => false,
+                        _ => {
+                            return Err(ParserError::with_line_number(
+                                "invalid character in bitmap",
+                                *line_number,
+                            ))
+                        }
+                    });
+                }
+            }
+            glyph.height = rows.len();
+        }
+    }
+
+    for (line_number, line) in &block[rows..] {
+        let (key, value) = line.split_once(':').unwrap();
+        let metric = match key.trim() {
+            "shift-up" => &mut glyph.shift_up,
+            "left-bearing" => &mut glyph.left_bearing,
+            "right-bearing" => &mut glyph.right_bearing,
+            _ => continue,
+        };
+
+        *metric =
+            Some(value.trim().parse().map_err(|_| {
+                ParserError::with_line_number("expected integer value", *line_number)
+            })?);
+    }
+
+    Ok(glyph)
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    const YAFF: &str = indoc! {r#"
+        # Test font
+        name: Test Font
+        point-size: 8
+        dpi: 96
+        ascent: 6
+        descent: 2
+        encoding: unicode
+        family: Test
+        slant: italic
+        spacing: proportional
+        notice:
+            First line
+            Second line
+        converter-version: "1.0"
+        shift-up: -2
+
+        'A':
+        u+0391:
+            .@@.
+            @..@
+            @@@@
+            @..@
+            ....
+
+            right-bearing: 1
+
+        0x42:
+        "Bee":
+            @@.
+            @.@
+            shift-up: 0
+            left-bearing: 1
+            unknown-property: 5
+
+        "blank":
+            -
+            right-bearing: 4
+    "#};
+
+    #[test]
+    fn parse_yaff() {
+        let font = Font::parse_yaff(YAFF).unwrap();
+
+        assert_eq!(font.glyphs.len(), 4);
+        assert_eq!(
+            font.metrics,
+            Metrics {
+                ascent: 6,
+                descent: 2
+            }
+        );
+        assert_eq!(font.metadata.name, "Test Font");
+        assert_eq!(font.metadata.point_size, 8);
+        assert_eq!(font.metadata.resolution, Coord::new(96, 96));
+
+        let a = font.glyphs.get('A').unwrap();
+        assert_eq!(a.name, "U+0041");
+        assert_eq!(
+            a.bounding_box,
+            BoundingBox {
+                offset: Coord::new(0, -2),
+                size: Coord::new(4, 5),
+            }
+        );
+        assert_eq!(
+            a.width_horizontal,
+            Some(GlyphWidth {
+                scalable: Coord::new(468, 0),
+                device: Coord::new(5, 0),
+            })
+        );
+        assert_eq!(a.bitmap, [0x60, 0x90, 0xF0, 0x90, 0x00]);
+
+        let alpha = font.glyphs.get('Α').unwrap();
+        assert_eq!(alpha.name, "U+0391");
+        assert_eq!(alpha.bitmap, a.bitmap);
+
+        let b = font.glyphs.get('B').unwrap();
+        assert_eq!(b.name, "Bee");
+        assert_eq!(
+            b.bounding_box,
+            BoundingBox {
+                offset: Coord::new(1, 0),
+                size: Coord::new(3, 2),
+            }
+        );
+        assert_eq!(b.width_horizontal.unwrap().device, Coord::new(4, 0));
+        assert_eq!(b.bitmap, [0xC0, 0xA0]);
+
+        let blank = font.glyphs.iter().find(|g| g.name == "blank").unwrap();
+        assert_eq!(blank.encoding, Encoding::Unspecified);
+        assert_eq!(blank.bounding_box.size, Coord::new(0, 0));
+        assert_eq!(blank.width_horizontal.unwrap().device, Coord::new(4, 0));
+
+        let properties = &font.metadata.properties;
+        for (property, value) in [
+            (Property::FamilyName, "Test"),
+            (Property::Slant, "I"),
+            (Property::Spacing, "P"),
+            (Property::Notice, "First line\nSecond line"),
+            (Property::CharsetRegistry, "ISO10646"),
+            (Property::CharsetEncoding, "1"),
+        ] {
+            assert_eq!(
+                properties.try_get::<String>(property),
+                Ok(Some(value.to_string())),
+                "{property}"
+            );
+        }
+        for (property, value) in [
+            (Property::PointSize, 80),
+            (Property::ResolutionX, 96),
+            (Property::ResolutionY, 96),
+            (Property::FontAscent, 6),
+            (Property::FontDescent, 2),
+        ] {
+            assert_eq!(
+                properties.try_get::<i32>(property),
+                Ok(Some(value)),
+                "{property}"
+            );
+        }
+        assert_eq!(
+            properties.try_get_by_name::<String>("CONVERTER_VERSION"),
+            Ok(Some("1.0".to_string()))
+        );
+    }
+
+    #[test]
+    fn codepoint_labels() {
+        const YAFF: &str = indoc! {r#"
+            encoding: iso8859-5
+
+            0xb0:
+            u+0410:
+                @
+        "#};
+
+        let font = Font::parse_yaff(YAFF).unwrap();
+        assert_eq!(
+            font.glyphs.iter().next().unwrap().encoding,
+            Encoding::Standard(0xB0)
+        );
+
+        let font = Font::parse_yaff(YAFF.replace("encoding: iso8859-5\n", "").as_str()).unwrap();
+        assert_eq!(
+            font.glyphs.iter().next().unwrap().encoding,
+            Encoding::Standard(0x410)
+        );
+    }
+
+    #[test]
+    fn to_yaff() {
+        let font = Font::parse_yaff(YAFF).unwrap();
+
+        assert_eq!(
+            font.to_yaff().unwrap(),
+            indoc! {r#"
+                name: Test Font
+                point-size: 8
+                dpi: 96
+                ascent: 6
+                descent: 2
+                encoding: unicode
+                family: Test
+                slant: italic
+                spacing: proportional
+                pixel-size: 8
+                notice:
+                    First line
+                    Second line
+                average-width: 45
+                converter-version: 1.0
+                shift-up: -2
+                right-bearing: 1
+
+                'A':
+                    .@@.
+                    @..@
+                    @@@@
+                    @..@
+                    ....
+
+                'B':
+                "Bee":
+                    @@.
+                    @.@
+                    shift-up: 0
+                    left-bearing: 1
+                    right-bearing: 0
+
+                'Α':
+                    .@@.
+                    @..@
+                    @@@@
+                    @..@
+                    ....
+
+                "blank":
+                    -
+                    right-bearing: 4
+            "#}
+        );
+    }
+
+    #[test]
+    fn yaff_round_trip() {
+        let font = Font::parse_yaff(YAFF).unwrap();
+
+        assert_eq!(Font::parse_yaff(&font.to_yaff().unwrap()).unwrap(), font);
+    }
+
+    #[test]
+    fn invalid_tag_name() {
+        let font = Font::parse_yaff(YAFF).unwrap();
+
+        for name in ["\"quoted\"", "colon:", "new\nline"] {
+            let mut font = font.clone();
+            font.glyphs = font
+                .glyphs
+                .iter()
+                .cloned()
+                .map(|mut glyph| {
+                    if glyph.name == "Bee" {
+                        glyph.name = name.to_string();
+                    }
+                    glyph
+                })
+                .collect();
+
+            assert_eq!(
+                font.to_yaff(),
+                Err(YaffError::InvalidTagName(name.to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn bdf_round_trip() {
+        const BDF: &str = indoc! {r#"
+            STARTFONT 2.1
+            FONT -test-test-medium-r-normal--16-160-75-75-p-60-iso8859-1
+            SIZE 16 75 75
+            FONTBOUNDINGBOX 7 8 0 -2
+            STARTPROPERTIES 7
+            FOUNDRY "test"
+            WEIGHT_NAME "Medium"
+            SPACING "P"
+            FONT_ASCENT 6
+            FONT_DESCENT 2
+            CHARSET_REGISTRY "ISO8859"
+            CHARSET_ENCODING "1"
+            ENDPROPERTIES
+            CHARS 3
+            STARTCHAR space
+            ENCODING 32
+            SWIDTH 360 0
+            DWIDTH 6 0
+            BBX 0 0 0 0
+            BITMAP
+            ENDCHAR
+            STARTCHAR g
+            ENCODING 103
+            SWIDTH 360 0
+            DWIDTH 6 0
+            BBX 4 5 1 -2
+            BITMAP
+            70
+            90
+            70
+            10
+            60
+            ENDCHAR
+            STARTCHAR W
+            ENCODING 87
+            SWIDTH 480 0
+            DWIDTH 8 0
+            BBX 7 6 0 0
+            BITMAP
+            82
+            82
+            92
+            92
+            AA
+            44
+            ENDCHAR
+            ENDFONT
+        "#};
+
+        let bdf = Font::parse(BDF).unwrap();
+        let output = bdf.to_yaff().unwrap();
+        assert!(output.contains("\n0x67:\n"), "{output}");
+        assert!(!output.contains("'g':"), "{output}");
+
+        let yaff = Font::parse_yaff(&output).unwrap();
+
+        let diff = bdf.diff(&yaff);
+        assert_eq!(diff.added, []);
+        assert_eq!(diff.removed, []);
+        assert_eq!(diff.changed, []);
+        assert_eq!(diff.metrics, None);
+        assert_eq!(diff.bounding_box, None);
+        assert_eq!(yaff.metadata.name, bdf.metadata.name);
+        assert_eq!(yaff.metadata.point_size, 16);
+        assert_eq!(yaff.metadata.resolution, Coord::new(75, 75));
+        for property in [
+            Property::Foundry,
+            Property::WeightName,
+            Property::Spacing,
+            Property::CharsetRegistry,
+            Property::CharsetEncoding,
+        ] {
+            assert_eq!(
+                yaff.metadata.properties.try_get::<String>(property),
+                bdf.metadata.properties.try_get::<String>(property),
+                "{property}"
+            );
+        }
+    }
+
+    #[track_caller]
+    fn assert_error(input: &str, message: &str, line_number: usize) {
+        assert_eq!(
+            Font::parse_yaff(input),
+            Err(ParserError::with_line_number(message, line_number))
+        );
+    }
+
+    #[test]
+    fn invalid_files() {
+        assert_error("'A':\n    .@\n    @\n", "inconsistent bitmap width", 3);
+        assert_error("'A':\n    .x\n", "invalid character in bitmap", 2);
+        assert_error("'A':\nname: test\n", "expected glyph definition", 2);
+        assert_error("    .@\n", "unexpected indentation", 1);
+        assert_error(
+            "'AB':\n    .@\n",
+            "character labels must contain a single character",
+            1,
+        );
+        assert_error(
+            "'A':\n    @\n\nu+0041:\n    .\n",
+            "duplicate glyph label",
+            4,
+        );
+        assert_error("ascent: one\n'A':\n    @\n", "expected integer value", 1);
+        assert_error(
+            "'A':\n    @\n    shift-up: x\n",
+            "expected integer value",
+            3,
+        );
+        assert_error("test\n", "expected property or label", 1);
+    }
+}
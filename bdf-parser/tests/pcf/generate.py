@@ -0,0 +1,384 @@
+#!/usr/bin/env python3
+"""Generates the PCF test fixtures from the BDF fonts in `eg-bdf-examples`.
+
+All fixtures in this directory were written by this script. `bdftopcf` can
+produce the compressed metrics variants with the same format options:
+
+    6x10.pcf              bdftopcf -M -m -p4 -u1
+    6x10_pad2_unit2.pcf   bdftopcf -L -m -p2 -u2
+    10x20_pad8_unit4.pcf  bdftopcf -M -l -p8 -u4
+
+`bdftopcf` always uses compressed metrics if the metrics fit into a byte,
+which is why the uncompressed metrics variants can only be created by this
+script:
+
+    6x10_lsb_pad1.pcf          LSB byte and bit first, pad 1, unit 1
+    10x20_lsb_pad4_unit4.pcf   LSB byte and bit first, pad 4, unit 4
+
+Run from the repository root:
+
+    python3 bdf-parser/tests/pcf/generate.py
+"""
+
+import struct
+from pathlib import Path
+
+PCF_PROPERTIES = 1 << 0
+PCF_ACCELERATORS = 1 << 1
+PCF_METRICS = 1 << 2
+PCF_BITMAPS = 1 << 3
+PCF_INK_METRICS = 1 << 4
+PCF_BDF_ENCODINGS = 1 << 5
+PCF_SWIDTHS = 1 << 6
+PCF_GLYPH_NAMES = 1 << 7
+PCF_BDF_ACCELERATORS = 1 << 8
+
+PCF_DEFAULT_FORMAT = 0x000
+PCF_ACCEL_W_INKBOUNDS = 0x100
+PCF_COMPRESSED_METRICS = 0x100
+
+PCF_BYTE_MASK = 1 << 2
+PCF_BIT_MASK = 1 << 3
+
+EXAMPLES = Path("eg-bdf-examples/examples")
+OUTPUT = Path("bdf-parser/tests/pcf")
+
+SUBSET_6X10 = [0x00] + list(range(0x20, 0x7F)) + list(range(0x100, 0x180))
+SUBSET_10X20 = list(range(0x20, 0x7F))
+
+
+def parse_bdf(path, chars):
+    font = {"properties": [], "glyphs": []}
+    glyph = None
+    lines = iter(path.read_text(encoding="latin-1").splitlines())
+
+    for line in lines:
+        keyword, _, value = line.partition(" ")
+
+        if keyword == "FONT":
+            font["name"] = value
+        elif keyword == "STARTPROPERTIES":
+            for _ in range(int(value)):
+                name, _, value = next(lines).partition(" ")
+                if value.startswith('"'):
+                    value = value[1:-1].replace('""', '"')
+                else:
+                    value = int(value)
+                font["properties"].append((name, value))
+        elif keyword == "STARTCHAR":
+            glyph = {"name": value}
+        elif keyword == "ENCODING":
+            glyph["encoding"] = int(value)
+        elif keyword == "SWIDTH":
+            glyph["swidth"] = int(value.split()[0])
+        elif keyword == "DWIDTH":
+            glyph["dwidth"] = int(value.split()[0])
+        elif keyword == "BBX":
+            glyph["bbx"] = [int(v) for v in value.split()]
+        elif keyword == "BITMAP":
+            glyph["rows"] = [
+                bytes.fromhex(next(lines)) for _ in range(glyph["bbx"][1])
+            ]
+        elif keyword == "ENDCHAR":
+            if glyph["encoding"] in chars:
+                font["glyphs"].append(glyph)
+            glyph = None
+
+    # Remove the default char if it isn't included in the subset, like `Font::subset`.
+    encodings = [glyph["encoding"] for glyph in font["glyphs"]]
+    font["properties"] = [
+        (name, value)
+        for name, value in font["properties"]
+        if name != "DEFAULT_CHAR" or value in encodings
+    ]
+    font["properties"].append(("FONT", font["name"]))
+
+    return font
+
+
+class Writer:
+    def __init__(self, fmt):
+        self.fmt = fmt
+        self.endian = ">" if fmt & PCF_BYTE_MASK else "<"
+        self.data = bytearray(struct.pack("<I", fmt))
+
+    def pack(self, pattern, *values):
+        self.data += struct.pack(self.endian + pattern, *values)
+
+    def pad(self):
+        while len(self.data) % 4 != 0:
+            self.data.append(0)
+
+
+def glyph_metrics(glyph):
+    width, height, x, y = glyph["bbx"]
+    return (x, x + width, glyph["dwidth"], y + height, -y, 0)
+
+
+def ink_metrics(glyph):
+    width, height, x, y = glyph["bbx"]
+    set_pixels = [
+        (column, row)
+        for row, data in enumerate(glyph["rows"])
+        for column in range(width)
+        if data[column // 8] & (0x80 >> (column % 8))
+    ]
+
+    if not set_pixels:
+        return (0, 0, glyph["dwidth"], 0, 0, 0)
+
+    left = min(column for column, _ in set_pixels)
+    right = max(column for column, _ in set_pixels) + 1
+    top = min(row for _, row in set_pixels)
+    bottom = max(row for _, row in set_pixels) + 1
+
+    return (x + left, x + right, glyph["dwidth"], y + height - top, bottom - height - y, 0)
+
+
+def bounds(metrics):
+    minimum = tuple(min(m[i] for m in metrics) for i in range(6))
+    maximum = tuple(max(m[i] for m in metrics) for i in range(6))
+    return minimum, maximum
+
+
+def write_metrics(writer, metrics, compressed):
+    if compressed:
+        writer.pack("h", len(metrics))
+        for m in metrics:
+            writer.pack("5B", *(v + 0x80 for v in m[:5]))
+    else:
+        writer.pack("i", len(metrics))
+        for m in metrics:
+            writer.pack("5hH", *m)
+
+
+def properties_table(font, fmt):
+    writer = Writer(fmt)
+    strings = bytearray()
+
+    def string_offset(value):
+        offset = len(strings)
+        strings.extend(value.encode("latin-1") + b"\0")
+        return offset
+
+    writer.pack("i", len(font["properties"]))
+    for name, value in font["properties"]:
+        name_offset = string_offset(name)
+        if isinstance(value, str):
+            writer.pack("iBi", name_offset, 1, string_offset(value))
+        else:
+            writer.pack("iBi", name_offset, 0, value)
+    writer.pad()
+    writer.pack("i", len(strings))
+    writer.data += strings
+
+    return writer
+
+
+def accelerators_table(font, fmt):
+    properties = dict(font["properties"])
+    metrics = [glyph_metrics(glyph) for glyph in font["glyphs"]]
+    minbounds, maxbounds = bounds(metrics)
+    max_overlap = max(m[1] - m[2] for m in metrics)
+
+    writer = Writer(fmt)
+    writer.pack(
+        "8B",
+        int(max_overlap <= minbounds[0]),
+        int(minbounds == maxbounds),
+        0,
+        int(minbounds[2] == maxbounds[2]),
+        0,
+        0,
+        0,
+        0,
+    )
+    writer.pack(
+        "3i", properties["FONT_ASCENT"], properties["FONT_DESCENT"], max_overlap
+    )
+    writer.pack("5hH", *minbounds)
+    writer.pack("5hH", *maxbounds)
+    if fmt & PCF_ACCEL_W_INKBOUNDS:
+        ink_minbounds, ink_maxbounds = bounds(
+            [ink_metrics(glyph) for glyph in font["glyphs"]]
+        )
+        writer.pack("5hH", *ink_minbounds)
+        writer.pack("5hH", *ink_maxbounds)
+
+    return writer
+
+
+def metrics_table(metrics, fmt):
+    writer = Writer(fmt)
+    write_metrics(writer, metrics, fmt & PCF_COMPRESSED_METRICS)
+    return writer
+
+
+def reverse_bits(byte):
+    return int(f"{byte:08b}"[::-1], 2)
+
+
+def padded_row_size(glyph, pad):
+    return -(-glyph["bbx"][0] // (8 * pad)) * pad
+
+
+def glyph_bitmap(glyph, fmt):
+    pad = 1 << (fmt & 3)
+    unit = 1 << ((fmt >> 4) & 3)
+    data = bytearray()
+
+    for row in glyph["rows"]:
+        row = bytearray(row)
+        while len(row) % pad != 0:
+            row.append(0)
+
+        if not fmt & PCF_BIT_MASK:
+            row = bytearray(reverse_bits(byte) for byte in row)
+        if bool(fmt & PCF_BYTE_MASK) != bool(fmt & PCF_BIT_MASK):
+            row = b"".join(row[i : i + unit][::-1] for i in range(0, len(row), unit))
+
+        data += row
+
+    return data
+
+
+def bitmaps_table(font, fmt):
+    bitmaps = [glyph_bitmap(glyph, fmt) for glyph in font["glyphs"]]
+
+    sizes = [
+        sum(padded_row_size(glyph, 1 << pad) * glyph["bbx"][1] for glyph in font["glyphs"])
+        for pad in range(4)
+    ]
+
+    writer = Writer(fmt)
+    writer.pack("i", len(bitmaps))
+    offset = 0
+    for bitmap in bitmaps:
+        writer.pack("i", offset)
+        offset += len(bitmap)
+    writer.pack("4i", *sizes)
+    for bitmap in bitmaps:
+        writer.data += bitmap
+
+    return writer
+
+
+def encodings_table(font, fmt):
+    properties = dict(font["properties"])
+    encodings = [glyph["encoding"] for glyph in font["glyphs"]]
+    min_byte2 = min(e & 0xFF for e in encodings)
+    max_byte2 = max(e & 0xFF for e in encodings)
+    min_byte1 = min(e >> 8 for e in encodings)
+    max_byte1 = max(e >> 8 for e in encodings)
+
+    indices = [0xFFFF] * ((max_byte2 - min_byte2 + 1) * (max_byte1 - min_byte1 + 1))
+    for index, encoding in enumerate(encodings):
+        byte1, byte2 = encoding >> 8, encoding & 0xFF
+        indices[(byte1 - min_byte1) * (max_byte2 - min_byte2 + 1) + byte2 - min_byte2] = index
+
+    writer = Writer(fmt)
+    writer.pack(
+        "4hH",
+        min_byte2,
+        max_byte2,
+        min_byte1,
+        max_byte1,
+        properties.get("DEFAULT_CHAR", 0xFFFF) & 0xFFFF,
+    )
+    writer.pack(f"{len(indices)}H", *indices)
+
+    return writer
+
+
+def swidths_table(font, fmt):
+    writer = Writer(fmt)
+    writer.pack("i", len(font["glyphs"]))
+    for glyph in font["glyphs"]:
+        writer.pack("i", glyph["swidth"])
+
+    return writer
+
+
+def glyph_names_table(font, fmt):
+    strings = bytearray()
+
+    writer = Writer(fmt)
+    writer.pack("i", len(font["glyphs"]))
+    for glyph in font["glyphs"]:
+        writer.pack("i", len(strings))
+        strings += glyph["name"].encode("latin-1") + b"\0"
+    writer.pack("i", len(strings))
+    writer.data += strings
+
+    return writer
+
+
+def write_pcf(path, font, fmt, compressed_metrics=True, include_ink_metrics=False):
+    metrics_format = fmt | (PCF_COMPRESSED_METRICS if compressed_metrics else 0)
+    metrics = [glyph_metrics(glyph) for glyph in font["glyphs"]]
+
+    tables = [
+        (PCF_PROPERTIES, properties_table(font, fmt)),
+        (PCF_ACCELERATORS, accelerators_table(font, fmt)),
+        (PCF_METRICS, metrics_table(metrics, metrics_format)),
+        (PCF_BITMAPS, bitmaps_table(font, fmt)),
+    ]
+    if include_ink_metrics:
+        ink = [ink_metrics(glyph) for glyph in font["glyphs"]]
+        tables.append((PCF_INK_METRICS, metrics_table(ink, metrics_format)))
+    tables += [
+        (PCF_BDF_ENCODINGS, encodings_table(font, fmt)),
+        (PCF_SWIDTHS, swidths_table(font, fmt)),
+        (PCF_GLYPH_NAMES, glyph_names_table(font, fmt)),
+        (PCF_BDF_ACCELERATORS, accelerators_table(font, fmt | PCF_ACCEL_W_INKBOUNDS)),
+    ]
+
+    for _, table in tables:
+        table.pad()
+
+    header = bytearray(b"\1fcp" + struct.pack("<i", len(tables)))
+    offset = len(header) + 16 * len(tables)
+    for table_type, table in tables:
+        header += struct.pack("<4I", table_type, table.fmt, len(table.data), offset)
+        offset += len(table.data)
+
+    path.write_bytes(header + b"".join(table.data for _, table in tables))
+
+
+def pcf_format(msb_byte, msb_bit, glyph_pad, scan_unit):
+    return (
+        (PCF_BYTE_MASK if msb_byte else 0)
+        | (PCF_BIT_MASK if msb_bit else 0)
+        | [1, 2, 4, 8].index(glyph_pad)
+        | [1, 2, 4, 8].index(scan_unit) << 4
+    )
+
+
+def main():
+    font_6x10 = parse_bdf(EXAMPLES / "6x10.bdf", SUBSET_6X10)
+    font_10x20 = parse_bdf(EXAMPLES / "10x20.bdf", SUBSET_10X20)
+
+    write_pcf(OUTPUT / "6x10.pcf", font_6x10, pcf_format(True, True, 4, 1))
+    write_pcf(
+        OUTPUT / "6x10_lsb_pad1.pcf",
+        font_6x10,
+        pcf_format(False, False, 1, 1),
+        compressed_metrics=False,
+        include_ink_metrics=True,
+    )
+    write_pcf(
+        OUTPUT / "6x10_pad2_unit2.pcf", font_6x10, pcf_format(False, True, 2, 2)
+    )
+    write_pcf(
+        OUTPUT / "10x20_pad8_unit4.pcf", font_10x20, pcf_format(True, False, 8, 4)
+    )
+    write_pcf(
+        OUTPUT / "10x20_lsb_pad4_unit4.pcf",
+        font_10x20,
+        pcf_format(False, False, 4, 4),
+        compressed_metrics=False,
+    )
+
+
+if __name__ == "__main__":
+    main()
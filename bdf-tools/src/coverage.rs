@@ -0,0 +1,171 @@
+use std::{fmt::Write, ops::RangeInclusive};
+
+use clap::ValueEnum;
+use eg_font_converter::{Coverage, CoverageReport};
+use serde_json::json;
+
+/// Output format for coverage reports.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// Human readable table.
+    #[default]
+    Table,
+    /// JSON.
+    Json,
+}
+
+/// Formats a coverage report.
+pub fn format_report(report: &CoverageReport, format: Format) -> String {
+    match format {
+        Format::Table => format_table(report),
+        Format::Json => format_json(report),
+    }
+}
+
+fn format_table(report: &CoverageReport) -> String {
+    let mut output = String::new();
+
+    // The totals of Unicode blocks include unassigned code points.
+    for (title, total, coverages) in [
+        ("Unicode block", "Range", &report.blocks),
+        ("Mapping", "Total", &report.mappings),
+    ] {
+        let name_width = coverages
+            .iter()
+            .map(|coverage| coverage.name.len())
+            .chain(std::iter::once(title.len()))
+            .max()
+            .unwrap();
+
+        writeln!(
+            output,
+            "{title:<name_width$}  {:>7}  {:>7}  {:>6}  Missing",
+            "Covered", total, "%"
+        )
+        .unwrap();
+
+        for coverage in coverages.iter() {
+            let line = format!(
+                "{:<name_width$}  {:>7}  {:>7}  {:>6.1}  {}",
+                coverage.name,
+                coverage.covered(),
+                coverage.total,
+                coverage.percentage(),
+                format_ranges(&coverage.missing)
+            );
+            writeln!(output, "{}", line.trim_end()).unwrap();
+        }
+
+        output.push('\n');
+    }
+
+    output
+}
+
+fn format_json(report: &CoverageReport) -> String {
+    let coverages = |coverages: &[Coverage]| {
+        coverages
+            .iter()
+            .map(|coverage| {
+                json!({
+                    "name": coverage.name,
+                    "covered": coverage.covered(),
+                    "total": coverage.total,
+                    "missing": coverage
+                        .missing
+                        .iter()
+                        .map(|c| format!("U+{:04X}", u32::from(*c)))
+                        .collect::<Vec<_>>(),
+                })
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let json = json!({
+        "blocks": coverages(&report.blocks),
+        "mappings": coverages(&report.mappings),
+    });
+
+    let mut output = serde_json::to_string_pretty(&json).unwrap();
+    output.push('\n');
+    output
+}
+
+/// Formats a sorted list of code points as a list of ranges.
+fn format_ranges(chars: &[char]) -> String {
+    let mut ranges: Vec<RangeInclusive<u32>> = Vec::new();
+
+    for c in chars.iter().map(|c| u32::from(*c)) {
+        match ranges.last_mut() {
+            Some(range) if c == *range.end() + 1 => *range = *range.start()..=c,
+            _ => ranges.push(c..=c),
+        }
+    }
+
+    ranges
+        .iter()
+        .map(|range| {
+            if range.start() == range.end() {
+                format!("U+{:04X}", range.start())
+            } else {
+                format!("U+{:04X}-U+{:04X}", range.start(), range.end())
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> CoverageReport {
+        CoverageReport {
+            blocks: vec![Coverage {
+                name: "Basic Latin".to_string(),
+                total: 95,
+                missing: vec!['A', 'B', 'C', 'x'],
+            }],
+            mappings: vec![Coverage {
+                name: "ASCII".to_string(),
+                total: 96,
+                missing: vec!['A', 'B', 'C', 'x', '\x7F'],
+            }],
+        }
+    }
+
+    #[test]
+    fn ranges() {
+        assert_eq!(format_ranges(&[]), "");
+        assert_eq!(format_ranges(&['a']), "U+0061");
+        assert_eq!(
+            format_ranges(&['a', 'b', 'c', 'x', 'z']),
+            "U+0061-U+0063, U+0078, U+007A"
+        );
+    }
+
+    #[test]
+    fn table() {
+        assert_eq!(
+            format_report(&report(), Format::Table),
+            concat!(
+                "Unicode block  Covered    Range       %  Missing\n",
+                "Basic Latin         91       95    95.8  U+0041-U+0043, U+0078\n",
+                "\n",
+                "Mapping  Covered    Total       %  Missing\n",
+                "ASCII         91       96    94.8  U+0041-U+0043, U+0078, U+007F\n",
+                "\n",
+            )
+        );
+    }
+
+    #[test]
+    fn json() {
+        let json: serde_json::Value =
+            serde_json::from_str(&format_report(&report(), Format::Json)).unwrap();
+
+        assert_eq!(json["blocks"][0]["name"], "Basic Latin");
+        assert_eq!(json["blocks"][0]["covered"], 91);
+        assert_eq!(json["mappings"][0]["missing"][4], "U+007F");
+    }
+}
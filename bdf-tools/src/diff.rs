@@ -0,0 +1,189 @@
+use std::fmt::Write;
+
+use bdf_parser::{FontDiff, GlyphDiff, PropertyValue};
+
+use crate::glyph;
+
+/// Formats a font diff as human readable text.
+pub fn format_diff(diff: &FontDiff) -> String {
+    let mut output = String::new();
+
+    if diff.is_empty() {
+        output.push_str("Fonts are identical\n");
+        return output;
+    }
+
+    if let Some((old, new)) = diff.bounding_box {
+        writeln!(output, "Font bounding box: {old:?} -> {new:?}").unwrap();
+    }
+
+    if let Some((old, new)) = diff.metrics {
+        writeln!(
+            output,
+            "Metrics: ascent {} -> {}, descent {} -> {}",
+            old.ascent, new.ascent, old.descent, new.descent
+        )
+        .unwrap();
+    }
+
+    if let Some((old, new)) = diff.bits_per_pixel {
+        writeln!(output, "Bits per pixel: {old} -> {new}").unwrap();
+    }
+
+    if !diff.properties.is_empty() {
+        writeln!(output, "\nChanged properties ({}):", diff.properties.len()).unwrap();
+
+        for property in &diff.properties {
+            writeln!(
+                output,
+                "  {}: {} -> {}",
+                property.name,
+                format_value(property.old.as_ref()),
+                format_value(property.new.as_ref())
+            )
+            .unwrap();
+        }
+    }
+
+    if !diff.added.is_empty() {
+        writeln!(output, "\nAdded glyphs ({}):", diff.added.len()).unwrap();
+
+        for glyph in &diff.added {
+            writeln!(output, "  {}", glyph::label(glyph)).unwrap();
+        }
+    }
+
+    if !diff.removed.is_empty() {
+        writeln!(output, "\nRemoved glyphs ({}):", diff.removed.len()).unwrap();
+
+        for glyph in &diff.removed {
+            writeln!(output, "  {}", glyph::label(glyph)).unwrap();
+        }
+    }
+
+    if !diff.changed.is_empty() {
+        writeln!(output, "\nChanged glyphs ({}):", diff.changed.len()).unwrap();
+
+        for glyph in &diff.changed {
+            format_glyph_diff(&mut output, glyph);
+        }
+    }
+
+    output.trim_start().to_string()
+}
+
+fn format_value(value: Option<&PropertyValue>) -> String {
+    value.map_or_else(|| "(none)".to_string(), |value| value.to_string())
+}
+
+fn format_glyph_diff(output: &mut String, diff: &GlyphDiff) {
+    let mut changes = Vec::new();
+    if diff.name_changed() {
+        changes.push(format!("name {:?} -> {:?}", diff.old.name, diff.new.name));
+    }
+    if diff.bitmap_changed() {
+        changes.push("bitmap".to_string());
+    }
+    if diff.bounding_box_changed() {
+        changes.push(format!(
+            "bounding box {:?} -> {:?}",
+            diff.old.bounding_box, diff.new.bounding_box
+        ));
+    }
+    if diff.width_changed() {
+        changes.push(format!(
+            "width {:?} -> {:?}",
+            diff.old.width_horizontal, diff.new.width_horizontal
+        ));
+    }
+
+    writeln!(
+        output,
+        "\n  {}: {}",
+        glyph::label(&diff.new),
+        changes.join(", ")
+    )
+    .unwrap();
+
+    if !diff.bitmap_changed() {
+        return;
+    }
+
+    let bounding_box = diff.old.bounding_box.union(&diff.new.bounding_box);
+    let old = glyph::render(&diff.old, &bounding_box);
+    let new = glyph::render(&diff.new, &bounding_box);
+    let width = bounding_box.size.x.max(3) as usize;
+
+    writeln!(output, "    {:<width$}   new", "old").unwrap();
+    for (old, new) in old.iter().zip(new.iter()) {
+        writeln!(output, "    {old:<width$}   {new}").unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bdf_parser::{BoundingBox, Coord, Encoding, Font, Glyph};
+
+    fn glyph(bitmap: &[u8]) -> Glyph {
+        Glyph {
+            name: "A".to_string(),
+            encoding: Encoding::Standard(65),
+            bounding_box: BoundingBox {
+                offset: Coord::new(0, 0),
+                size: Coord::new(3, 3),
+            },
+            bitmap: bitmap.to_vec(),
+            ..Glyph::default()
+        }
+    }
+
+    #[test]
+    fn side_by_side() {
+        let diff = FontDiff {
+            added: Vec::new(),
+            removed: Vec::new(),
+            changed: vec![GlyphDiff {
+                old: glyph(&[0x40, 0xE0, 0xA0]),
+                new: glyph(&[0x40, 0xA0, 0xE0]),
+                bits_per_pixel: (1, 1),
+            }],
+            properties: Vec::new(),
+            bounding_box: None,
+            metrics: None,
+            bits_per_pixel: None,
+        };
+
+        assert_eq!(
+            format_diff(&diff),
+            concat!(
+                "Changed glyphs (1):\n",
+                "\n",
+                "  U+0041 'A' (A): bitmap\n",
+                "    old   new\n",
+                "    .#.   .#.\n",
+                "    ###   #.#\n",
+                "    #.#   ###\n",
+            )
+        );
+    }
+
+    #[test]
+    fn identical_fonts() {
+        let font = Font {
+            glyphs: [glyph(&[0x40, 0xE0, 0xA0])].into_iter().collect(),
+            metadata: bdf_parser::Metadata {
+                name: "test".to_string(),
+                point_size: 3,
+                resolution: Coord::new(75, 75),
+                bits_per_pixel: 1,
+                bounding_box: BoundingBox::default(),
+                metrics_set: Default::default(),
+                properties: Default::default(),
+            },
+            metrics: Default::default(),
+        };
+
+        assert_eq!(format_diff(&font.diff(&font)), "Fonts are identical\n");
+    }
+}
@@ -0,0 +1,94 @@
+use bdf_parser::{BoundingBox, Coord, Encoding, Glyph};
+
+/// Returns a human readable label for a glyph.
+pub fn label(glyph: &Glyph) -> String {
+    match glyph.encoding {
+        Encoding::Standard(index) => match char::from_u32(index) {
+            Some(c) if !c.is_control() => format!("U+{index:04X} '{c}' ({})", glyph.name),
+            _ => format!("U+{index:04X} ({})", glyph.name),
+        },
+        Encoding::NonStandard(index) => format!("non standard {index} ({})", glyph.name),
+        Encoding::Unspecified => format!("unspecified ({})", glyph.name),
+    }
+}
+
+/// Returns the state of a pixel in BDF coordinates.
+///
+/// Pixels outside the glyph bounding box are returned as unset.
+pub fn pixel_at(glyph: &Glyph, point: Coord) -> bool {
+    let BoundingBox { offset, size } = glyph.bounding_box;
+
+    let x = point.x - offset.x;
+    let y = offset.y + size.y - 1 - point.y;
+
+    if x < 0 || y < 0 || y >= size.y {
+        return false;
+    }
+
+    glyph.pixel(x as usize, y as usize).unwrap_or(false)
+}
+
+/// Renders a glyph as ASCII art.
+///
+/// The returned rows cover the given bounding box, which is specified in BDF
+/// coordinates. Set pixels are drawn as `#` and unset pixels as `.`.
+pub fn render(glyph: &Glyph, bounding_box: &BoundingBox) -> Vec<String> {
+    let BoundingBox { offset, size } = *bounding_box;
+
+    (0..size.y)
+        .rev()
+        .map(|y| {
+            (0..size.x)
+                .map(|x| {
+                    if pixel_at(glyph, Coord::new(offset.x + x, offset.y + y)) {
+                        '#'
+                    } else {
+                        '.'
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn glyph() -> Glyph {
+        Glyph {
+            name: "A".to_string(),
+            encoding: Encoding::Standard(65),
+            bounding_box: BoundingBox {
+                offset: Coord::new(1, -1),
+                size: Coord::new(3, 3),
+            },
+            bitmap: vec![0x40, 0xE0, 0xA0],
+            ..Glyph::default()
+        }
+    }
+
+    #[test]
+    fn render_glyph() {
+        let bounding_box = BoundingBox {
+            offset: Coord::new(0, -2),
+            size: Coord::new(5, 5),
+        };
+
+        assert_eq!(
+            render(&glyph(), &bounding_box),
+            [
+                ".....", //
+                "..#..", //
+                ".###.", //
+                ".#.#.", //
+                ".....", //
+            ]
+        );
+    }
+
+    #[test]
+    fn glyph_label() {
+        assert_eq!(label(&glyph()), "U+0041 'A' (A)");
+    }
+}
@@ -0,0 +1,147 @@
+//! Command line tools for BDF fonts.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, Context, Result};
+use bdf_parser::Font;
+use clap::{Parser, Subcommand};
+use eg_font_converter::{unicode_blocks, CoverageReport};
+
+mod coverage;
+mod diff;
+mod glyph;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about)]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Compare two BDF fonts glyph by glyph.
+    Diff {
+        /// Old BDF file.
+        old: PathBuf,
+
+        /// New BDF file.
+        new: PathBuf,
+    },
+    /// Print the glyph coverage by Unicode block and embedded-graphics mapping.
+    ///
+    /// The coverage of Unicode blocks is calculated against the whole code
+    /// point range of the block, including unassigned code points.
+    Coverage {
+        /// BDF file.
+        bdf_file: PathBuf,
+
+        /// Output format.
+        #[arg(long, value_enum, default_value_t)]
+        format: coverage::Format,
+
+        /// Include all Unicode blocks instead of only blocks which contain glyphs.
+        #[arg(long)]
+        all_blocks: bool,
+    },
+    /// Convert a u8g2 font C array into a BDF font.
+    U8g2ToBdf {
+        /// C source file which contains the u8g2 font array.
+        source_file: PathBuf,
+
+        /// Name of the font array, required if the file contains multiple arrays.
+        #[arg(long)]
+        array: Option<String>,
+
+        /// Output BDF file. The BDF data is written to stdout if no file is specified.
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+}
+
+fn read_font(path: &Path) -> Result<Font> {
+    let data =
+        std::fs::read(path).with_context(|| format!("couldn't read BDF file from {path:?}"))?;
+
+    let str = String::from_utf8_lossy(&data);
+    Font::parse(&str).with_context(|| format!("couldn't parse BDF file {path:?}"))
+}
+
+fn read_u8g2_font(path: &Path, array: Option<&str>) -> Result<Font> {
+    let source =
+        std::fs::read(path).with_context(|| format!("couldn't read source file from {path:?}"))?;
+    let source = String::from_utf8_lossy(&source);
+
+    let mut fonts = Font::parse_u8g2_source(&source)
+        .with_context(|| format!("couldn't parse u8g2 font in {path:?}"))?;
+
+    if let Some(array) = array {
+        fonts
+            .into_iter()
+            .find(|(name, _)| name == array)
+            .map(|(_, font)| font)
+            .ok_or_else(|| anyhow!("array \"{array}\" not found in {path:?}"))
+    } else if fonts.len() == 1 {
+        Ok(fonts.remove(0).1)
+    } else {
+        let names = fonts
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        bail!(
+            "{path:?} contains multiple arrays, use --array to select one of: {}",
+            names.join(", ")
+        )
+    }
+}
+
+fn run(args: &Args) -> Result<()> {
+    match &args.command {
+        Command::Diff { old, new } => {
+            let old = read_font(old)?;
+            let new = read_font(new)?;
+
+            print!("{}", diff::format_diff(&old.diff(&new)));
+        }
+        Command::Coverage {
+            bdf_file,
+            format,
+            all_blocks,
+        } => {
+            let font = read_font(bdf_file)?;
+
+            let report = if *all_blocks {
+                CoverageReport::with_blocks(&font, unicode_blocks())
+            } else {
+                CoverageReport::new(&font)
+            };
+
+            print!("{}", coverage::format_report(&report, *format));
+        }
+        Command::U8g2ToBdf {
+            source_file,
+            array,
+            output,
+        } => {
+            let bdf = read_u8g2_font(source_file, array.as_deref())?.to_bdf();
+
+            if let Some(output) = output {
+                std::fs::write(output, bdf)
+                    .with_context(|| format!("couldn't write BDF file {output:?}"))?;
+            } else {
+                print!("{bdf}");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn main() {
+    let args = Args::parse();
+
+    if let Err(e) = run(&args) {
+        eprintln!("Error: {e:#}");
+        std::process::exit(1);
+    }
+}
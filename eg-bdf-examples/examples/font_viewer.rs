@@ -144,12 +144,12 @@ fn try_main() -> Result<()> {
 
         for event in window.events() {
             match event {
-                SimulatorEvent::KeyDown { keycode, .. } => match keycode {
-                    Keycode::M => {
-                        use_mono_font = !use_mono_font;
-                    }
-                    _ => {}
-                },
+                SimulatorEvent::KeyDown {
+                    keycode: Keycode::M,
+                    ..
+                } => {
+                    use_mono_font = !use_mono_font;
+                }
                 SimulatorEvent::Quit => break 'main_loop,
                 _ => {}
             }
@@ -0,0 +1,465 @@
+//! Compile time font conversion for embedded-graphics.
+//!
+//! This crate provides the [`bdf_font!`] macro, which converts a BDF font
+//! while the crate is compiled. Unlike a build script, which uses
+//! [`eg-font-converter`] to save the generated code and data files to
+//! `OUT_DIR`, the macro expands directly to the generated constant, with the
+//! bitmap data inlined into the code.
+//!
+//! ```ignore
+//! use eg_font_converter_macros::bdf_font;
+//!
+//! bdf_font!(pub FONT_6X10, "fonts/6x10.bdf", glyphs = "A-Za-z0-9 ", kind = eg_bdf);
+//! ```
+//!
+//! [`eg-font-converter`]: eg_font_converter
+
+#![warn(missing_docs, missing_debug_implementations, unused)]
+#![deny(unsafe_code)]
+
+use std::path::PathBuf;
+
+use bdf_parser::Font;
+use eg_font_converter::{read_font_file, FontConverter, Mapping, Visibility};
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, ToTokens};
+use syn::{
+    parse::{Parse, ParseStream},
+    Error, Ident, LitChar, LitStr, Result, Token,
+};
+
+/// Converts a BDF font at compile time.
+///
+/// The macro expands to the same code as `EgBdfOutput::rust` or
+/// `MonoFontOutput::rust` in [`eg-font-converter`], with the bitmap data
+/// inlined into the generated code. The first two arguments are the name of
+/// the generated constant, optionally prefixed by a visibility, and the path
+/// to the font file. Relative paths are resolved relative to the directory
+/// which contains the `Cargo.toml` file of the crate that uses the macro.
+///
+/// The remaining arguments are optional and use a `key = value` syntax:
+///
+/// | Key                        | Value                                                            |
+/// |----------------------------|------------------------------------------------------------------|
+/// | `glyphs`                   | String with characters and ranges of characters, e.g. `"A-Z_"`   |
+/// | `mapping`                  | Name of an embedded-graphics mapping, e.g. `"ASCII"`             |
+/// | `kind`                     | `eg_bdf` for a `BdfFont` (default) or `mono_font` for a `MonoFont` |
+/// | `missing_glyph_substitute` | Character which is used for glyphs that aren't included in the font |
+/// | `replacement_character`    | Character which is drawn for characters without a glyph           |
+///
+/// A `-` between two characters in `glyphs` includes all characters in the
+/// inclusive range. To include the `-` character itself, place it at the
+/// start or end of the string. If `glyphs` and `mapping` are both omitted
+/// all glyphs in the font are included.
+///
+/// # Examples
+///
+/// ```ignore
+/// use eg_font_converter_macros::bdf_font;
+///
+/// bdf_font!(FONT_6X10, "fonts/6x10.bdf", glyphs = "A-Za-z0-9", kind = eg_bdf);
+///
+/// bdf_font!(
+///     pub(crate) FONT_6X10_MONO,
+///     "fonts/6x10.bdf",
+///     mapping = "ISO_8859_15",
+///     missing_glyph_substitute = '?',
+///     kind = mono_font,
+/// );
+/// ```
+///
+/// [`eg-font-converter`]: eg_font_converter
+#[proc_macro]
+pub fn bdf_font(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(input as BdfFontInput);
+
+    input
+        .expand()
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Output kind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    EgBdf,
+    MonoFont,
+}
+
+/// Parsed `bdf_font!` arguments.
+#[derive(Debug)]
+struct BdfFontInput {
+    visibility: syn::Visibility,
+    name: Ident,
+    file: LitStr,
+    glyphs: Option<LitStr>,
+    mapping: Option<LitStr>,
+    kind: Option<Ident>,
+    missing_glyph_substitute: Option<LitChar>,
+    replacement_character: Option<LitChar>,
+}
+
+impl Parse for BdfFontInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let visibility = input.parse()?;
+        let name = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let file = input.parse()?;
+
+        let mut output = Self {
+            visibility,
+            name,
+            file,
+            glyphs: None,
+            mapping: None,
+            kind: None,
+            missing_glyph_substitute: None,
+            replacement_character: None,
+        };
+
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+
+            let key: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+
+            match key.to_string().as_str() {
+                "glyphs" => set_option(&mut output.glyphs, &key, input.parse()?)?,
+                "mapping" => set_option(&mut output.mapping, &key, input.parse()?)?,
+                "kind" => set_option(&mut output.kind, &key, input.parse()?)?,
+                "missing_glyph_substitute" => {
+                    set_option(&mut output.missing_glyph_substitute, &key, input.parse()?)?
+                }
+                "replacement_character" => {
+                    set_option(&mut output.replacement_character, &key, input.parse()?)?
+                }
+                _ => return Err(Error::new(key.span(), format!("unknown option `{key}`"))),
+            }
+        }
+
+        Ok(output)
+    }
+}
+
+fn set_option<T>(option: &mut Option<T>, key: &Ident, value: T) -> Result<()> {
+    if option.is_some() {
+        return Err(Error::new(key.span(), format!("duplicate option `{key}`")));
+    }
+
+    *option = Some(value);
+
+    Ok(())
+}
+
+impl BdfFontInput {
+    fn expand(&self) -> Result<TokenStream> {
+        let kind = self.kind()?;
+        let visibility = self.constant_visibility()?;
+
+        let file = std::env::var_os("CARGO_MANIFEST_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_default()
+            .join(self.file.value());
+        let font =
+            read_font_file(&file).map_err(|e| Error::new(self.file.span(), format!("{e:#}")))?;
+
+        let mut converter = FontConverter::with_font(&font, &self.name.to_string())
+            .constant_visibility(visibility)
+            .inline_data(true);
+
+        if let Some(substitute) = &self.missing_glyph_substitute {
+            ensure_glyph(&font, substitute.value(), substitute.span())?;
+            converter = converter.missing_glyph_substitute(substitute.value());
+        }
+
+        let mut selected_glyphs = Vec::new();
+
+        if let Some(mapping) = &self.mapping {
+            let mapping_value = Mapping::iter()
+                .find(|m| m.mime() == mapping.value())
+                .ok_or_else(|| {
+                    Error::new(
+                        mapping.span(),
+                        format!("unknown mapping \"{}\"", mapping.value()),
+                    )
+                })?;
+
+            self.ensure_glyphs(&font, mapping_value.glyph_mapping().chars(), mapping.span())?;
+            selected_glyphs.extend(mapping_value.glyph_mapping().chars());
+            converter = converter.glyphs(mapping_value);
+        }
+
+        if let Some(glyphs) = &self.glyphs {
+            let chars = parse_glyphs(&glyphs.value()).map_err(|e| Error::new(glyphs.span(), e))?;
+
+            self.ensure_glyphs(&font, chars.iter().copied(), glyphs.span())?;
+            selected_glyphs.extend(chars.iter().copied());
+            converter = converter.glyphs(chars.as_slice());
+        }
+
+        if let Some(c) = &self.replacement_character {
+            ensure_replacement_character(&font, &selected_glyphs, c.value(), c.span())?;
+            converter = converter.replacement_character(c.value());
+        }
+
+        let rust = match kind {
+            Kind::EgBdf => converter.convert_eg_bdf().map(|font| font.rust()),
+            Kind::MonoFont => converter.convert_mono_font().map(|font| font.rust()),
+        }
+        .map_err(|e| Error::new(Span::call_site(), format!("{e:#}")))?;
+
+        let code = rust
+            .parse::<TokenStream>()
+            .map_err(|e| Error::new(Span::call_site(), e.to_string()))?;
+
+        // Include the font file to rebuild the crate if the file changes.
+        let file = file.to_string_lossy();
+
+        Ok(quote! {
+            #code
+
+            const _: &[u8] = include_bytes!(#file);
+        })
+    }
+
+    fn kind(&self) -> Result<Kind> {
+        match &self.kind {
+            Some(kind) if kind == "eg_bdf" => Ok(Kind::EgBdf),
+            Some(kind) if kind == "mono_font" => Ok(Kind::MonoFont),
+            Some(kind) => Err(Error::new(
+                kind.span(),
+                format!("unknown kind `{kind}`, expected `eg_bdf` or `mono_font`"),
+            )),
+            None => Ok(Kind::EgBdf),
+        }
+    }
+
+    fn constant_visibility(&self) -> Result<Visibility> {
+        self.visibility
+            .to_token_stream()
+            .to_string()
+            .parse()
+            .map_err(|e| Error::new_spanned(&self.visibility, format!("{e:#}")))
+    }
+
+    /// Ensures that all glyphs are contained in the font.
+    ///
+    /// Missing glyphs are ignored if a substitute is used.
+    fn ensure_glyphs(
+        &self,
+        font: &Font,
+        mut chars: impl Iterator<Item = char>,
+        span: Span,
+    ) -> Result<()> {
+        if self.missing_glyph_substitute.is_some() {
+            return Ok(());
+        }
+
+        chars.try_for_each(|c| ensure_glyph(font, c, span))
+    }
+}
+
+fn ensure_glyph(font: &Font, c: char, span: Span) -> Result<()> {
+    if font.glyphs.contains(c) {
+        Ok(())
+    } else {
+        Err(Error::new(
+            span,
+            format!(
+                "glyph '{}' (U+{:04X}) is not contained in the BDF font",
+                c,
+                u32::from(c)
+            ),
+        ))
+    }
+}
+
+/// Ensures that the replacement character is included in the selected glyphs.
+///
+/// All glyphs in the font are included if no glyphs are selected.
+fn ensure_replacement_character(
+    font: &Font,
+    selected_glyphs: &[char],
+    c: char,
+    span: Span,
+) -> Result<()> {
+    let included = if selected_glyphs.is_empty() {
+        font.glyphs.contains(c)
+    } else {
+        selected_glyphs.contains(&c)
+    };
+
+    if included {
+        Ok(())
+    } else {
+        Err(Error::new(
+            span,
+            format!(
+                "replacement character '{}' (U+{:04X}) is not included in the glyphs",
+                c,
+                u32::from(c)
+            ),
+        ))
+    }
+}
+
+/// Parses a list of characters and character ranges.
+fn parse_glyphs(glyphs: &str) -> std::result::Result<Vec<char>, String> {
+    let chars = glyphs.chars().collect::<Vec<_>>();
+
+    let mut output = Vec::new();
+    let mut index = 0;
+    while index < chars.len() {
+        match chars[index..] {
+            [start, '-', end, ..] => {
+                if start > end {
+                    return Err(format!("invalid glyph range '{start}-{end}'"));
+                }
+
+                output.extend(start..=end);
+                index += 3;
+            }
+            [c, ..] => {
+                output.push(c);
+                index += 1;
+            }
+            [] => unreachable!(),
+        }
+    }
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand(input: TokenStream) -> Result<TokenStream> {
+        syn::parse2::<BdfFontInput>(input)?.expand()
+    }
+
+    fn expand_error(input: TokenStream) -> String {
+        expand(input).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn glyphs() {
+        assert_eq!(parse_glyphs("A-Cx").unwrap(), ['A', 'B', 'C', 'x']);
+        assert_eq!(parse_glyphs("-a-c-").unwrap(), ['-', 'a', 'b', 'c', '-']);
+        assert!(parse_glyphs("").unwrap().is_empty());
+        assert!(parse_glyphs("Z-A").is_err());
+    }
+
+    #[test]
+    fn options() {
+        let input = syn::parse2::<BdfFontInput>(quote!(
+            pub(crate) FONT, "font.bdf", glyphs = "A-Z", kind = mono_font,
+        ))
+        .unwrap();
+
+        assert_eq!(input.name, "FONT");
+        assert_eq!(input.file.value(), "font.bdf");
+        assert_eq!(input.glyphs.as_ref().unwrap().value(), "A-Z");
+        assert_eq!(input.constant_visibility().unwrap(), Visibility::PubCrate);
+        assert_eq!(input.kind().unwrap(), Kind::MonoFont);
+    }
+
+    #[test]
+    fn unknown_option() {
+        let error = syn::parse2::<BdfFontInput>(quote!(FONT, "font.bdf", size = 10)).unwrap_err();
+        assert_eq!(error.to_string(), "unknown option `size`");
+    }
+
+    #[test]
+    fn duplicate_option() {
+        let error =
+            syn::parse2::<BdfFontInput>(quote!(FONT, "font.bdf", kind = eg_bdf, kind = mono_font))
+                .unwrap_err();
+        assert_eq!(error.to_string(), "duplicate option `kind`");
+    }
+
+    #[test]
+    fn missing_file() {
+        assert!(
+            expand_error(quote!(FONT, "missing.bdf")).starts_with("couldn't read font file from")
+        );
+    }
+
+    #[test]
+    fn missing_glyph() {
+        assert_eq!(
+            expand_error(quote!(
+                FONT,
+                "../eg-bdf-examples/examples/6x10.bdf",
+                glyphs = "A\u{E000}"
+            )),
+            "glyph '\u{E000}' (U+E000) is not contained in the BDF font"
+        );
+    }
+
+    #[test]
+    fn unknown_kind() {
+        assert_eq!(
+            expand_error(quote!(
+                FONT,
+                "../eg-bdf-examples/examples/6x10.bdf",
+                kind = bmfont
+            )),
+            "unknown kind `bmfont`, expected `eg_bdf` or `mono_font`"
+        );
+    }
+
+    #[test]
+    fn unknown_mapping() {
+        assert_eq!(
+            expand_error(quote!(
+                FONT,
+                "../eg-bdf-examples/examples/6x10.bdf",
+                mapping = "UNKNOWN"
+            )),
+            "unknown mapping \"UNKNOWN\""
+        );
+    }
+
+    #[test]
+    fn missing_replacement_character() {
+        assert_eq!(
+            expand_error(quote!(
+                FONT,
+                "../eg-bdf-examples/examples/6x10.bdf",
+                glyphs = "A-Z",
+                replacement_character = '?'
+            )),
+            "replacement character '?' (U+003F) is not included in the glyphs"
+        );
+    }
+
+    #[test]
+    fn missing_replacement_character_in_font() {
+        assert_eq!(
+            expand_error(quote!(
+                FONT,
+                "../eg-bdf-examples/examples/6x10.bdf",
+                replacement_character = '\u{E000}'
+            )),
+            "replacement character '\u{E000}' (U+E000) is not included in the glyphs"
+        );
+    }
+
+    #[test]
+    fn inlined_data() {
+        let output = expand(quote!(
+            FONT,
+            "../eg-bdf-examples/examples/6x10.bdf",
+            glyphs = "A"
+        ))
+        .unwrap()
+        .to_string();
+
+        assert!(output.starts_with("const FONT : :: eg_bdf :: BdfFont"));
+        assert!(output.contains("data : b\""));
+    }
+}
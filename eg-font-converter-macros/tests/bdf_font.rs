@@ -0,0 +1,71 @@
+use eg_font_converter::{FontConverter, Mapping};
+use eg_font_converter_macros::bdf_font;
+use embedded_graphics::{
+    mock_display::MockDisplay,
+    mono_font::MonoTextStyle,
+    pixelcolor::BinaryColor,
+    prelude::*,
+    text::{Baseline, Text},
+};
+
+bdf_font!(
+    FONT_6X10,
+    "../eg-bdf-examples/examples/6x10.bdf",
+    glyphs = "A-Za-z0-9",
+    kind = eg_bdf
+);
+
+bdf_font!(
+    pub(crate) FONT_6X10_MONO,
+    "../eg-bdf-examples/examples/6x10.bdf",
+    mapping = "ASCII",
+    missing_glyph_substitute = '?',
+    kind = mono_font,
+);
+
+#[test]
+fn eg_bdf() {
+    let expected = FontConverter::with_file("../eg-bdf-examples/examples/6x10.bdf", "FONT_6X10")
+        .glyphs('A'..='Z')
+        .glyphs('a'..='z')
+        .glyphs('0'..='9')
+        .convert_eg_bdf()
+        .unwrap();
+
+    assert_eq!(FONT_6X10, expected.as_font());
+}
+
+#[test]
+fn mono_font() {
+    let expected =
+        FontConverter::with_file("../eg-bdf-examples/examples/6x10.bdf", "FONT_6X10_MONO")
+            .glyphs(Mapping::Ascii)
+            .missing_glyph_substitute('?')
+            .convert_mono_font()
+            .unwrap();
+    let expected = expected.as_font();
+
+    let mut display = MockDisplay::new();
+    Text::with_baseline(
+        "Ab1~",
+        Point::zero(),
+        MonoTextStyle::new(&FONT_6X10_MONO, BinaryColor::On),
+        Baseline::Top,
+    )
+    .draw(&mut display)
+    .unwrap();
+
+    let mut expected_display = MockDisplay::new();
+    Text::with_baseline(
+        "Ab1~",
+        Point::zero(),
+        MonoTextStyle::new(&expected, BinaryColor::On),
+        Baseline::Top,
+    )
+    .draw(&mut expected_display)
+    .unwrap();
+
+    display.assert_eq(&expected_display);
+    assert_eq!(FONT_6X10_MONO.character_size, expected.character_size);
+    assert_eq!(FONT_6X10_MONO.baseline, expected.baseline);
+}
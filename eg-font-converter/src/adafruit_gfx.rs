@@ -0,0 +1,369 @@
+use std::{fmt::Write, fs, io, path::Path};
+
+use anyhow::{bail, Context, Result};
+use bdf_parser::Encoding;
+use bitvec::{prelude::*, vec::BitVec};
+
+use crate::ConvertedFont;
+
+/// Number of bytes per line in the generated bitmap array.
+const BYTES_PER_LINE: usize = 12;
+
+/// Adafruit GFX glyph.
+///
+/// Corresponds to the `GFXglyph` struct in the Adafruit GFX library.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GfxGlyph {
+    /// Offset of the glyph in the bitmap data.
+    pub bitmap_offset: u16,
+    /// Bitmap width.
+    pub width: u8,
+    /// Bitmap height.
+    pub height: u8,
+    /// Horizontal distance to the next glyph.
+    pub x_advance: u8,
+    /// Horizontal distance from the cursor position to the left edge of the bitmap.
+    pub x_offset: i8,
+    /// Vertical distance from the baseline to the top edge of the bitmap.
+    pub y_offset: i8,
+}
+
+/// Font conversion output for the Adafruit GFX library.
+///
+/// The output is a C header, which contains a `GFXfont` with the same format
+/// as the fonts generated by the `fontconvert` tool in the Adafruit GFX
+/// library. Adafruit GFX fonts contain a continuous range of code points, so
+/// that code points between the first and last glyph, which aren't included
+/// in the converted glyphs, are added as empty glyphs.
+#[derive(Debug)]
+pub struct AdafruitGfxOutput {
+    font: ConvertedFont,
+    data: Vec<u8>,
+    glyphs: Vec<GfxGlyph>,
+    first: u32,
+    last: u32,
+    y_advance: u8,
+}
+
+impl AdafruitGfxOutput {
+    pub(crate) fn new(font: ConvertedFont) -> Result<Self> {
+        font.ensure_monochrome()?;
+
+        let mut glyphs = font
+            .glyphs
+            .iter()
+            .filter_map(|glyph| match glyph.encoding {
+                Encoding::Standard(code_point) => Some((code_point, glyph)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        glyphs.sort_by_key(|(code_point, _)| *code_point);
+        glyphs.dedup_by_key(|(code_point, _)| *code_point);
+
+        let (Some((first, _)), Some((last, _))) = (glyphs.first(), glyphs.last()) else {
+            bail!("font doesn't contain any glyphs with a standard encoding");
+        };
+        let (first, last) = (*first, *last);
+        if last > u32::from(u16::MAX) {
+            bail!("code point U+{last:04X} is outside of the supported range");
+        }
+
+        let y_advance = u8::try_from(font.bdf.metrics.line_height())
+            .context("line height doesn't fit into the yAdvance field")?;
+
+        let mut data = Vec::new();
+        let mut gfx_glyphs = Vec::new();
+        let mut glyphs = glyphs.into_iter().peekable();
+
+        for code_point in first..=last {
+            let bitmap_offset = u16::try_from(data.len())
+                .context("bitmap data exceeds the maximum size of 65535 bytes")?;
+
+            let Some((_, glyph)) = glyphs.next_if(|(c, _)| *c == code_point) else {
+                gfx_glyphs.push(GfxGlyph {
+                    bitmap_offset,
+                    width: 0,
+                    height: 0,
+                    x_advance: 0,
+                    x_offset: 0,
+                    y_offset: 0,
+                });
+                continue;
+            };
+
+            let error =
+                |field: &str| format!("{field} of glyph \"{}\" is out of range", glyph.name);
+
+            let size = glyph.bounding_box.size;
+            let offset = glyph.bounding_box.offset;
+            let device_width = glyph
+                .width_horizontal
+                .map(|width| width.device.x)
+                .unwrap_or_default();
+
+            let gfx_glyph = GfxGlyph {
+                bitmap_offset,
+                width: u8::try_from(size.x).with_context(|| error("width"))?,
+                height: u8::try_from(size.y).with_context(|| error("height"))?,
+                x_advance: u8::try_from(device_width).with_context(|| error("xAdvance"))?,
+                x_offset: i8::try_from(offset.x).with_context(|| error("xOffset"))?,
+                y_offset: i8::try_from(-(offset.y + size.y)).with_context(|| error("yOffset"))?,
+            };
+
+            // The bitmap of each glyph starts at a byte boundary, but the rows
+            // aren't padded.
+            let mut bits = BitVec::<u8, Msb0>::new();
+            bits.extend(glyph.pixels());
+            data.extend(bits.as_raw_slice());
+
+            gfx_glyphs.push(gfx_glyph);
+        }
+
+        Ok(Self {
+            font,
+            data,
+            glyphs: gfx_glyphs,
+            first,
+            last,
+            y_advance,
+        })
+    }
+
+    /// Returns the generated C header.
+    pub fn header(&self) -> String {
+        let name = &self.font.name;
+        let mut output = String::new();
+
+        for comment in &self.font.comments {
+            writeln!(output, "// {comment}").unwrap();
+        }
+        if !self.font.comments.is_empty() {
+            output.push('\n');
+        }
+
+        writeln!(output, "const uint8_t {name}Bitmaps[] PROGMEM = {{").unwrap();
+        for line in self.data.chunks(BYTES_PER_LINE) {
+            let bytes = line
+                .iter()
+                .map(|byte| format!("0x{byte:02X}"))
+                .collect::<Vec<_>>();
+            writeln!(output, "  {},", bytes.join(", ")).unwrap();
+        }
+        writeln!(output, "}};\n").unwrap();
+
+        writeln!(output, "const GFXglyph {name}Glyphs[] PROGMEM = {{").unwrap();
+        for (code_point, glyph) in (self.first..=self.last).zip(&self.glyphs) {
+            let GfxGlyph {
+                bitmap_offset,
+                width,
+                height,
+                x_advance,
+                x_offset,
+                y_offset,
+            } = glyph;
+
+            write!(
+                output,
+                "  {{ {bitmap_offset:5}, {width:3}, {height:3}, {x_advance:3}, {x_offset:4}, {y_offset:4} }}, // 0x{code_point:02X}"
+            )
+            .unwrap();
+            match char::from_u32(code_point) {
+                Some(c) if !c.is_control() => writeln!(output, " '{c}'").unwrap(),
+                _ => output.push('\n'),
+            }
+        }
+        writeln!(output, "}};\n").unwrap();
+
+        writeln!(output, "const GFXfont {name} PROGMEM = {{").unwrap();
+        writeln!(output, "  (uint8_t  *){name}Bitmaps,").unwrap();
+        writeln!(output, "  (GFXglyph *){name}Glyphs,").unwrap();
+        writeln!(
+            output,
+            "  0x{:02X}, 0x{:02X}, {} }};\n",
+            self.first, self.last, self.y_advance
+        )
+        .unwrap();
+
+        // Each glyph uses 7 bytes and the font struct uses 7 bytes plus two pointers.
+        let size = self.data.len() + self.glyphs.len() * 7 + 7;
+        writeln!(output, "// Approx. {size} bytes").unwrap();
+
+        output
+    }
+
+    /// Returns the bitmap data.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns the glyphs.
+    ///
+    /// The glyphs are sorted by code point, starting at the
+    /// [`first`](Self::first) code point.
+    pub fn glyphs(&self) -> &[GfxGlyph] {
+        &self.glyphs
+    }
+
+    /// Returns the code point of the first glyph.
+    pub fn first(&self) -> u32 {
+        self.first
+    }
+
+    /// Returns the code point of the last glyph.
+    pub fn last(&self) -> u32 {
+        self.last
+    }
+
+    /// Returns the line height.
+    pub fn y_advance(&self) -> u8 {
+        self.y_advance
+    }
+
+    /// Saves the C header to the given directory.
+    ///
+    /// The file name is the file stem with a `.h` extension.
+    pub fn save<P: AsRef<Path>>(&self, output_directory: P) -> io::Result<()> {
+        let path = output_directory
+            .as_ref()
+            .join(&self.font.file_stem)
+            .with_extension("h");
+
+        fs::write(path, self.header())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::FontConverter;
+
+    use super::*;
+
+    const FONT: &str = r#"
+        STARTFONT 2.1
+        FONT -test-Test-Medium-R-Normal--8-80-75-75-P-60-ISO10646-1
+        SIZE 8 75 75
+        FONTBOUNDINGBOX 6 8 0 -2
+        STARTPROPERTIES 2
+        FONT_ASCENT 6
+        FONT_DESCENT 2
+        ENDPROPERTIES
+        CHARS 3
+        STARTCHAR A
+        ENCODING 65
+        DWIDTH 6 0
+        BBX 5 6 0 0
+        BITMAP
+        20
+        50
+        88
+        F8
+        88
+        88
+        ENDCHAR
+        STARTCHAR C
+        ENCODING 67
+        DWIDTH 5 0
+        BBX 3 3 1 -1
+        BITMAP
+        E0
+        80
+        E0
+        ENDCHAR
+        STARTCHAR wide
+        ENCODING 68
+        DWIDTH 7 0
+        BBX 300 1 0 0
+        BITMAP
+        00
+        ENDCHAR
+        ENDFONT
+    "#;
+
+    #[test]
+    fn glyphs_and_bitmap() {
+        let output = FontConverter::with_string(FONT, "TestFont")
+            .glyphs("AC")
+            .convert_adafruit_gfx()
+            .unwrap();
+
+        assert_eq!(output.first(), 0x41);
+        assert_eq!(output.last(), 0x43);
+        assert_eq!(output.y_advance(), 8);
+        assert_eq!(
+            output.glyphs(),
+            [
+                GfxGlyph {
+                    bitmap_offset: 0,
+                    width: 5,
+                    height: 6,
+                    x_advance: 6,
+                    x_offset: 0,
+                    y_offset: -6,
+                },
+                GfxGlyph {
+                    bitmap_offset: 4,
+                    width: 0,
+                    height: 0,
+                    x_advance: 0,
+                    x_offset: 0,
+                    y_offset: 0,
+                },
+                GfxGlyph {
+                    bitmap_offset: 4,
+                    width: 3,
+                    height: 3,
+                    x_advance: 5,
+                    x_offset: 1,
+                    y_offset: -2,
+                },
+            ]
+        );
+
+        // A: 00100 01010 10001 11111 10001 10001 (30 bits)
+        // C: 111 100 111 (9 bits)
+        assert_eq!(output.data(), [0x22, 0xA3, 0xF8, 0xC4, 0xF3, 0x80],);
+    }
+
+    #[test]
+    fn header() {
+        let output = FontConverter::with_string(FONT, "TestFont")
+            .glyphs("AC")
+            .comment("Test font")
+            .convert_adafruit_gfx()
+            .unwrap();
+
+        assert_eq!(
+            output.header(),
+            concat!(
+                "// Test font\n",
+                "\n",
+                "const uint8_t TestFontBitmaps[] PROGMEM = {\n",
+                "  0x22, 0xA3, 0xF8, 0xC4, 0xF3, 0x80,\n",
+                "};\n",
+                "\n",
+                "const GFXglyph TestFontGlyphs[] PROGMEM = {\n",
+                "  {     0,   5,   6,   6,    0,   -6 }, // 0x41 'A'\n",
+                "  {     4,   0,   0,   0,    0,    0 }, // 0x42 'B'\n",
+                "  {     4,   3,   3,   5,    1,   -2 }, // 0x43 'C'\n",
+                "};\n",
+                "\n",
+                "const GFXfont TestFont PROGMEM = {\n",
+                "  (uint8_t  *)TestFontBitmaps,\n",
+                "  (GFXglyph *)TestFontGlyphs,\n",
+                "  0x41, 0x43, 8 };\n",
+                "\n",
+                "// Approx. 34 bytes\n",
+            )
+        );
+    }
+
+    #[test]
+    fn out_of_range() {
+        let error = FontConverter::with_string(FONT, "TestFont")
+            .glyphs('D')
+            .convert_adafruit_gfx()
+            .unwrap_err();
+
+        assert_eq!(error.to_string(), "width of glyph \"wide\" is out of range");
+    }
+}
@@ -0,0 +1,486 @@
+use std::{fmt::Write, fs, path::Path};
+
+use anyhow::{anyhow, bail, Context, Result};
+use bdf_parser::{Encoding, Property};
+use eg_bdf::BdfTextStyle;
+use embedded_graphics::{
+    pixelcolor::BinaryColor,
+    prelude::*,
+    text::{Baseline, Text},
+};
+use embedded_graphics_simulator::SimulatorDisplay;
+use serde_json::json;
+
+use crate::{ConvertedFont, EgBdfOutput};
+
+/// Number of empty pixels between the glyphs in the atlas.
+const SPACING: u32 = 1;
+
+/// BMFont character.
+///
+/// Corresponds to a `char` entry in a BMFont descriptor. All coordinates are
+/// in pixels and the Y axis points down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BmFontChar {
+    /// Unicode code point.
+    pub id: u32,
+    /// X coordinate of the glyph in the atlas.
+    pub x: u32,
+    /// Y coordinate of the glyph in the atlas.
+    pub y: u32,
+    /// Glyph width.
+    pub width: u32,
+    /// Glyph height.
+    pub height: u32,
+    /// Horizontal distance from the cursor position to the left edge of the glyph.
+    pub xoffset: i32,
+    /// Vertical distance from the top of the line to the top edge of the glyph.
+    pub yoffset: i32,
+    /// Horizontal distance to the next glyph.
+    pub xadvance: i32,
+}
+
+/// Font conversion output for the AngelCode [BMFont] format.
+///
+/// The output consists of a PNG atlas, which contains the glyphs, and a
+/// descriptor in the text or JSON format. The atlas is a grayscale image
+/// with an alpha channel, which contains white glyphs on a transparent
+/// background.
+///
+/// [BMFont]: https://www.angelcode.com/products/bmfont/doc/file_format.html
+#[derive(Debug)]
+pub struct BmFontOutput {
+    font: ConvertedFont,
+    atlas: SimulatorDisplay<BinaryColor>,
+    chars: Vec<BmFontChar>,
+}
+
+impl BmFontOutput {
+    pub(crate) fn new(bdf: EgBdfOutput) -> Result<Self> {
+        let font = bdf.as_font();
+        let style = BdfTextStyle::new(&font, BinaryColor::On);
+        let ascent = bdf.font.bdf.metrics.ascent as i32;
+
+        let mut glyphs = bdf
+            .font
+            .glyphs
+            .iter()
+            .filter_map(|glyph| match glyph.encoding {
+                Encoding::Standard(code_point) => Some(
+                    char::from_u32(code_point)
+                        .map(|c| (c, glyph))
+                        .ok_or_else(|| {
+                            anyhow!(
+                                "encoding of glyph \"{}\" isn't a valid Unicode code point: {code_point:#X}",
+                                glyph.name
+                            )
+                        }),
+                ),
+                _ => None,
+            })
+            .collect::<Result<Vec<_>>>()?;
+        glyphs.sort_by_key(|(c, _)| *c);
+        glyphs.dedup_by_key(|(c, _)| *c);
+
+        if glyphs.is_empty() {
+            bail!("font doesn't contain any glyphs with a standard encoding");
+        }
+
+        let size = |glyph: &bdf_parser::Glyph| {
+            let size = glyph.bounding_box.size;
+            if size.x > 0 && size.y > 0 {
+                Size::new(size.x as u32, size.y as u32)
+            } else {
+                Size::zero()
+            }
+        };
+
+        // The atlas width is chosen to make the atlas roughly square.
+        let area = glyphs
+            .iter()
+            .map(|(_, glyph)| {
+                let size = size(glyph) + Size::new_equal(SPACING);
+                size.width * size.height
+            })
+            .sum::<u32>();
+        let max_width = glyphs
+            .iter()
+            .map(|(_, glyph)| size(glyph).width)
+            .max()
+            .unwrap_or_default();
+        let atlas_width = area.isqrt().max(max_width).max(1).next_power_of_two();
+
+        // Glyphs are placed in rows from left to right.
+        let mut chars = Vec::new();
+        let mut position = Point::zero();
+        let mut row_height = 0;
+        for (c, glyph) in &glyphs {
+            let glyph_size = size(glyph);
+
+            if glyph_size.width > 0 && position.x as u32 + glyph_size.width > atlas_width {
+                position = Point::new(0, position.y + (row_height + SPACING) as i32);
+                row_height = 0;
+            }
+
+            let offset = glyph.bounding_box.offset;
+            let top = offset.y + glyph.bounding_box.size.y;
+            let device_width = glyph
+                .width_horizontal
+                .map(|width| width.device.x)
+                .unwrap_or_default();
+
+            let (x, y) = if glyph_size.width > 0 {
+                (position.x as u32, position.y as u32)
+            } else {
+                (0, 0)
+            };
+            chars.push(BmFontChar {
+                id: u32::from(*c),
+                x,
+                y,
+                width: glyph_size.width,
+                height: glyph_size.height,
+                xoffset: offset.x,
+                yoffset: ascent - top,
+                xadvance: device_width,
+            });
+
+            if glyph_size.width > 0 {
+                position.x += (glyph_size.width + SPACING) as i32;
+                row_height = row_height.max(glyph_size.height);
+            }
+        }
+        let atlas_height = (position.y as u32 + row_height).max(1);
+
+        let mut atlas = SimulatorDisplay::new(Size::new(atlas_width, atlas_height));
+        for (bm_char, (c, glyph)) in chars.iter().zip(&glyphs) {
+            if bm_char.width == 0 {
+                continue;
+            }
+
+            // The text is drawn relative to the row above the baseline.
+            let origin = Point::new(
+                bm_char.x as i32 - glyph.bounding_box.offset.x,
+                bm_char.y as i32 + glyph.bounding_box.offset.y + glyph.bounding_box.size.y - 1,
+            );
+            Text::with_baseline(&String::from(*c), origin, style, Baseline::Alphabetic)
+                .draw(&mut atlas)
+                .unwrap();
+        }
+
+        Ok(Self {
+            font: bdf.font,
+            atlas,
+            chars,
+        })
+    }
+
+    /// Returns the characters.
+    ///
+    /// The characters are sorted by code point.
+    pub fn chars(&self) -> &[BmFontChar] {
+        &self.chars
+    }
+
+    /// Returns the file name of the atlas.
+    ///
+    /// The file name is the file stem with a `.png` extension.
+    pub fn page_file(&self) -> String {
+        format!("{}.png", self.font.file_stem)
+    }
+
+    /// Returns the descriptor in the text format.
+    pub fn text(&self) -> String {
+        let info = self.info();
+        let mut output = String::new();
+
+        writeln!(
+            output,
+            "info face=\"{}\" size={} bold=0 italic=0 charset=\"\" unicode=1 stretchH=100 smooth=0 aa=1 padding=0,0,0,0 spacing={SPACING},{SPACING} outline=0",
+            info.face.replace('"', "'"),
+            info.size
+        )
+        .unwrap();
+        writeln!(
+            output,
+            "common lineHeight={} base={} scaleW={} scaleH={} pages=1 packed=0 alphaChnl=0 redChnl=4 greenChnl=4 blueChnl=4",
+            info.line_height, info.base, info.scale.width, info.scale.height
+        )
+        .unwrap();
+        writeln!(output, "page id=0 file=\"{}\"", self.page_file()).unwrap();
+        writeln!(output, "chars count={}", self.chars.len()).unwrap();
+        for c in &self.chars {
+            writeln!(
+                output,
+                "char id={:<5} x={:<5} y={:<5} width={:<5} height={:<5} xoffset={:<5} yoffset={:<5} xadvance={:<5} page=0  chnl=15",
+                c.id, c.x, c.y, c.width, c.height, c.xoffset, c.yoffset, c.xadvance
+            )
+            .unwrap();
+        }
+        writeln!(output, "kernings count=0").unwrap();
+
+        output
+    }
+
+    /// Returns the descriptor in the JSON format.
+    ///
+    /// The JSON format uses the same structure as the output of common
+    /// BMFont to JSON converters.
+    pub fn json(&self) -> String {
+        let info = self.info();
+
+        let chars = self
+            .chars
+            .iter()
+            .enumerate()
+            .map(|(index, c)| {
+                json!({
+                    "id": c.id,
+                    "index": index,
+                    "char": char::from_u32(c.id).map(String::from).unwrap_or_default(),
+                    "x": c.x,
+                    "y": c.y,
+                    "width": c.width,
+                    "height": c.height,
+                    "xoffset": c.xoffset,
+                    "yoffset": c.yoffset,
+                    "xadvance": c.xadvance,
+                    "page": 0,
+                    "chnl": 15,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let descriptor = json!({
+            "pages": [self.page_file()],
+            "chars": chars,
+            "info": {
+                "face": info.face,
+                "size": info.size,
+                "bold": 0,
+                "italic": 0,
+                "charset": [],
+                "unicode": 1,
+                "stretchH": 100,
+                "smooth": 0,
+                "aa": 1,
+                "padding": [0, 0, 0, 0],
+                "spacing": [SPACING, SPACING],
+            },
+            "common": {
+                "lineHeight": info.line_height,
+                "base": info.base,
+                "scaleW": info.scale.width,
+                "scaleH": info.scale.height,
+                "pages": 1,
+                "packed": 0,
+                "alphaChnl": 0,
+                "redChnl": 4,
+                "greenChnl": 4,
+                "blueChnl": 4,
+            },
+            "kernings": [],
+        });
+
+        let mut output = serde_json::to_string_pretty(&descriptor).unwrap();
+        output.push('\n');
+        output
+    }
+
+    /// Returns the atlas as a PNG image.
+    pub fn png(&self) -> Result<Vec<u8>> {
+        let size = self.atlas.size();
+
+        let mut data = Vec::new();
+        for y in 0..size.height as i32 {
+            for x in 0..size.width as i32 {
+                let alpha = match self.atlas.get_pixel(Point::new(x, y)) {
+                    BinaryColor::On => 0xFF,
+                    BinaryColor::Off => 0x00,
+                };
+                data.extend([0xFF, alpha]);
+            }
+        }
+
+        let mut png = Vec::new();
+        let mut encoder = png::Encoder::new(&mut png, size.width, size.height);
+        encoder.set_color(png::ColorType::GrayscaleAlpha);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&data)?;
+        writer.finish()?;
+
+        Ok(png)
+    }
+
+    /// Saves the atlas and the text descriptor to the given directory.
+    ///
+    /// The descriptor uses the file stem with a `.fnt` extension.
+    pub fn save<P: AsRef<Path>>(&self, output_directory: P) -> Result<()> {
+        self.save_with_descriptor(output_directory.as_ref(), "fnt", self.text())
+    }
+
+    /// Saves the atlas and the JSON descriptor to the given directory.
+    ///
+    /// The descriptor uses the file stem with a `.json` extension.
+    pub fn save_json<P: AsRef<Path>>(&self, output_directory: P) -> Result<()> {
+        self.save_with_descriptor(output_directory.as_ref(), "json", self.json())
+    }
+
+    fn save_with_descriptor(
+        &self,
+        output_directory: &Path,
+        extension: &str,
+        descriptor: String,
+    ) -> Result<()> {
+        let descriptor_path = output_directory
+            .join(&self.font.file_stem)
+            .with_extension(extension);
+        fs::write(&descriptor_path, descriptor)
+            .with_context(|| format!("failed to write {}", descriptor_path.display()))?;
+
+        let png_path = output_directory.join(self.page_file());
+        fs::write(&png_path, self.png()?)
+            .with_context(|| format!("failed to write PNG file to {}", png_path.display()))
+    }
+
+    fn info(&self) -> Info {
+        let bdf = &self.font.bdf;
+        let properties = &bdf.metadata.properties;
+
+        Info {
+            face: properties
+                .try_get::<String>(Property::FamilyName)
+                .ok()
+                .flatten()
+                .unwrap_or_else(|| self.font.name.clone()),
+            size: properties
+                .try_get::<i32>(Property::PixelSize)
+                .ok()
+                .flatten()
+                .unwrap_or(bdf.metrics.line_height() as i32),
+            line_height: bdf.metrics.line_height(),
+            base: bdf.metrics.ascent,
+            scale: self.atlas.size(),
+        }
+    }
+}
+
+/// Common values in the `info` and `common` blocks.
+#[derive(Debug)]
+struct Info {
+    face: String,
+    size: i32,
+    line_height: u32,
+    base: u32,
+    scale: Size,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::FontConverter;
+
+    use super::*;
+
+    fn convert() -> BmFontOutput {
+        FontConverter::with_file("../eg-bdf-examples/examples/6x10.bdf", "font_6x10")
+            .glyphs(' '..='~')
+            .convert_bmfont()
+            .unwrap()
+    }
+
+    #[test]
+    fn atlas() {
+        let output = convert();
+        let bdf = &output.font.bdf;
+
+        let png = output.png().unwrap();
+        let decoder = png::Decoder::new(std::io::Cursor::new(png));
+        let mut reader = decoder.read_info().unwrap();
+        let mut data = vec![0; reader.output_buffer_size().unwrap()];
+        let info = reader.next_frame(&mut data).unwrap();
+        assert_eq!(info.color_type, png::ColorType::GrayscaleAlpha);
+
+        // 95 glyphs with 7x11 pixels including spacing result in 18 glyphs per row.
+        assert_eq!((info.width, info.height), (128, 65));
+
+        for c in output.chars() {
+            let glyph = bdf
+                .glyphs
+                .iter()
+                .find(|glyph| glyph.encoding == Encoding::Standard(c.id))
+                .unwrap();
+
+            for y in 0..c.height {
+                for x in 0..c.width {
+                    let index = ((c.y + y) * info.width + c.x + x) as usize * 2;
+                    let expected = glyph.pixel(x as usize, y as usize).unwrap();
+                    assert_eq!(data[index + 1] == 0xFF, expected, "{c:?} {x} {y}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn chars() {
+        let output = convert();
+
+        assert_eq!(
+            output.chars()[0x21],
+            BmFontChar {
+                id: 'A' as u32,
+                x: 7 * 15,
+                y: 11,
+                width: 6,
+                height: 10,
+                xoffset: 0,
+                yoffset: 0,
+                xadvance: 6,
+            }
+        );
+    }
+
+    #[test]
+    fn text() {
+        let text = convert().text();
+        let lines = text.lines().collect::<Vec<_>>();
+
+        assert_eq!(
+            lines[..5],
+            [
+                "info face=\"Fixed\" size=10 bold=0 italic=0 charset=\"\" unicode=1 stretchH=100 smooth=0 aa=1 padding=0,0,0,0 spacing=1,1 outline=0",
+                "common lineHeight=10 base=8 scaleW=128 scaleH=65 pages=1 packed=0 alphaChnl=0 redChnl=4 greenChnl=4 blueChnl=4",
+                "page id=0 file=\"font_6x10.png\"",
+                "chars count=95",
+                "char id=32    x=0     y=0     width=6     height=10    xoffset=0     yoffset=0     xadvance=6     page=0  chnl=15",
+            ]
+        );
+        assert_eq!(lines.last(), Some(&"kernings count=0"));
+    }
+
+    #[test]
+    fn json() {
+        let json = serde_json::from_str::<serde_json::Value>(&convert().json()).unwrap();
+
+        assert_eq!(json["pages"], json!(["font_6x10.png"]));
+        assert_eq!(json["common"]["lineHeight"], 10);
+        assert_eq!(json["common"]["base"], 8);
+        assert_eq!(
+            json["chars"][0x21],
+            json!({
+                "id": 65,
+                "index": 33,
+                "char": "A",
+                "x": 105,
+                "y": 11,
+                "width": 6,
+                "height": 10,
+                "xoffset": 0,
+                "yoffset": 0,
+                "xadvance": 6,
+                "page": 0,
+                "chnl": 15,
+            })
+        );
+    }
+}
@@ -0,0 +1,529 @@
+use std::{fmt::Write, fs, io, path::Path};
+
+use anyhow::{bail, Context, Result};
+use bdf_parser::{Encoding, Glyph};
+
+use crate::ConvertedFont;
+
+/// Number of bytes per line in the generated bitmap array.
+const BYTES_PER_LINE: usize = 16;
+
+/// Bitmap layout of the C header output.
+///
+/// The default layout stores the pixels row by row with the most
+/// significant bit first and pads each row to a multiple of 8 bits. Page
+/// based displays, like the SSD1306, expect vertical bytes with the least
+/// significant bit at the top, which corresponds to
+/// [`PixelOrder::PageMajor`] and [`BitOrder::LsbFirst`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CHeaderLayout {
+    /// Order of the pixels in the bitmaps.
+    pub pixel_order: PixelOrder,
+
+    /// Order of the bits in each byte.
+    pub bit_order: BitOrder,
+
+    /// Alignment of the rows or columns.
+    pub line_alignment: LineAlignment,
+}
+
+/// Order of the pixels in a bitmap.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PixelOrder {
+    /// Pixels are stored row by row, from left to right.
+    #[default]
+    RowMajor,
+    /// Pixels are stored column by column, from top to bottom.
+    ColumnMajor,
+    /// Pixels are stored in pages of 8 rows, from top to bottom.
+    ///
+    /// Each page is stored column by column, from left to right, with one
+    /// byte per column. The last page is padded to 8 rows and the
+    /// [`LineAlignment`] has no effect.
+    PageMajor,
+}
+
+/// Order of the bits in a byte.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum BitOrder {
+    /// The first pixel is stored in the most significant bit.
+    #[default]
+    MsbFirst,
+    /// The first pixel is stored in the least significant bit.
+    LsbFirst,
+}
+
+/// Alignment of the rows or columns in a bitmap.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LineAlignment {
+    /// Each row or column starts at a byte boundary.
+    #[default]
+    Byte,
+    /// Rows or columns aren't padded.
+    Packed,
+}
+
+/// Glyph in the C header output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CGlyph {
+    /// Unicode code point.
+    pub code_point: u32,
+    /// Offset of the glyph in the bitmap data.
+    pub bitmap_offset: u32,
+    /// Bitmap width.
+    pub width: u8,
+    /// Bitmap height.
+    pub height: u8,
+    /// Horizontal distance from the cursor position to the left edge of the bitmap.
+    pub x_offset: i8,
+    /// Vertical distance from the baseline to the top edge of the bitmap.
+    pub y_offset: i8,
+    /// Horizontal distance to the next glyph.
+    pub x_advance: u8,
+}
+
+/// Font conversion output for plain C code.
+///
+/// The output is a C header, which doesn't depend on a specific graphics
+/// library. It contains a bitmap array, a glyph table, which is sorted by
+/// code point, and a font struct with the line metrics. The bitmap of each
+/// glyph starts at a byte boundary and its layout is defined by a
+/// [`CHeaderLayout`].
+#[derive(Debug)]
+pub struct CHeaderOutput {
+    font: ConvertedFont,
+    layout: CHeaderLayout,
+    data: Vec<u8>,
+    glyphs: Vec<CGlyph>,
+}
+
+impl CHeaderOutput {
+    pub(crate) fn new(font: ConvertedFont, layout: CHeaderLayout) -> Result<Self> {
+        font.ensure_monochrome()?;
+
+        let mut glyphs = font
+            .glyphs
+            .iter()
+            .filter_map(|glyph| match glyph.encoding {
+                Encoding::Standard(code_point) => Some((code_point, glyph)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        glyphs.sort_by_key(|(code_point, _)| *code_point);
+        glyphs.dedup_by_key(|(code_point, _)| *code_point);
+
+        if glyphs.is_empty() {
+            bail!("font doesn't contain any glyphs with a standard encoding");
+        }
+        u8::try_from(font.bdf.metrics.line_height())
+            .context("line height doesn't fit into the line_height field")?;
+        u16::try_from(glyphs.len()).context("font contains too many glyphs")?;
+
+        let mut data = Vec::new();
+        let mut c_glyphs = Vec::new();
+
+        for (code_point, glyph) in glyphs {
+            let error =
+                |field: &str| format!("{field} of glyph \"{}\" is out of range", glyph.name);
+
+            let size = glyph.bounding_box.size;
+            let offset = glyph.bounding_box.offset;
+            let device_width = glyph
+                .width_horizontal
+                .map(|width| width.device.x)
+                .unwrap_or_default();
+
+            c_glyphs.push(CGlyph {
+                code_point,
+                bitmap_offset: data.len() as u32,
+                width: u8::try_from(size.x).with_context(|| error("width"))?,
+                height: u8::try_from(size.y).with_context(|| error("height"))?,
+                x_offset: i8::try_from(offset.x).with_context(|| error("x_offset"))?,
+                y_offset: i8::try_from(-(offset.y + size.y)).with_context(|| error("y_offset"))?,
+                x_advance: u8::try_from(device_width).with_context(|| error("x_advance"))?,
+            });
+
+            data.extend(pack_bitmap(glyph, layout));
+        }
+
+        Ok(Self {
+            font,
+            layout,
+            data,
+            glyphs: c_glyphs,
+        })
+    }
+
+    /// Returns the generated C header.
+    pub fn header(&self) -> String {
+        let name = &self.font.name;
+        let guard = format!("{}_H", name.to_ascii_uppercase());
+        let metrics = &self.font.bdf.metrics;
+        let mut output = String::new();
+
+        for comment in &self.font.comments {
+            writeln!(output, "// {comment}").unwrap();
+        }
+        if !self.font.comments.is_empty() {
+            output.push('\n');
+        }
+
+        writeln!(output, "#ifndef {guard}").unwrap();
+        writeln!(output, "#define {guard}\n").unwrap();
+        writeln!(output, "#include <stdint.h>\n").unwrap();
+
+        // The types are shared by all fonts that are generated by this output.
+        output.push_str(concat!(
+            "#ifndef FONT_TYPES_DEFINED\n",
+            "#define FONT_TYPES_DEFINED\n",
+            "\n",
+            "typedef struct {\n",
+            "    uint32_t code_point;\n",
+            "    uint32_t bitmap_offset;\n",
+            "    uint8_t width;\n",
+            "    uint8_t height;\n",
+            "    int8_t x_offset;\n",
+            "    int8_t y_offset;\n",
+            "    uint8_t x_advance;\n",
+            "} font_glyph_t;\n",
+            "\n",
+            "typedef struct {\n",
+            "    const uint8_t *bitmap;\n",
+            "    const font_glyph_t *glyphs;\n",
+            "    uint16_t glyph_count;\n",
+            "    uint8_t line_height;\n",
+            "    uint8_t ascent;\n",
+            "} font_t;\n",
+            "\n",
+            "#endif\n",
+            "\n",
+        ));
+
+        let CHeaderLayout {
+            pixel_order,
+            bit_order,
+            line_alignment,
+        } = self.layout;
+        let pixel_order = match pixel_order {
+            PixelOrder::RowMajor => "row-major",
+            PixelOrder::ColumnMajor => "column-major",
+            PixelOrder::PageMajor => "page-major",
+        };
+        let bit_order = match bit_order {
+            BitOrder::MsbFirst => "MSB first",
+            BitOrder::LsbFirst => "LSB first",
+        };
+        let line_alignment = match line_alignment {
+            LineAlignment::Byte => "byte aligned",
+            LineAlignment::Packed => "bit packed",
+        };
+        writeln!(
+            output,
+            "// Bitmap layout: {pixel_order}, {bit_order}, {line_alignment}"
+        )
+        .unwrap();
+        writeln!(output, "static const uint8_t {name}_bitmap[] = {{").unwrap();
+        for line in self.data.chunks(BYTES_PER_LINE) {
+            let bytes = line
+                .iter()
+                .map(|byte| format!("0x{byte:02X}"))
+                .collect::<Vec<_>>();
+            writeln!(output, "    {},", bytes.join(", ")).unwrap();
+        }
+        // Empty arrays aren't allowed in C.
+        if self.data.is_empty() {
+            writeln!(output, "    0x00").unwrap();
+        }
+        writeln!(output, "}};\n").unwrap();
+
+        writeln!(output, "static const font_glyph_t {name}_glyphs[] = {{").unwrap();
+        for glyph in &self.glyphs {
+            let CGlyph {
+                code_point,
+                bitmap_offset,
+                width,
+                height,
+                x_offset,
+                y_offset,
+                x_advance,
+            } = glyph;
+
+            write!(
+                output,
+                "    {{ 0x{code_point:04X}, {bitmap_offset:5}, {width:3}, {height:3}, {x_offset:4}, {y_offset:4}, {x_advance:3} }},"
+            )
+            .unwrap();
+            match char::from_u32(*code_point) {
+                Some(c) if !c.is_control() => writeln!(output, " // '{c}'").unwrap(),
+                _ => output.push('\n'),
+            }
+        }
+        writeln!(output, "}};\n").unwrap();
+
+        writeln!(output, "static const font_t {name} = {{").unwrap();
+        writeln!(output, "    {name}_bitmap,").unwrap();
+        writeln!(output, "    {name}_glyphs,").unwrap();
+        writeln!(output, "    {},", self.glyphs.len()).unwrap();
+        writeln!(output, "    {},", metrics.line_height()).unwrap();
+        writeln!(output, "    {},", metrics.ascent).unwrap();
+        writeln!(output, "}};\n").unwrap();
+
+        writeln!(output, "#endif").unwrap();
+
+        output
+    }
+
+    /// Returns the bitmap data.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns the glyphs.
+    ///
+    /// The glyphs are sorted by code point.
+    pub fn glyphs(&self) -> &[CGlyph] {
+        &self.glyphs
+    }
+
+    /// Saves the C header to the given directory.
+    ///
+    /// The file name is the file stem with a `.h` extension.
+    pub fn save<P: AsRef<Path>>(&self, output_directory: P) -> io::Result<()> {
+        let path = output_directory
+            .as_ref()
+            .join(&self.font.file_stem)
+            .with_extension("h");
+
+        fs::write(path, self.header())
+    }
+}
+
+/// Converts the bitmap of a glyph into the given layout.
+fn pack_bitmap(glyph: &Glyph, layout: CHeaderLayout) -> Vec<u8> {
+    let width = glyph.bounding_box.size.x.max(0) as usize;
+    let height = glyph.bounding_box.size.y.max(0) as usize;
+    let pixel = |x, y| glyph.pixel(x, y).unwrap_or_default();
+
+    let (lines, line_length) = match layout.pixel_order {
+        PixelOrder::RowMajor => (height, width),
+        PixelOrder::ColumnMajor => (width, height),
+        PixelOrder::PageMajor => (height.div_ceil(8), width * 8),
+    };
+
+    let mut bits = Vec::new();
+    for line in 0..lines {
+        for index in 0..line_length {
+            bits.push(match layout.pixel_order {
+                PixelOrder::RowMajor => pixel(index, line),
+                PixelOrder::ColumnMajor => pixel(line, index),
+                PixelOrder::PageMajor => pixel(index / 8, line * 8 + index % 8),
+            });
+        }
+        if layout.line_alignment == LineAlignment::Byte {
+            bits.resize(bits.len().next_multiple_of(8), false);
+        }
+    }
+
+    bits.chunks(8)
+        .map(|byte| {
+            byte.iter()
+                .enumerate()
+                .filter(|(_, bit)| **bit)
+                .fold(0, |value, (index, _)| match layout.bit_order {
+                    BitOrder::MsbFirst => value | 0x80 >> index,
+                    BitOrder::LsbFirst => value | 1 << index,
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::FontConverter;
+
+    use super::*;
+
+    const FONT: &str = r#"
+        STARTFONT 2.1
+        FONT -test-Test-Medium-R-Normal--8-80-75-75-P-60-ISO10646-1
+        SIZE 8 75 75
+        FONTBOUNDINGBOX 6 8 0 -2
+        STARTPROPERTIES 2
+        FONT_ASCENT 6
+        FONT_DESCENT 2
+        ENDPROPERTIES
+        CHARS 2
+        STARTCHAR A
+        ENCODING 65
+        DWIDTH 6 0
+        BBX 5 6 0 0
+        BITMAP
+        20
+        50
+        88
+        F8
+        88
+        88
+        ENDCHAR
+        STARTCHAR C
+        ENCODING 67
+        DWIDTH 5 0
+        BBX 3 3 1 -1
+        BITMAP
+        E0
+        80
+        E0
+        ENDCHAR
+        ENDFONT
+    "#;
+
+    fn convert(layout: CHeaderLayout) -> CHeaderOutput {
+        FontConverter::with_string(FONT, "test_font")
+            .convert_c_header(layout)
+            .unwrap()
+    }
+
+    #[test]
+    fn glyphs() {
+        let output = convert(CHeaderLayout::default());
+
+        assert_eq!(
+            output.glyphs(),
+            [
+                CGlyph {
+                    code_point: 0x41,
+                    bitmap_offset: 0,
+                    width: 5,
+                    height: 6,
+                    x_offset: 0,
+                    y_offset: -6,
+                    x_advance: 6,
+                },
+                CGlyph {
+                    code_point: 0x43,
+                    bitmap_offset: 6,
+                    width: 3,
+                    height: 3,
+                    x_offset: 1,
+                    y_offset: -2,
+                    x_advance: 5,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn row_major_msb_first_byte_aligned() {
+        let output = convert(CHeaderLayout::default());
+
+        assert_eq!(
+            output.data(),
+            [0x20, 0x50, 0x88, 0xF8, 0x88, 0x88, 0xE0, 0x80, 0xE0]
+        );
+    }
+
+    #[test]
+    fn row_major_lsb_first_packed() {
+        let output = convert(CHeaderLayout {
+            bit_order: BitOrder::LsbFirst,
+            line_alignment: LineAlignment::Packed,
+            ..CHeaderLayout::default()
+        });
+
+        // A: 00100 01010 10001 11111 10001 10001
+        // C: 111 100 111
+        assert_eq!(output.data(), [0x44, 0xC5, 0x1F, 0x23, 0xCF, 0x01]);
+    }
+
+    #[test]
+    fn column_major_lsb_first_byte_aligned() {
+        let output = convert(CHeaderLayout {
+            pixel_order: PixelOrder::ColumnMajor,
+            bit_order: BitOrder::LsbFirst,
+            ..CHeaderLayout::default()
+        });
+
+        // A: 001111 010100 100100 010100 001111
+        // C: 111 101 101
+        assert_eq!(
+            output.data(),
+            [0x3C, 0x0A, 0x09, 0x0A, 0x3C, 0x07, 0x05, 0x05]
+        );
+    }
+
+    #[test]
+    fn column_major_msb_first_packed() {
+        let output = convert(CHeaderLayout {
+            pixel_order: PixelOrder::ColumnMajor,
+            line_alignment: LineAlignment::Packed,
+            ..CHeaderLayout::default()
+        });
+
+        // A: 001111 010100 100100 010100 001111
+        // C: 111 101 101
+        assert_eq!(output.data(), [0x3D, 0x49, 0x14, 0x3C, 0xF6, 0x80]);
+    }
+
+    #[test]
+    fn page_major_lsb_first() {
+        let font = r#"
+            STARTFONT 2.1
+            FONT -test-Test-Medium-R-Normal--12-120-75-75-P-40-ISO10646-1
+            SIZE 12 75 75
+            FONTBOUNDINGBOX 3 10 0 0
+            STARTPROPERTIES 2
+            FONT_ASCENT 10
+            FONT_DESCENT 0
+            ENDPROPERTIES
+            CHARS 1
+            STARTCHAR bar
+            ENCODING 124
+            DWIDTH 4 0
+            BBX 3 10 0 0
+            BITMAP
+            E0
+            40
+            40
+            40
+            40
+            40
+            40
+            40
+            40
+            A0
+            ENDCHAR
+            ENDFONT
+        "#;
+
+        let layout = CHeaderLayout {
+            pixel_order: PixelOrder::PageMajor,
+            bit_order: BitOrder::LsbFirst,
+            ..CHeaderLayout::default()
+        };
+        let output = FontConverter::with_string(font, "test_font")
+            .convert_c_header(layout)
+            .unwrap();
+
+        // page 0: 10000000 11111111 10000000
+        // page 1: 01000000 10000000 01000000
+        assert_eq!(output.data(), [0x01, 0xFF, 0x01, 0x02, 0x01, 0x02]);
+        assert!(output
+            .header()
+            .contains("// Bitmap layout: page-major, LSB first, byte aligned\n"));
+    }
+
+    #[test]
+    fn header() {
+        let header = convert(CHeaderLayout::default()).header();
+
+        for expected in [
+            "#ifndef TEST_FONT_H\n#define TEST_FONT_H\n",
+            "// Bitmap layout: row-major, MSB first, byte aligned\n",
+            "static const uint8_t test_font_bitmap[] = {\n    0x20, 0x50, 0x88, 0xF8, 0x88, 0x88, 0xE0, 0x80, 0xE0,\n};\n",
+            "    { 0x0041,     0,   5,   6,    0,   -6,   6 }, // 'A'\n",
+            "static const font_t test_font = {\n    test_font_bitmap,\n    test_font_glyphs,\n    2,\n    8,\n    6,\n};\n",
+        ] {
+            assert!(header.contains(expected), "{expected}\n\n{header}");
+        }
+    }
+}
@@ -0,0 +1,194 @@
+use std::collections::BTreeSet;
+
+use bdf_parser::{Encoding, Font};
+use embedded_graphics::mono_font::mapping::Mapping;
+use unicode_blocks::{find_unicode_block, UnicodeBlock};
+
+/// Glyph coverage of a set of characters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Coverage {
+    /// Name of the character set.
+    pub name: String,
+
+    /// Total number of characters in the character set.
+    ///
+    /// For Unicode blocks this is the number of code points in the block
+    /// range, including unassigned code points.
+    pub total: usize,
+
+    /// Characters which aren't included in the font.
+    pub missing: Vec<char>,
+}
+
+impl Coverage {
+    /// Calculates the coverage of a character set.
+    pub fn new(name: &str, font: &Font, chars: impl IntoIterator<Item = char>) -> Self {
+        let chars = chars.into_iter().collect::<BTreeSet<_>>();
+
+        Self {
+            name: name.to_string(),
+            total: chars.len(),
+            missing: chars
+                .into_iter()
+                .filter(|c| !font.glyphs.contains(*c))
+                .collect(),
+        }
+    }
+
+    /// Calculates the coverage of a Unicode block.
+    ///
+    /// The coverage is calculated against the raw code point range of the
+    /// block, which includes unassigned code points. Blocks with unassigned
+    /// code points therefore never reach 100%, even if all assigned
+    /// characters are included in the font. Control characters are excluded
+    /// from the coverage calculation.
+    pub fn unicode_block(font: &Font, block: UnicodeBlock) -> Self {
+        let chars = (block.start()..=block.end())
+            .filter_map(char::from_u32)
+            .filter(|c| !c.is_control());
+
+        Self::new(block.name(), font, chars)
+    }
+
+    /// Calculates the coverage of an embedded-graphics mapping.
+    pub fn mapping(font: &Font, mapping: Mapping) -> Self {
+        let chars = mapping.glyph_mapping().chars();
+
+        Self::new(mapping.mime(), font, chars)
+    }
+
+    /// Returns the number of characters which are included in the font.
+    pub fn covered(&self) -> usize {
+        self.total - self.missing.len()
+    }
+
+    /// Returns `true` if all characters are included in the font.
+    pub fn is_complete(&self) -> bool {
+        self.missing.is_empty()
+    }
+
+    /// Returns the percentage of characters which are included in the font.
+    pub fn percentage(&self) -> f64 {
+        if self.total == 0 {
+            100.0
+        } else {
+            self.covered() as f64 * 100.0 / self.total as f64
+        }
+    }
+}
+
+/// Coverage report.
+///
+/// The report contains the coverage for all Unicode blocks which contain at
+/// least one glyph of the font and for all embedded-graphics [`Mapping`]s.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoverageReport {
+    /// Coverage by Unicode block.
+    pub blocks: Vec<Coverage>,
+
+    /// Coverage by embedded-graphics mapping.
+    pub mappings: Vec<Coverage>,
+}
+
+impl CoverageReport {
+    /// Creates a coverage report for a font.
+    pub fn new(font: &Font) -> Self {
+        let blocks = font
+            .glyphs
+            .iter()
+            .filter_map(|glyph| match glyph.encoding {
+                // TODO: assumes unicode
+                Encoding::Standard(index) => char::from_u32(index),
+                _ => None,
+            })
+            .filter_map(find_unicode_block)
+            .collect::<BTreeSet<_>>();
+
+        Self::with_blocks(font, blocks)
+    }
+
+    /// Creates a coverage report for a font and a given list of Unicode blocks.
+    ///
+    /// Use [`unicode_blocks`] to create a report for all Unicode blocks.
+    pub fn with_blocks(font: &Font, blocks: impl IntoIterator<Item = UnicodeBlock>) -> Self {
+        Self {
+            blocks: blocks
+                .into_iter()
+                .map(|block| Coverage::unicode_block(font, block))
+                .collect(),
+            mappings: Mapping::iter()
+                .map(|mapping| Coverage::mapping(font, mapping))
+                .collect(),
+        }
+    }
+}
+
+/// Returns an iterator over all Unicode blocks.
+pub fn unicode_blocks() -> impl Iterator<Item = UnicodeBlock> {
+    let mut next = Some(0u32);
+
+    std::iter::from_fn(move || loop {
+        let index = next?;
+        next = index.checked_add(1).filter(|i| *i <= u32::from(char::MAX));
+
+        if let Some(block) = char::from_u32(index).and_then(find_unicode_block) {
+            next = block
+                .end()
+                .checked_add(1)
+                .filter(|i| *i <= u32::from(char::MAX));
+            return Some(block);
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn font() -> Font {
+        let bdf = std::fs::read_to_string("../eg-bdf-examples/examples/6x10.bdf").unwrap();
+        Font::parse(&bdf).unwrap()
+    }
+
+    #[test]
+    fn coverage() {
+        let font = font();
+
+        let coverage = Coverage::new("test", &font, "ab\u{E000}".chars());
+        assert_eq!(coverage.total, 3);
+        assert_eq!(coverage.covered(), 2);
+        assert_eq!(coverage.missing, ['\u{E000}']);
+        assert!(!coverage.is_complete());
+    }
+
+    #[test]
+    fn report() {
+        let report = CoverageReport::new(&font());
+
+        let basic_latin = &report.blocks[0];
+        assert_eq!(basic_latin.name, "Basic Latin");
+        assert_eq!(basic_latin.total, 95);
+        assert!(basic_latin.is_complete());
+
+        let latin1 = &report.blocks[1];
+        assert_eq!(latin1.name, "Latin-1 Supplement");
+        assert_eq!(latin1.total, 96);
+        assert!(latin1.is_complete());
+
+        let ascii = &report.mappings[0];
+        assert_eq!(ascii.name, "ASCII");
+        assert_eq!(ascii.missing, ['\u{7F}']);
+    }
+
+    #[test]
+    fn all_unicode_blocks() {
+        let blocks = unicode_blocks().collect::<Vec<_>>();
+
+        assert_eq!(blocks.first().unwrap().name(), "Basic Latin");
+        assert_eq!(
+            blocks.last().unwrap().name(),
+            "Supplementary Private Use Area-B"
+        );
+        assert!(blocks.windows(2).all(|w| w[0].end() < w[1].start()));
+    }
+}
@@ -1,13 +1,16 @@
 use std::{fs, io, path::Path};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use bdf_parser::{BoundingBox, Encoding, Metrics};
 use bitvec::{prelude::*, vec::BitVec};
-use eg_bdf::{BdfFont, BdfGlyph};
+use eg_bdf::{BdfFont, BdfGlyph, BdfTextStyle};
 use embedded_graphics::{
-    geometry::{Point, Size},
+    pixelcolor::BinaryColor,
+    prelude::*,
     primitives::Rectangle,
+    text::{Baseline, Text},
 };
+use embedded_graphics_simulator::{OutputSettings, SimulatorDisplay};
 use quote::{format_ident, quote};
 
 use crate::ConvertedFont;
@@ -37,6 +40,8 @@ pub struct EgBdfOutput {
 
 impl EgBdfOutput {
     pub(crate) fn new(font: ConvertedFont) -> Result<Self> {
+        font.ensure_monochrome()?;
+
         let mut data = BitVec::<u8, Msb0>::new();
         let mut glyphs = Vec::new();
         let bounding_box = bounding_box_to_rectangle(&font.bdf.metadata.bounding_box);
@@ -82,7 +87,8 @@ impl EgBdfOutput {
 
     fn try_rust(&self) -> Result<String> {
         let constant_name = format_ident!("{}", self.font.name);
-        let data_file = self.font.data_file().to_string_lossy().to_string();
+        let visibility = self.font.constant_visibility.to_tokens();
+        let data = self.font.data_tokens(self.data());
         let ConvertedFont {
             bdf,
             replacement_character,
@@ -113,16 +119,24 @@ impl EgBdfOutput {
             })
         });
 
-        let comments = self.font.comments.iter().map(|comment| {
-            let comment = format!(" {comment}");
-            quote!(
-                #[doc = #comment]
-            )
-        });
+        let comments = self
+            .font
+            .doc_lines(|| {
+                self.bitmap()
+                    .to_grayscale_output_image(&OutputSettings::default())
+                    .to_base64_png()
+                    .context("couldn't encode PNG image")
+            })?
+            .into_iter()
+            .map(|comment| {
+                quote!(
+                    #[doc = #comment]
+                )
+            });
 
         Ok(prettyplease::unparse(&syn::parse2(quote!(
             #( #comments )*
-            pub const #constant_name: ::eg_bdf::BdfFont = {
+            #visibility const #constant_name: ::eg_bdf::BdfFont = {
                 const fn rect(x: i32, y: i32, width: u32, height: u32) -> ::embedded_graphics::primitives::Rectangle {
                     ::embedded_graphics::primitives::Rectangle::new(
                         ::embedded_graphics::geometry::Point::new(x, y),
@@ -131,7 +145,7 @@ impl EgBdfOutput {
                 }
 
                 ::eg_bdf::BdfFont {
-                    data: include_bytes!(#data_file),
+                    data: #data,
                     replacement_character: #replacement_character,
                     ascent: #ascent,
                     descent: #descent,
@@ -164,6 +178,41 @@ impl EgBdfOutput {
         }
     }
 
+    /// Draws the given characters into a bitmap with 16 glyphs per row.
+    ///
+    /// Each glyph is drawn into a cell with the size of the font bounding box.
+    pub(crate) fn draw_glyphs<I>(&self, characters: I) -> SimulatorDisplay<BinaryColor>
+    where
+        I: ExactSizeIterator<Item = char>,
+    {
+        let glyphs_per_row = 16; //TODO: make configurable
+        let columns = glyphs_per_row; // TODO: allow smaller column count
+        let rows = characters.len().div_ceil(glyphs_per_row);
+
+        let font = self.as_font();
+        let style = BdfTextStyle::new(&font, BinaryColor::On);
+        let character_size = self.bounding_box.size;
+
+        let mut bitmap = SimulatorDisplay::new(
+            character_size.component_mul(Size::new(columns as u32, rows as u32)),
+        );
+
+        for (i, c) in characters.enumerate() {
+            let x = (i % glyphs_per_row) as i32 * character_size.width as i32;
+            let y = (i / glyphs_per_row) as i32 * character_size.height as i32;
+
+            Text::with_baseline(&String::from(c), Point::new(x, y), style, Baseline::Top)
+                .draw(&mut bitmap)
+                .unwrap();
+        }
+
+        bitmap
+    }
+
+    fn bitmap(&self) -> SimulatorDisplay<BinaryColor> {
+        self.draw_glyphs(self.glyphs.iter().map(|glyph| glyph.character))
+    }
+
     /// Saves the rust file and bitmap data to the given directory.
     pub fn save<P: AsRef<Path>>(&self, output_directory: P) -> io::Result<()> {
         let output_directory = output_directory.as_ref();
@@ -173,4 +222,14 @@ impl EgBdfOutput {
 
         Ok(())
     }
+
+    /// Saves an image of all glyphs as a PNG file.
+    pub fn save_png<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+
+        self.bitmap()
+            .to_grayscale_output_image(&OutputSettings::default())
+            .save_png(path)
+            .with_context(|| format!("failed to write PNG file to {}", path.display()))
+    }
 }
@@ -0,0 +1,455 @@
+use std::{collections::BTreeSet, io::Cursor, path::Path};
+
+use anyhow::{bail, ensure, Context, Result};
+use bdf_parser::{
+    BoundingBox, Coord, Encoding, Font, Glyph, GlyphWidth, Glyphs, Metadata, Metrics,
+};
+use embedded_graphics::prelude::*;
+
+use crate::{FileOrData, GlyphRange};
+
+/// Glyph width mode.
+///
+/// See [`GlyphSheet::width_mode`] for more information.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WidthMode {
+    /// All glyphs use the cell width.
+    #[default]
+    Cell,
+
+    /// The glyph widths are trimmed to the set pixels.
+    ///
+    /// Empty columns on the left and right side of each glyph are removed and
+    /// `spacing` pixels are added after each glyph. Glyphs without set pixels,
+    /// like the space character, use the cell width.
+    TrimToInk {
+        /// Spacing between glyphs in pixels.
+        spacing: u32,
+    },
+}
+
+/// Glyph sheet importer.
+///
+/// A glyph sheet is a PNG image which contains the glyphs of a font in a grid
+/// of equally sized cells. The cells are assigned to characters row by row,
+/// starting at the top left corner of the image.
+///
+/// # Examples
+///
+/// ```no_run
+/// use eg_font_converter::{FontConverter, GlyphSheet, Mapping};
+/// use embedded_graphics::prelude::*;
+///
+/// let font = GlyphSheet::with_file("font.png", Size::new(6, 10))
+///     .glyphs(Mapping::Ascii)
+///     .baseline(7)
+///     .import()
+///     .unwrap();
+///
+/// let output = FontConverter::with_font(&font, "FONT")
+///     .convert_mono_font()
+///     .unwrap();
+/// ```
+#[derive(Debug)]
+pub struct GlyphSheet<'a> {
+    png: FileOrData<'a>,
+    cell_size: Size,
+    glyphs: Vec<char>,
+    baseline: Option<u32>,
+    width_mode: WidthMode,
+    inverted: bool,
+}
+
+impl<'a> GlyphSheet<'a> {
+    /// Creates a glyph sheet importer from a PNG file.
+    pub fn with_file<P: AsRef<Path>>(png_file: P, cell_size: Size) -> Self {
+        Self::new(FileOrData::File(png_file.as_ref().to_owned()), cell_size)
+    }
+
+    /// Creates a glyph sheet importer from PNG data.
+    pub fn with_data(png: &'a [u8], cell_size: Size) -> Self {
+        Self::new(FileOrData::Data(png), cell_size)
+    }
+
+    fn new(png: FileOrData<'a>, cell_size: Size) -> Self {
+        Self {
+            png,
+            cell_size,
+            glyphs: Vec::new(),
+            baseline: None,
+            width_mode: WidthMode::default(),
+            inverted: false,
+        }
+    }
+
+    /// Adds characters to the glyph sheet.
+    ///
+    /// The characters are assigned to the cells in the order they were added.
+    /// See [`FontConverter::glyphs`](crate::FontConverter::glyphs) for the
+    /// supported argument types.
+    pub fn glyphs<G: GlyphRange>(mut self, glyphs: G) -> Self {
+        self.glyphs.extend(glyphs.glyphs());
+
+        self
+    }
+
+    /// Sets the baseline row.
+    ///
+    /// The baseline row is the lowest row in each cell which is used by glyphs
+    /// without descenders. All rows below the baseline row are used for the
+    /// font descent.
+    ///
+    /// Defaults to the last row in each cell.
+    pub fn baseline(mut self, row: u32) -> Self {
+        self.baseline = Some(row);
+
+        self
+    }
+
+    /// Sets the glyph width mode.
+    ///
+    /// Defaults to [`WidthMode::Cell`].
+    pub fn width_mode(mut self, width_mode: WidthMode) -> Self {
+        self.width_mode = width_mode;
+
+        self
+    }
+
+    /// Treats light pixels as set pixels.
+    ///
+    /// By default dark pixels are set and light or transparent pixels are
+    /// unset. After calling this method light pixels are set instead.
+    /// Transparent pixels are always unset.
+    pub fn inverted(mut self) -> Self {
+        self.inverted = true;
+
+        self
+    }
+
+    /// Imports the glyph sheet.
+    pub fn import(&self) -> Result<Font> {
+        let Size { width, height } = self.cell_size;
+        ensure!(width > 0 && height > 0, "cell size must not be zero");
+
+        let baseline = self.baseline.unwrap_or(height - 1);
+        ensure!(
+            baseline < height,
+            "baseline row ({baseline}) is outside the cell height ({height})"
+        );
+
+        let mut chars = BTreeSet::new();
+        if let Some(c) = self.glyphs.iter().find(|c| !chars.insert(**c)) {
+            bail!("duplicate character '{c}' (U+{:04X})", u32::from(*c));
+        }
+
+        let image = match &self.png {
+            FileOrData::File(file) => {
+                let data = std::fs::read(file)
+                    .with_context(|| format!("couldn't read PNG file from {file:?}"))?;
+                self.decode(&data)?
+            }
+            FileOrData::Data(data) => self.decode(data)?,
+        };
+
+        ensure!(
+            image.size.width % width == 0 && image.size.height % height == 0,
+            "image size ({}x{}) isn't a multiple of the cell size ({width}x{height})",
+            image.size.width,
+            image.size.height,
+        );
+
+        let columns = image.size.width / width;
+        let cells = columns * (image.size.height / height);
+        ensure!(
+            self.glyphs.len() <= cells as usize,
+            "glyph sheet contains {cells} cells, but {} characters were specified",
+            self.glyphs.len()
+        );
+
+        let metrics = Metrics {
+            ascent: baseline + 1,
+            descent: height - baseline - 1,
+        };
+
+        let glyphs = self
+            .glyphs
+            .iter()
+            .enumerate()
+            .map(|(index, c)| {
+                let index = index as u32;
+                let cell = Point::new(
+                    ((index % columns) * width) as i32,
+                    ((index / columns) * height) as i32,
+                );
+                self.glyph(*c, &image, cell, &metrics)
+            })
+            .collect::<Glyphs>();
+
+        let metadata = Metadata::for_imported_font(&glyphs, &metrics, height, true);
+
+        Ok(Font {
+            metadata,
+            glyphs,
+            metrics,
+        })
+    }
+
+    fn glyph(&self, c: char, image: &Image, cell: Point, metrics: &Metrics) -> Glyph {
+        let Size { width, height } = self.cell_size;
+        let pixel = |x: u32, y: u32| image.pixel(cell + Point::new(x as i32, y as i32));
+
+        let columns = (0..width)
+            .filter(|x| (0..height).any(|y| pixel(*x, y)))
+            .collect::<Vec<_>>();
+
+        let (left, right, device_width) = match (self.width_mode, columns.first(), columns.last()) {
+            (WidthMode::TrimToInk { spacing }, Some(left), Some(right)) => {
+                (*left, right + 1, right + 1 - left + spacing)
+            }
+            _ => (0, width, width),
+        };
+        let glyph_width = right - left;
+
+        // Each row is padded to a whole number of bytes.
+        let bytes_per_row = glyph_width.div_ceil(8) as usize;
+        let mut bitmap = vec![0; bytes_per_row * height as usize];
+        for y in 0..height {
+            for x in 0..glyph_width {
+                if pixel(left + x, y) {
+                    bitmap[(x / 8) as usize + bytes_per_row * y as usize] |= 0x80 >> (x % 8);
+                }
+            }
+        }
+
+        // The glyph cell is scaled to 1000 units for the scalable width.
+        let device_width = device_width as i32;
+        let scalable_width = device_width * 1000 / height as i32;
+
+        Glyph {
+            name: format!("U+{:04X}", u32::from(c)),
+            encoding: Encoding::Standard(c.into()),
+            width_horizontal: Some(GlyphWidth {
+                scalable: Coord::new(scalable_width, 0),
+                device: Coord::new(device_width, 0),
+            }),
+            width_vertical: None,
+            bounding_box: BoundingBox {
+                offset: Coord::new(0, -(metrics.descent as i32)),
+                size: Coord::new(glyph_width as i32, height as i32),
+            },
+            origin_offset: None,
+            bitmap,
+        }
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Image> {
+        let mut decoder = png::Decoder::new(Cursor::new(data));
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+
+        let mut reader = decoder.read_info().context("couldn't decode PNG image")?;
+        let mut buffer = vec![
+            0;
+            reader
+                .output_buffer_size()
+                .context("PNG image is too large")?
+        ];
+        let info = reader
+            .next_frame(&mut buffer)
+            .context("couldn't decode PNG image")?;
+
+        let samples = info.color_type.samples();
+        let pixels = buffer[..info.buffer_size()]
+            .chunks_exact(samples)
+            .map(|pixel| {
+                let (luma, alpha) = match pixel {
+                    [luma] => (*luma, 255),
+                    [luma, alpha] => (*luma, *alpha),
+                    [r, g, b] => (luma(*r, *g, *b), 255),
+                    [r, g, b, alpha] => (luma(*r, *g, *b), *alpha),
+                    _ => unreachable!(),
+                };
+
+                alpha >= 128 && ((luma < 128) != self.inverted)
+            })
+            .collect();
+
+        Ok(Image {
+            size: Size::new(info.width, info.height),
+            pixels,
+        })
+    }
+}
+
+/// Calculates the luma of an RGB color.
+pub(crate) fn luma(r: u8, g: u8, b: u8) -> u8 {
+    ((u32::from(r) * 299 + u32::from(g) * 587 + u32::from(b) * 114) / 1000) as u8
+}
+
+/// Decoded binary image.
+#[derive(Debug)]
+struct Image {
+    size: Size,
+    pixels: Vec<bool>,
+}
+
+impl Image {
+    fn pixel(&self, point: Point) -> bool {
+        self.pixels[point.x as usize + point.y as usize * self.size.width as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FontConverter;
+
+    /// Encodes a PNG image.
+    fn png(size: Size, color_type: png::ColorType, data: &[u8]) -> Vec<u8> {
+        let mut png = Vec::new();
+
+        let mut encoder = png::Encoder::new(&mut png, size.width, size.height);
+        encoder.set_color(color_type);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(data).unwrap();
+        writer.finish().unwrap();
+
+        png
+    }
+
+    /// Glyph sheet with 2x1 cells of 4x3 pixels, which contains an `A` and a `.`.
+    const SHEET: &[&str] = &[
+        ".#......", //
+        "#.#.....", //
+        "###..#..", //
+    ];
+
+    fn sheet_png(color_type: png::ColorType) -> Vec<u8> {
+        let pixels = SHEET.iter().flat_map(|row| row.chars());
+
+        let data = match color_type {
+            png::ColorType::Grayscale => pixels
+                .map(|c| if c == '#' { 0 } else { 255 })
+                .collect::<Vec<_>>(),
+            png::ColorType::Rgba => pixels
+                .flat_map(|c| {
+                    if c == '#' {
+                        [255, 0, 0, 255]
+                    } else {
+                        [255, 255, 255, 0]
+                    }
+                })
+                .collect(),
+            _ => unreachable!(),
+        };
+
+        png(Size::new(8, 3), color_type, &data)
+    }
+
+    #[test]
+    fn cell_width() {
+        let png = sheet_png(png::ColorType::Grayscale);
+        let font = GlyphSheet::with_data(&png, Size::new(4, 3))
+            .glyphs("A.")
+            .baseline(1)
+            .import()
+            .unwrap();
+
+        assert_eq!(
+            font.metrics,
+            Metrics {
+                ascent: 2,
+                descent: 1
+            }
+        );
+
+        let a = font.glyphs.get('A').unwrap();
+        assert_eq!(a.name, "U+0041");
+        assert_eq!(
+            a.bounding_box,
+            BoundingBox {
+                offset: Coord::new(0, -1),
+                size: Coord::new(4, 3),
+            }
+        );
+        assert_eq!(a.width_horizontal.unwrap().device, Coord::new(4, 0));
+        assert_eq!(a.bitmap, [0x40, 0xA0, 0xE0]);
+
+        let dot = font.glyphs.get('.').unwrap();
+        assert_eq!(dot.bitmap, [0x00, 0x00, 0x40]);
+    }
+
+    #[test]
+    fn trim_to_ink() {
+        let png = sheet_png(png::ColorType::Rgba);
+        let font = GlyphSheet::with_data(&png, Size::new(4, 3))
+            .glyphs("A.")
+            .width_mode(WidthMode::TrimToInk { spacing: 1 })
+            .import()
+            .unwrap();
+
+        let a = font.glyphs.get('A').unwrap();
+        assert_eq!(a.bounding_box.size, Coord::new(3, 3));
+        assert_eq!(a.width_horizontal.unwrap().device, Coord::new(4, 0));
+        assert_eq!(a.bitmap, [0x40, 0xA0, 0xE0]);
+
+        let dot = font.glyphs.get('.').unwrap();
+        assert_eq!(dot.bounding_box.size, Coord::new(1, 3));
+        assert_eq!(dot.width_horizontal.unwrap().device, Coord::new(2, 0));
+        assert_eq!(dot.bitmap, [0x00, 0x00, 0x80]);
+    }
+
+    #[test]
+    fn invalid_sheets() {
+        let png = sheet_png(png::ColorType::Grayscale);
+
+        for (sheet, message) in [
+            (
+                GlyphSheet::with_data(&png, Size::new(4, 3)).glyphs("ABC"),
+                "glyph sheet contains 2 cells, but 3 characters were specified",
+            ),
+            (
+                GlyphSheet::with_data(&png, Size::new(3, 3)),
+                "image size (8x3) isn't a multiple of the cell size (3x3)",
+            ),
+            (
+                GlyphSheet::with_data(&png, Size::new(4, 3)).baseline(3),
+                "baseline row (3) is outside the cell height (3)",
+            ),
+            (
+                GlyphSheet::with_data(&png, Size::new(4, 3)).glyphs("AA"),
+                "duplicate character 'A' (U+0041)",
+            ),
+        ] {
+            assert_eq!(sheet.import().unwrap_err().to_string(), message);
+        }
+    }
+
+    #[test]
+    fn mono_font_png_round_trip() {
+        let original = FontConverter::with_file("../eg-bdf-examples/examples/6x10.bdf", "TEST")
+            .glyphs(' '..='~')
+            .convert_mono_font()
+            .unwrap();
+
+        let file = std::env::temp_dir().join(format!(
+            "eg-font-converter-glyph-sheet-{}.png",
+            std::process::id()
+        ));
+        original.save_png(&file).unwrap();
+
+        // The PNG output contains white glyphs on a black background.
+        let font = GlyphSheet::with_file(&file, Size::new(6, 10))
+            .glyphs(' '..='~')
+            .baseline(7)
+            .inverted()
+            .import()
+            .unwrap();
+        std::fs::remove_file(file).unwrap();
+
+        let converted = FontConverter::with_font(&font, "TEST")
+            .convert_mono_font()
+            .unwrap();
+        assert_eq!(converted.data(), original.data());
+    }
+}
@@ -7,6 +7,17 @@
 //! [`eg-bdf`] crate and have the advantage that proportional fonts are
 //! supported.
 //!
+//! Fonts can also be converted into C code for the [Adafruit GFX library],
+//! the [u8g2 library] and the [LVGL library] by using
+//! [`FontConverter::convert_adafruit_gfx`], [`FontConverter::convert_u8g2`]
+//! and [`FontConverter::convert_lvgl`]. A plain C header with a configurable
+//! bitmap layout can be generated by using [`FontConverter::convert_c_header`]
+//! and fonts for game engine text renderers by using
+//! [`FontConverter::convert_bmfont`].
+//!
+//! [`Specimen`] generates SVG and HTML specimens of BDF fonts and converted
+//! fonts for design reviews.
+//!
 //! The crate can either be used as a library to convert fonts in a
 //! build script or as a command line to convert them ahead of time.
 //!
@@ -36,6 +47,14 @@
 //! The font can now be used like any other [`MonoFont`] by using the
 //! `FONT_6X10_AZ` constant in a [`MonoTextStyle`].
 //!
+//! Projects which convert many fonts can describe all conversions in a TOML
+//! manifest instead and convert them with a single call to [`convert_manifest`].
+//! The same manifest can be passed to the command line tool by using
+//! `eg-font-converter convert --manifest fonts.toml --output-dir <DIR>`.
+//!
+//! Fonts can also be converted at compile time without a build script by
+//! using the `bdf_font!` macro from the `eg-font-converter-macros` crate.
+//!
 //! # Using `eg_font_converter` as a command line tool
 //!
 //! Install the `eg-font-converter` tool using cargo:
@@ -43,20 +62,37 @@
 //! ```sh
 //! cargo install eg-font-converter
 //! ```
-//! Run `eg-font-converter --help` to see a list of all available options.
+//! Run `eg-font-converter --help` to see a list of all available subcommands
+//! and `eg-font-converter <SUBCOMMAND> --help` to see the options of a subcommand.
 //!
-//! The tool can now be used to convert BDF fonts:
+//! The `convert` subcommand can now be used to convert BDF fonts:
 //! ```sh
-//! eg-font-converter --glyph-range A Z --rust font.rs --data font.data 6x10.bdf FONT
+//! eg-font-converter convert --glyph-range A Z --rust font.rs --data font.data 6x10.bdf FONT
 //! ```
-//! If the `--glyph-range` or `--mapping` options are not specified to limit the
-//! selection of glyphs, all glyphs in the source font are included by default.
+//! If the `--glyph-range`, `--glyphs` or `--mapping` options are not specified
+//! to limit the selection of glyphs, all glyphs in the source font are included
+//! by default.
+//!
+//! Alternatively, the `--output-dir` option saves the Rust file and the data
+//! file to a directory. The generated font type can be selected by using the
+//! `--kind` option:
+//! ```sh
+//! eg-font-converter convert --kind eg-bdf --output-dir src/fonts 6x10.bdf FONT_6X10
+//! ```
+//!
+//! The other subcommands can be used to inspect fonts before they are converted:
+//! `info` prints the font metadata, `list-glyphs` lists all glyphs, `preview`
+//! renders a sample text and `subset` writes a BDF file with a reduced set of
+//! glyphs.
 //!
 //! The generated files can be included into a project by using the [`include!`]
 //! macro or with a `mod` statement, if the generated files are inside the
 //! project's `src` directory.
 //!
 //! [`embedded-graphics`]: embedded_graphics
+//! [Adafruit GFX library]: https://github.com/adafruit/Adafruit-GFX-Library
+//! [u8g2 library]: https://github.com/olikraus/u8g2
+//! [LVGL library]: https://lvgl.io
 //! [`eg-bdf`]: eg_bdf
 //! [`MonoFont`]: embedded_graphics::mono_font::MonoFont
 //! [`MonoTextStyle`]: embedded_graphics::mono_font::MonoTextStyle
@@ -74,25 +110,58 @@
 use anyhow::{anyhow, ensure, Context, Result};
 use bdf_parser::{Encoding, Font, Glyph};
 use embedded_graphics::mono_font::mapping::GlyphMapping;
+use flate2::read::GzDecoder;
 use std::{
     collections::BTreeSet,
+    io::Read,
     iter,
     ops::{Range, RangeInclusive},
     path::{Path, PathBuf},
+    str::FromStr,
 };
 
+pub use bdf_parser::{ConflictResolution, MergePolicy, MetricsAdjustment};
 pub use embedded_graphics::mono_font::mapping::Mapping;
+pub use unicode_blocks::UnicodeBlock;
 
+mod adafruit_gfx;
+mod bmfont;
+mod c_header;
+mod coverage;
 mod eg_bdf_font;
+mod glyph_sheet;
+mod lvgl;
+mod manifest;
 mod mono_font;
-
+mod specimen;
+mod truetype;
+mod u8g2;
+
+pub use adafruit_gfx::{AdafruitGfxOutput, GfxGlyph};
+pub use bmfont::{BmFontChar, BmFontOutput};
+pub use c_header::{BitOrder, CGlyph, CHeaderLayout, CHeaderOutput, LineAlignment, PixelOrder};
+pub use coverage::{unicode_blocks, Coverage, CoverageReport};
 pub use eg_bdf_font::EgBdfOutput;
+pub use glyph_sheet::{GlyphSheet, WidthMode};
+pub use lvgl::{LvglGlyph, LvglOutput};
+pub use manifest::convert_manifest;
 pub use mono_font::MonoFontOutput;
+pub use specimen::Specimen;
+pub use truetype::{BitmapStrike, OutlineRasterizer, RasterMode, StrikeExtractor};
+pub use u8g2::U8g2Output;
 
 #[derive(Debug)]
 enum FileOrString<'a> {
     File(PathBuf),
+    Files(Vec<PathBuf>),
     String(&'a str),
+    Font(&'a Font),
+}
+
+#[derive(Debug)]
+enum FileOrData<'a> {
+    File(PathBuf),
+    Data(&'a [u8]),
 }
 
 /// Font converter.
@@ -100,13 +169,16 @@ enum FileOrString<'a> {
 pub struct FontConverter<'a> {
     bdf: FileOrString<'a>,
     name: String,
-    file_stem: String, //TODO: make configurable
+    file_stem: String,
     replacement_character: Option<char>,
     constant_visibility: Visibility,
     embedded_graphics_crate_path: String,
     data_file_extension: String,
     data_file_path: Option<PathBuf>,
     comments: Vec<String>,
+    inline_png: bool,
+    inline_data: bool,
+    merge_policy: MergePolicy,
 
     glyphs: BTreeSet<char>,
     missing_glyph_substitute: Option<char>,
@@ -114,15 +186,44 @@ pub struct FontConverter<'a> {
 
 impl<'a> FontConverter<'a> {
     /// Creates a font converter from a BDF file.
+    ///
+    /// Files with a `.pcf` extension are parsed as PCF files. BDF and PCF files
+    /// can also be compressed with gzip, if the file name ends with `.gz`.
     pub fn with_file<P: AsRef<Path>>(bdf_file: P, name: &str) -> Self {
         Self::new(FileOrString::File(bdf_file.as_ref().to_owned()), name)
     }
 
+    /// Creates a font converter from multiple BDF files.
+    ///
+    /// The fonts are merged into a single font in the given order by using
+    /// [`Font::merge`](bdf_parser::Font::merge). The merge policy can be set by
+    /// calling [`merge_policy`](Self::merge_policy).
+    pub fn with_files<I, P>(bdf_files: I, name: &str) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        let files = bdf_files
+            .into_iter()
+            .map(|file| file.as_ref().to_owned())
+            .collect();
+
+        Self::new(FileOrString::Files(files), name)
+    }
+
     /// Creates a font converter from BDF data.
     pub fn with_string(bdf: &'a str, name: &str) -> Self {
         Self::new(FileOrString::String(bdf), name)
     }
 
+    /// Creates a font converter from a parsed font.
+    ///
+    /// This can be used to convert fonts which were loaded from other sources
+    /// than BDF files, e.g. by using [`Font::parse_hex`](bdf_parser::Font::parse_hex).
+    pub fn with_font(font: &'a Font, name: &str) -> Self {
+        Self::new(FileOrString::Font(font), name)
+    }
+
     fn new(file_or_data: FileOrString<'a>, name: &str) -> Self {
         Self {
             bdf: file_or_data,
@@ -134,6 +235,9 @@ impl<'a> FontConverter<'a> {
             data_file_extension: "data".to_string(),
             data_file_path: None,
             comments: Vec::new(),
+            inline_png: false,
+            inline_data: false,
+            merge_policy: MergePolicy::default(),
             glyphs: BTreeSet::new(),
             missing_glyph_substitute: None,
         }
@@ -245,6 +349,15 @@ impl<'a> FontConverter<'a> {
         self
     }
 
+    /// Sets the file stem of the generated files.
+    ///
+    /// Defaults to the lowercase constant name.
+    pub fn file_stem(mut self, file_stem: &str) -> Self {
+        self.file_stem = file_stem.to_string();
+
+        self
+    }
+
     /// Adds a documentation comments to the generated Rust code.
     pub fn comment(mut self, comment: &str) -> Self {
         self.comments.push(comment.to_string());
@@ -252,24 +365,68 @@ impl<'a> FontConverter<'a> {
         self
     }
 
+    /// Sets if a PNG image of the glyphs is inlined into the documentation comment.
+    ///
+    /// The image is embedded as a base64 encoded data URL, which increases
+    /// the size of the generated Rust code. Defaults to `false`.
+    pub fn inline_png(mut self, inline_png: bool) -> Self {
+        self.inline_png = inline_png;
+
+        self
+    }
+
+    /// Sets if the bitmap data is inlined into the generated Rust code.
+    ///
+    /// By default the bitmap data is included from the data file by using
+    /// `include_bytes`. If the data is inlined the generated code doesn't
+    /// depend on the data file, which is used by the `bdf_font` macro in the
+    /// `eg-font-converter-macros` crate. Defaults to `false`.
+    pub fn inline_data(mut self, inline_data: bool) -> Self {
+        self.inline_data = inline_data;
+
+        self
+    }
+
+    /// Sets the merge policy.
+    ///
+    /// The merge policy is used to combine the fonts if the converter was
+    /// created by using [`with_files`](Self::with_files).
+    pub fn merge_policy(mut self, policy: MergePolicy) -> Self {
+        self.merge_policy = policy;
+
+        self
+    }
+
     fn convert(&self) -> Result<ConvertedFont> {
         ensure!(
             is_valid_identifier(&self.name),
             "name is not a valid Rust identifier: {}",
             self.name
         );
+        ensure!(
+            self.constant_visibility.is_valid(),
+            "invalid constant visibility: {}",
+            self.constant_visibility.to_rust()
+        );
+        ensure!(!self.file_stem.is_empty(), "file stem must not be empty");
 
         let bdf = match &self.bdf {
-            FileOrString::File(file) => {
-                let data = std::fs::read(file)
-                    .with_context(|| format!("couldn't read BDF file from {file:?}"))?;
-
-                let str = String::from_utf8_lossy(&data);
-                Font::parse(&str)
+            FileOrString::File(file) => read_font_file(file)?,
+            FileOrString::Files(files) => {
+                let (first, rest) = files
+                    .split_first()
+                    .ok_or_else(|| anyhow!("no BDF files specified"))?;
+
+                rest.iter().try_fold(read_font_file(first)?, |font, file| {
+                    font.merge(&read_font_file(file)?, self.merge_policy)
+                        .with_context(|| format!("couldn't merge BDF file {file:?}"))
+                })?
             }
-            FileOrString::String(str) => Font::parse(str),
-        }
-        .with_context(|| "couldn't parse BDF file".to_string())?;
+            FileOrString::String(str) => {
+                Font::parse(str).with_context(|| "couldn't parse BDF file".to_string())?
+            }
+            FileOrString::Font(font) => (*font).clone(),
+        };
 
         let glyphs = if self.glyphs.is_empty() {
             bdf.glyphs.iter().cloned().collect()
@@ -278,12 +435,10 @@ impl<'a> FontConverter<'a> {
                 .iter()
                 .copied()
                 .map(|c| {
-                    let glyph_c =
-                        if bdf.glyphs.get(c).is_none() && self.missing_glyph_substitute.is_some() {
-                            self.missing_glyph_substitute.unwrap()
-                        } else {
-                            c
-                        };
+                    let glyph_c = match self.missing_glyph_substitute {
+                        Some(substitute) if bdf.glyphs.get(c).is_none() => substitute,
+                        _ => c,
+                    };
 
                     bdf.glyphs
                         .get(glyph_c)
@@ -322,6 +477,8 @@ impl<'a> FontConverter<'a> {
             data_file_extension: self.data_file_extension.clone(),
             data_file_path: self.data_file_path.clone(),
             comments: self.comments.clone(),
+            inline_png: self.inline_png,
+            inline_data: self.inline_data,
             underline_position,
             underline_thickness,
             strikethrough_position,
@@ -362,6 +519,82 @@ impl<'a> FontConverter<'a> {
             .and_then(EgBdfOutput::new)
             .and_then(MonoFontOutput::new)
     }
+
+    /// Converts the font into a C header for the [Adafruit GFX library].
+    ///
+    /// [Adafruit GFX library]: https://github.com/adafruit/Adafruit-GFX-Library
+    pub fn convert_adafruit_gfx(&self) -> Result<AdafruitGfxOutput> {
+        self.convert().and_then(AdafruitGfxOutput::new)
+    }
+
+    /// Converts the font into C source code for the [u8g2 library].
+    ///
+    /// [u8g2 library]: https://github.com/olikraus/u8g2
+    pub fn convert_u8g2(&self) -> Result<U8g2Output> {
+        self.convert().and_then(U8g2Output::new)
+    }
+
+    /// Converts the font into C source code for the [LVGL library].
+    ///
+    /// Unlike the other outputs, this output supports grayscale fonts with
+    /// 2, 4 or 8 bits per pixel.
+    ///
+    /// [LVGL library]: https://lvgl.io
+    pub fn convert_lvgl(&self) -> Result<LvglOutput> {
+        self.convert().and_then(LvglOutput::new)
+    }
+
+    /// Converts the font into a plain C header.
+    ///
+    /// The header doesn't depend on a specific graphics library and the
+    /// layout of the bitmaps is defined by `layout`.
+    pub fn convert_c_header(&self, layout: CHeaderLayout) -> Result<CHeaderOutput> {
+        self.convert()
+            .and_then(|font| CHeaderOutput::new(font, layout))
+    }
+
+    /// Converts the font into the AngelCode BMFont format.
+    ///
+    /// The output consists of a PNG atlas and a text or JSON descriptor.
+    pub fn convert_bmfont(&self) -> Result<BmFontOutput> {
+        self.convert()
+            .and_then(EgBdfOutput::new)
+            .and_then(BmFontOutput::new)
+    }
+}
+
+/// Reads a font file.
+///
+/// Files with a `.pcf` extension are parsed as PCF files and all other files
+/// as BDF files. BDF and PCF files can also be compressed with gzip, if the
+/// file name ends with `.gz`.
+pub fn read_font_file<P: AsRef<Path>>(file: P) -> Result<Font> {
+    let file = file.as_ref();
+    let mut data =
+        std::fs::read(file).with_context(|| format!("couldn't read font file from {file:?}"))?;
+
+    let has_extension = |file: &Path, extension: &str| {
+        file.extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case(extension))
+    };
+
+    let mut file = file.to_owned();
+    if has_extension(&file, "gz") {
+        let mut decompressed = Vec::new();
+        GzDecoder::new(data.as_slice())
+            .read_to_end(&mut decompressed)
+            .with_context(|| format!("couldn't decompress {file:?}"))?;
+
+        data = decompressed;
+        file.set_extension("");
+    }
+
+    if has_extension(&file, "pcf") {
+        Font::parse_pcf(&data).with_context(|| "couldn't parse PCF file".to_string())
+    } else {
+        let str = String::from_utf8_lossy(&data);
+        Font::parse(&str).with_context(|| "couldn't parse BDF file".to_string())
+    }
 }
 
 fn is_valid_identifier(ident: &str) -> bool {
@@ -379,6 +612,8 @@ struct ConvertedFont {
     pub data_file_extension: String,
     pub data_file_path: Option<PathBuf>,
     pub comments: Vec<String>,
+    pub inline_png: bool,
+    pub inline_data: bool,
 
     pub glyphs: Vec<Glyph>,
     pub replacement_character: usize,
@@ -401,6 +636,49 @@ impl ConvertedFont {
             .map(|(index, _)| index)
     }
 
+    fn ensure_monochrome(&self) -> Result<()> {
+        let bits_per_pixel = self.bdf.metadata.bits_per_pixel;
+        ensure!(
+            bits_per_pixel == 1,
+            "fonts with {bits_per_pixel} bits per pixel aren't supported by this output format"
+        );
+
+        Ok(())
+    }
+
+    /// Returns the lines of the documentation comment for the generated constant.
+    fn doc_lines(&self, png: impl FnOnce() -> Result<String>) -> Result<Vec<String>> {
+        let mut lines = self
+            .comments
+            .iter()
+            .map(|comment| format!(" {comment}"))
+            .collect::<Vec<_>>();
+
+        if self.inline_png {
+            if !lines.is_empty() {
+                lines.push(String::new());
+            }
+            lines.push(format!(
+                " ![{}](data:image/png;base64,{})",
+                self.name,
+                png()?
+            ));
+        }
+
+        Ok(lines)
+    }
+
+    /// Returns the expression for the bitmap data in the generated Rust code.
+    fn data_tokens(&self, data: &[u8]) -> impl quote::ToTokens {
+        if self.inline_data {
+            let data = proc_macro2::Literal::byte_string(data);
+            quote::quote!(#data)
+        } else {
+            let data_file = self.data_file().to_string_lossy().to_string();
+            quote::quote!(include_bytes!(#data_file))
+        }
+    }
+
     fn rust_file_path(&self, output_directory: &Path) -> PathBuf {
         output_directory.join(&self.file_stem).with_extension("rs")
     }
@@ -498,8 +776,31 @@ pub enum Visibility {
 }
 
 impl Visibility {
-    // TODO: is Visibility even used anymore?
-    #[allow(unused)]
+    fn is_valid(&self) -> bool {
+        match self {
+            Visibility::PubIn(path) => path
+                .split("::")
+                .all(|segment| is_valid_identifier(segment.trim())),
+            _ => true,
+        }
+    }
+
+    fn to_tokens(&self) -> impl quote::ToTokens {
+        match self {
+            Visibility::Private => quote::quote!(),
+            Visibility::Pub => quote::quote!(pub),
+            Visibility::PubCrate => quote::quote!(pub(crate)),
+            Visibility::PubSelf => quote::quote!(pub(self)),
+            Visibility::PubSuper => quote::quote!(pub(super)),
+            Visibility::PubIn(path) => {
+                let segments = path
+                    .split("::")
+                    .map(|segment| quote::format_ident!("{}", segment.trim()));
+                quote::quote!(pub(in #(#segments)::*))
+            }
+        }
+    }
+
     fn to_rust(&self) -> String {
         match self {
             Visibility::Private => "",
@@ -513,9 +814,47 @@ impl Visibility {
     }
 }
 
+impl FromStr for Visibility {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+
+        let visibility = match s {
+            "" | "private" => Visibility::Private,
+            "pub" => Visibility::Pub,
+            _ => {
+                let inner = s
+                    .strip_prefix("pub")
+                    .map(str::trim_start)
+                    .and_then(|s| s.strip_prefix('('))
+                    .and_then(|s| s.strip_suffix(')'))
+                    .map(str::trim)
+                    .ok_or_else(|| anyhow!("invalid visibility: {s}"))?;
+
+                match inner {
+                    "crate" => Visibility::PubCrate,
+                    "self" => Visibility::PubSelf,
+                    "super" => Visibility::PubSuper,
+                    _ => inner
+                        .strip_prefix("in ")
+                        .map(|path| Visibility::PubIn(path.trim().to_string()))
+                        .ok_or_else(|| anyhow!("invalid visibility: {s}"))?,
+                }
+            }
+        };
+
+        ensure!(visibility.is_valid(), "invalid visibility: {s}");
+
+        Ok(visibility)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use flate2::{write::GzEncoder, Compression};
+    use std::io::Write;
 
     const FONT: &str = r#"
         STARTFONT 2.1
@@ -575,6 +914,73 @@ mod tests {
             .eq(['A', 'B', 'C', 'E', 'G', 'H', 'V', 'W', 'Y'].iter()));
     }
 
+    #[test]
+    fn with_files() {
+        let converter = FontConverter::with_files(
+            [
+                "../eg-bdf-examples/examples/6x10.bdf",
+                "../eg-bdf-examples/examples/10x20.bdf",
+            ],
+            "TEST",
+        )
+        .glyphs('A')
+        .glyphs('\u{3E8}');
+        let font = converter.convert().unwrap();
+
+        // 'A' is taken from the first font and 'Ϩ' from the second font.
+        assert_eq!(font.glyphs[0].bounding_box.size.y, 10);
+        assert_eq!(font.glyphs[1].bounding_box.size.y, 20);
+    }
+
+    #[test]
+    fn pcf_file() {
+        let bdf = FontConverter::with_file("../eg-bdf-examples/examples/6x10.bdf", "TEST")
+            .glyphs('A'..='Z')
+            .convert()
+            .unwrap();
+        let pcf = FontConverter::with_file("../bdf-parser/tests/pcf/6x10.pcf", "TEST")
+            .glyphs('A'..='Z')
+            .convert()
+            .unwrap();
+
+        assert_eq!(pcf.glyphs, bdf.glyphs);
+        assert_eq!(pcf.bdf.metrics, bdf.bdf.metrics);
+    }
+
+    #[test]
+    fn gzip_compressed_file() {
+        let pcf = std::fs::read("../bdf-parser/tests/pcf/6x10.pcf").unwrap();
+
+        let file = std::env::temp_dir().join(format!(
+            "eg-font-converter-6x10-{}.pcf.gz",
+            std::process::id()
+        ));
+        let mut encoder = GzEncoder::new(
+            std::fs::File::create(&file).unwrap(),
+            Compression::default(),
+        );
+        encoder.write_all(&pcf).unwrap();
+        encoder.finish().unwrap();
+
+        let font = FontConverter::with_file(&file, "TEST")
+            .glyphs('A')
+            .convert()
+            .unwrap();
+        assert_eq!(font.glyphs[0].name, "A");
+
+        std::fs::remove_file(file).unwrap();
+    }
+
+    #[test]
+    fn with_font() {
+        let font = Font::parse_hex("0041:0000000018242442427E424242420000\n", 14).unwrap();
+
+        let converted = FontConverter::with_font(&font, "TEST").convert().unwrap();
+        assert_eq!(converted.glyphs.len(), 1);
+        assert_eq!(converted.glyphs[0].name, "U+0041");
+        assert_eq!(converted.bdf.metrics.line_height(), 16);
+    }
+
     #[test]
     fn no_glyph_ranges() {
         let converter = FontConverter::with_string(FONT, "TEST");
@@ -583,4 +989,97 @@ mod tests {
         assert_eq!(font.glyphs[0].name, "A");
         assert_eq!(font.glyphs[0].encoding, Encoding::Standard(65));
     }
+
+    #[test]
+    fn parse_visibility() {
+        assert_eq!(
+            "private".parse::<Visibility>().unwrap(),
+            Visibility::Private
+        );
+        assert_eq!("pub".parse::<Visibility>().unwrap(), Visibility::Pub);
+        assert_eq!(
+            "pub(crate)".parse::<Visibility>().unwrap(),
+            Visibility::PubCrate
+        );
+        assert_eq!(
+            "pub (super)".parse::<Visibility>().unwrap(),
+            Visibility::PubSuper
+        );
+        assert_eq!(
+            "pub(in crate::fonts)".parse::<Visibility>().unwrap(),
+            Visibility::PubIn("crate::fonts".to_string())
+        );
+
+        assert!("public".parse::<Visibility>().is_err());
+        assert!("pub(in crate::)".parse::<Visibility>().is_err());
+    }
+
+    #[test]
+    fn constant_visibility() {
+        let converter = FontConverter::with_string(FONT, "TEST");
+
+        let font = converter
+            .constant_visibility(Visibility::PubIn("crate::fonts".to_string()))
+            .convert_eg_bdf()
+            .unwrap();
+        assert!(font
+            .rust()
+            .contains("pub(in crate::fonts) const TEST: ::eg_bdf::BdfFont"));
+
+        let font = FontConverter::with_string(FONT, "TEST")
+            .constant_visibility(Visibility::Private)
+            .convert_mono_font()
+            .unwrap();
+        assert!(font
+            .rust()
+            .starts_with("const TEST: ::embedded_graphics::mono_font::MonoFont"));
+
+        assert!(FontConverter::with_string(FONT, "TEST")
+            .constant_visibility(Visibility::PubIn("1".to_string()))
+            .convert()
+            .is_err());
+    }
+
+    #[test]
+    fn file_stem() {
+        let font = FontConverter::with_string(FONT, "TEST")
+            .file_stem("font")
+            .data_file_extension("bin")
+            .data_file_path("data")
+            .convert()
+            .unwrap();
+
+        assert_eq!(
+            font.rust_file_path("out".as_ref()),
+            Path::new("out/font.rs")
+        );
+        assert_eq!(
+            font.data_file_path("out".as_ref()),
+            Path::new("out/data/font.bin")
+        );
+    }
+
+    #[test]
+    fn inline_png() {
+        let font = FontConverter::with_string(FONT, "TEST")
+            .comment("Comment")
+            .inline_png(true)
+            .convert_eg_bdf()
+            .unwrap();
+
+        let rust = font.rust();
+        assert!(rust.starts_with("/// Comment\n///\n/// ![TEST](data:image/png;base64,"));
+    }
+
+    #[test]
+    fn inline_data() {
+        let font = FontConverter::with_string(FONT, "TEST")
+            .inline_data(true)
+            .convert_mono_font()
+            .unwrap();
+
+        let rust = font.rust();
+        assert!(!rust.contains("include_bytes!"));
+        assert!(rust.contains("ImageRaw::new(\n        b\"\\xFF"));
+    }
 }
@@ -0,0 +1,719 @@
+use std::{fmt::Write, fs, io, path::Path};
+
+use anyhow::{bail, ensure, Context, Result};
+use bdf_parser::{Encoding, Glyph};
+use bitvec::{prelude::*, vec::BitVec};
+
+use crate::ConvertedFont;
+
+/// Number of bytes per line in the generated bitmap array.
+const BYTES_PER_LINE: usize = 16;
+
+/// Minimum number of consecutive code points which are stored in a range cmap.
+///
+/// Shorter sequences are added to a sparse cmap, because each additional cmap
+/// uses more memory than the entries in a sparse cmap.
+const MIN_RANGE_LENGTH: usize = 16;
+
+/// Maximum size of the bitmap data, which is limited by the 20 bit
+/// `bitmap_index` field.
+const MAX_BITMAP_SIZE: usize = 1 << 20;
+
+/// Maximum advance width in 1/16 pixels, which is limited by the 12 bit
+/// `adv_w` field.
+const MAX_ADV_W: u32 = (1 << 12) - 1;
+
+/// LVGL glyph descriptor.
+///
+/// Corresponds to the `lv_font_fmt_txt_glyph_dsc_t` struct in LVGL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LvglGlyph {
+    /// Offset of the glyph in the bitmap data.
+    pub bitmap_index: u32,
+    /// Horizontal distance to the next glyph in 1/16 pixels.
+    pub adv_w: u32,
+    /// Bitmap width.
+    pub box_w: u8,
+    /// Bitmap height.
+    pub box_h: u8,
+    /// Horizontal distance from the cursor position to the left edge of the bitmap.
+    pub ofs_x: i8,
+    /// Vertical distance from the baseline to the bottom edge of the bitmap.
+    pub ofs_y: i8,
+}
+
+/// Character map type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CmapType {
+    /// Consecutive code points with consecutive glyph IDs.
+    Range,
+    /// List of code points with consecutive glyph IDs.
+    Sparse,
+}
+
+/// Character map.
+///
+/// Corresponds to the `lv_font_fmt_txt_cmap_t` struct in LVGL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Cmap {
+    range_start: u32,
+    range_length: u32,
+    glyph_id_start: usize,
+    /// Code points relative to `range_start`, only used for sparse cmaps.
+    unicode_list: Vec<u16>,
+    cmap_type: CmapType,
+}
+
+/// Font conversion output for the [LVGL] library.
+///
+/// The output is C source code, which contains an `lv_font_t` that uses
+/// the `lv_font_fmt_txt` format of the built-in LVGL fonts. The bitmaps
+/// use the same number of bits per pixel as the BDF font, which makes it
+/// possible to convert grayscale BDF fonts with 2 or 4 bits per pixel.
+///
+/// [LVGL]: https://lvgl.io
+#[derive(Debug)]
+pub struct LvglOutput {
+    font: ConvertedFont,
+    data: Vec<u8>,
+    glyphs: Vec<(u32, LvglGlyph)>,
+    cmaps: Vec<Cmap>,
+}
+
+impl LvglOutput {
+    pub(crate) fn new(font: ConvertedFont) -> Result<Self> {
+        let bits_per_pixel = font.bdf.metadata.bits_per_pixel;
+        ensure!(
+            matches!(bits_per_pixel, 1 | 2 | 4 | 8),
+            "fonts with {bits_per_pixel} bits per pixel aren't supported by this output format"
+        );
+
+        let mut glyphs = font
+            .glyphs
+            .iter()
+            .filter_map(|glyph| match glyph.encoding {
+                Encoding::Standard(code_point) => Some((code_point, glyph)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        glyphs.sort_by_key(|(code_point, _)| *code_point);
+        glyphs.dedup_by_key(|(code_point, _)| *code_point);
+
+        if glyphs.is_empty() {
+            bail!("font doesn't contain any glyphs with a standard encoding");
+        }
+        ensure!(
+            glyphs.len() < usize::from(u16::MAX),
+            "font contains too many glyphs"
+        );
+
+        let mut data = BitVec::<u8, Msb0>::new();
+        let mut lvgl_glyphs = Vec::new();
+
+        for (code_point, glyph) in &glyphs {
+            let error =
+                |field: &str| format!("{field} of glyph \"{}\" is out of range", glyph.name);
+
+            let size = glyph.bounding_box.size;
+            let offset = glyph.bounding_box.offset;
+
+            let lvgl_glyph = LvglGlyph {
+                bitmap_index: (data.len() / 8) as u32,
+                adv_w: u32::try_from(device_width(glyph) * 16)
+                    .ok()
+                    .filter(|adv_w| *adv_w <= MAX_ADV_W)
+                    .with_context(|| error("adv_w"))?,
+                box_w: u8::try_from(size.x).with_context(|| error("width"))?,
+                box_h: u8::try_from(size.y).with_context(|| error("height"))?,
+                ofs_x: i8::try_from(offset.x).with_context(|| error("ofs_x"))?,
+                ofs_y: i8::try_from(offset.y).with_context(|| error("ofs_y"))?,
+            };
+
+            // The bitmap of each glyph starts at a byte boundary, but the rows
+            // aren't padded.
+            for y in 0..usize::from(lvgl_glyph.box_h) {
+                for x in 0..usize::from(lvgl_glyph.box_w) {
+                    let value = glyph.pixel_value(x, y, bits_per_pixel).unwrap_or_default();
+                    for bit in (0..bits_per_pixel).rev() {
+                        data.push(value & (1 << bit) != 0);
+                    }
+                }
+            }
+            data.resize(data.len().next_multiple_of(8), false);
+
+            lvgl_glyphs.push((*code_point, lvgl_glyph));
+        }
+
+        let data = data.into_vec();
+        ensure!(
+            data.len() <= MAX_BITMAP_SIZE,
+            "bitmap data exceeds the maximum size of {MAX_BITMAP_SIZE} bytes"
+        );
+
+        let code_points = glyphs
+            .iter()
+            .map(|(code_point, _)| *code_point)
+            .collect::<Vec<_>>();
+
+        Ok(Self {
+            font,
+            data,
+            glyphs: lvgl_glyphs,
+            cmaps: cmaps(&code_points),
+        })
+    }
+
+    /// Returns the generated C source code.
+    pub fn source(&self) -> String {
+        let name = &self.font.name;
+        let metrics = &self.font.bdf.metrics;
+        let mut output = String::new();
+
+        for comment in &self.font.comments {
+            writeln!(output, "// {comment}").unwrap();
+        }
+        if !self.font.comments.is_empty() {
+            output.push('\n');
+        }
+
+        writeln!(output, "#ifdef LV_LVGL_H_INCLUDE_SIMPLE").unwrap();
+        writeln!(output, "#include \"lvgl.h\"").unwrap();
+        writeln!(output, "#else").unwrap();
+        writeln!(output, "#include \"lvgl/lvgl.h\"").unwrap();
+        writeln!(output, "#endif\n").unwrap();
+
+        // Bitmaps
+        writeln!(
+            output,
+            "static LV_ATTRIBUTE_LARGE_CONST const uint8_t {name}_glyph_bitmap[] = {{"
+        )
+        .unwrap();
+        for (index, (code_point, glyph)) in self.glyphs.iter().enumerate() {
+            let start = glyph.bitmap_index as usize;
+            let end = self
+                .glyphs
+                .get(index + 1)
+                .map_or(self.data.len(), |(_, glyph)| glyph.bitmap_index as usize);
+            if start == end {
+                continue;
+            }
+
+            writeln!(output, "    /* {} */", describe(*code_point)).unwrap();
+            for line in self.data[start..end].chunks(BYTES_PER_LINE) {
+                let bytes = line
+                    .iter()
+                    .map(|byte| format!("0x{byte:02x}"))
+                    .collect::<Vec<_>>();
+                writeln!(output, "    {},", bytes.join(", ")).unwrap();
+            }
+        }
+        // Empty arrays aren't allowed in C.
+        if self.data.is_empty() {
+            writeln!(output, "    0x00").unwrap();
+        }
+        writeln!(output, "}};\n").unwrap();
+
+        // Glyph descriptors
+        writeln!(
+            output,
+            "static const lv_font_fmt_txt_glyph_dsc_t {name}_glyph_dsc[] = {{"
+        )
+        .unwrap();
+        writeln!(
+            output,
+            "    {{.bitmap_index = 0, .adv_w = 0, .box_w = 0, .box_h = 0, .ofs_x = 0, .ofs_y = 0}} /* id = 0 reserved */,"
+        )
+        .unwrap();
+        for (code_point, glyph) in &self.glyphs {
+            let LvglGlyph {
+                bitmap_index,
+                adv_w,
+                box_w,
+                box_h,
+                ofs_x,
+                ofs_y,
+            } = glyph;
+
+            writeln!(
+                output,
+                "    {{.bitmap_index = {bitmap_index}, .adv_w = {adv_w}, .box_w = {box_w}, .box_h = {box_h}, .ofs_x = {ofs_x}, .ofs_y = {ofs_y}}}, /* {} */",
+                describe(*code_point)
+            )
+            .unwrap();
+        }
+        writeln!(output, "}};\n").unwrap();
+
+        // Character maps
+        for (index, cmap) in self.cmaps.iter().enumerate() {
+            if cmap.cmap_type != CmapType::Sparse {
+                continue;
+            }
+
+            writeln!(
+                output,
+                "static const uint16_t {name}_unicode_list_{index}[] = {{"
+            )
+            .unwrap();
+            for line in cmap.unicode_list.chunks(BYTES_PER_LINE / 2) {
+                let offsets = line
+                    .iter()
+                    .map(|offset| format!("0x{offset:x}"))
+                    .collect::<Vec<_>>();
+                writeln!(output, "    {},", offsets.join(", ")).unwrap();
+            }
+            writeln!(output, "}};\n").unwrap();
+        }
+
+        writeln!(
+            output,
+            "static const lv_font_fmt_txt_cmap_t {name}_cmaps[] = {{"
+        )
+        .unwrap();
+        for (index, cmap) in self.cmaps.iter().enumerate() {
+            let (unicode_list, list_length, cmap_type) = match cmap.cmap_type {
+                CmapType::Range => ("NULL".to_string(), 0, "LV_FONT_FMT_TXT_CMAP_FORMAT0_TINY"),
+                CmapType::Sparse => (
+                    format!("{name}_unicode_list_{index}"),
+                    cmap.unicode_list.len(),
+                    "LV_FONT_FMT_TXT_CMAP_SPARSE_TINY",
+                ),
+            };
+
+            writeln!(output, "    {{").unwrap();
+            writeln!(
+                output,
+                "        .range_start = {}, .range_length = {}, .glyph_id_start = {},",
+                cmap.range_start, cmap.range_length, cmap.glyph_id_start
+            )
+            .unwrap();
+            writeln!(
+                output,
+                "        .unicode_list = {unicode_list}, .glyph_id_ofs_list = NULL, .list_length = {list_length}, .type = {cmap_type}"
+            )
+            .unwrap();
+            writeln!(output, "    }},").unwrap();
+        }
+        writeln!(output, "}};\n").unwrap();
+
+        // Font descriptor
+        writeln!(output, "#if LVGL_VERSION_MAJOR == 8").unwrap();
+        writeln!(output, "static lv_font_fmt_txt_glyph_cache_t {name}_cache;").unwrap();
+        writeln!(output, "#endif\n").unwrap();
+
+        writeln!(output, "#if LVGL_VERSION_MAJOR >= 8").unwrap();
+        writeln!(
+            output,
+            "static const lv_font_fmt_txt_dsc_t {name}_font_dsc = {{"
+        )
+        .unwrap();
+        writeln!(output, "#else").unwrap();
+        writeln!(output, "static lv_font_fmt_txt_dsc_t {name}_font_dsc = {{").unwrap();
+        writeln!(output, "#endif").unwrap();
+        writeln!(output, "    .glyph_bitmap = {name}_glyph_bitmap,").unwrap();
+        writeln!(output, "    .glyph_dsc = {name}_glyph_dsc,").unwrap();
+        writeln!(output, "    .cmaps = {name}_cmaps,").unwrap();
+        writeln!(output, "    .kern_dsc = NULL,").unwrap();
+        writeln!(output, "    .kern_scale = 0,").unwrap();
+        writeln!(output, "    .cmap_num = {},", self.cmaps.len()).unwrap();
+        writeln!(output, "    .bpp = {},", self.bits_per_pixel()).unwrap();
+        writeln!(output, "    .kern_classes = 0,").unwrap();
+        writeln!(output, "    .bitmap_format = 0,").unwrap();
+        writeln!(output, "#if LVGL_VERSION_MAJOR == 8").unwrap();
+        writeln!(output, "    .cache = &{name}_cache").unwrap();
+        writeln!(output, "#endif").unwrap();
+        writeln!(output, "}};\n").unwrap();
+
+        // Public font
+        let underline_position = metrics.ascent as i32 - self.font.underline_position as i32;
+
+        writeln!(output, "#if LVGL_VERSION_MAJOR >= 8").unwrap();
+        writeln!(output, "const lv_font_t {name} = {{").unwrap();
+        writeln!(output, "#else").unwrap();
+        writeln!(output, "lv_font_t {name} = {{").unwrap();
+        writeln!(output, "#endif").unwrap();
+        writeln!(
+            output,
+            "    .get_glyph_dsc = lv_font_get_glyph_dsc_fmt_txt,"
+        )
+        .unwrap();
+        writeln!(
+            output,
+            "    .get_glyph_bitmap = lv_font_get_bitmap_fmt_txt,"
+        )
+        .unwrap();
+        writeln!(output, "    .line_height = {},", metrics.line_height()).unwrap();
+        writeln!(output, "    .base_line = {},", metrics.descent).unwrap();
+        writeln!(output, "    .subpx = LV_FONT_SUBPX_NONE,").unwrap();
+        writeln!(output, "    .underline_position = {underline_position},").unwrap();
+        writeln!(
+            output,
+            "    .underline_thickness = {},",
+            self.font.underline_thickness
+        )
+        .unwrap();
+        writeln!(output, "    .dsc = &{name}_font_dsc,").unwrap();
+        writeln!(
+            output,
+            "#if LVGL_VERSION_MAJOR > 8 || (LVGL_VERSION_MAJOR == 8 && LVGL_VERSION_MINOR >= 2)"
+        )
+        .unwrap();
+        writeln!(output, "    .fallback = NULL,").unwrap();
+        writeln!(output, "#endif").unwrap();
+        writeln!(output, "    .user_data = NULL,").unwrap();
+        writeln!(output, "}};").unwrap();
+
+        output
+    }
+
+    /// Returns the bitmap data.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns the glyph descriptors and the associated code points.
+    ///
+    /// The glyphs are sorted by code point. The glyph with index `n` in the
+    /// returned slice has the glyph ID `n + 1`, because glyph ID 0 is
+    /// reserved in LVGL.
+    pub fn glyphs(&self) -> &[(u32, LvglGlyph)] {
+        &self.glyphs
+    }
+
+    /// Returns the number of bits per pixel.
+    pub fn bits_per_pixel(&self) -> u32 {
+        self.font.bdf.metadata.bits_per_pixel
+    }
+
+    /// Saves the C source code to the given directory.
+    ///
+    /// The file name is the file stem with a `.c` extension.
+    pub fn save<P: AsRef<Path>>(&self, output_directory: P) -> io::Result<()> {
+        let path = output_directory
+            .as_ref()
+            .join(&self.font.file_stem)
+            .with_extension("c");
+
+        fs::write(path, self.source())
+    }
+}
+
+fn device_width(glyph: &Glyph) -> i32 {
+    glyph
+        .width_horizontal
+        .map(|width| width.device.x)
+        .unwrap_or_default()
+}
+
+/// Returns a description of a code point for use in C comments.
+fn describe(code_point: u32) -> String {
+    match char::from_u32(code_point) {
+        Some(c) if !c.is_control() => format!("U+{code_point:04X} \"{c}\""),
+        _ => format!("U+{code_point:04X}"),
+    }
+}
+
+/// Creates the character maps for the sorted code points.
+///
+/// Long sequences of consecutive code points are stored in range cmaps and
+/// all other code points in sparse cmaps. Glyph IDs start at 1, because ID
+/// 0 is reserved.
+///
+/// The `range_length` field is 16 bits wide, which is why longer sequences
+/// are split into multiple range cmaps.
+fn cmaps(code_points: &[u32]) -> Vec<Cmap> {
+    let mut cmaps: Vec<Cmap> = Vec::new();
+
+    let mut glyph_id = 1;
+    let mut rest = code_points;
+    while let Some(&start) = rest.first() {
+        let length = rest
+            .iter()
+            .zip(start..)
+            .take_while(|(code_point, expected)| *code_point == expected)
+            .take(usize::from(u16::MAX))
+            .count();
+
+        if length >= MIN_RANGE_LENGTH {
+            cmaps.push(Cmap {
+                range_start: start,
+                range_length: length as u32,
+                glyph_id_start: glyph_id,
+                unicode_list: Vec::new(),
+                cmap_type: CmapType::Range,
+            });
+        } else {
+            // Extend the previous sparse cmap, if the offsets fit into 16 bits.
+            let sparse = cmaps.last_mut().filter(|cmap| {
+                cmap.cmap_type == CmapType::Sparse
+                    && rest[length - 1] - cmap.range_start < u32::from(u16::MAX)
+            });
+
+            let cmap = match sparse {
+                Some(cmap) => cmap,
+                None => {
+                    cmaps.push(Cmap {
+                        range_start: start,
+                        range_length: 0,
+                        glyph_id_start: glyph_id,
+                        unicode_list: Vec::new(),
+                        cmap_type: CmapType::Sparse,
+                    });
+                    cmaps.last_mut().unwrap()
+                }
+            };
+
+            for code_point in &rest[..length] {
+                cmap.unicode_list
+                    .push((code_point - cmap.range_start) as u16);
+            }
+            cmap.range_length = rest[length - 1] - cmap.range_start + 1;
+        }
+
+        glyph_id += length;
+        rest = &rest[length..];
+    }
+
+    cmaps
+}
+
+#[cfg(test)]
+mod tests {
+    use bdf_parser::Font;
+
+    use crate::FontConverter;
+
+    use super::*;
+
+    const FONT: &str = r#"
+        STARTFONT 2.1
+        FONT -test-Test-Medium-R-Normal--8-80-75-75-P-60-ISO10646-1
+        SIZE 8 75 75 2
+        FONTBOUNDINGBOX 6 8 0 -2
+        STARTPROPERTIES 2
+        FONT_ASCENT 6
+        FONT_DESCENT 2
+        ENDPROPERTIES
+        CHARS 3
+        STARTCHAR space
+        ENCODING 32
+        DWIDTH 4 0
+        BBX 0 0 0 0
+        BITMAP
+        ENDCHAR
+        STARTCHAR A
+        ENCODING 65
+        DWIDTH 6 0
+        BBX 3 2 1 0
+        BITMAP
+        1B
+        E4
+        ENDCHAR
+        STARTCHAR arrow
+        ENCODING 8592
+        DWIDTH 5 0
+        BBX 2 1 0 -1
+        BITMAP
+        F0
+        ENDCHAR
+        ENDFONT
+    "#;
+
+    #[test]
+    fn glyphs_and_bitmap() {
+        let output = FontConverter::with_string(FONT, "test_font")
+            .convert_lvgl()
+            .unwrap();
+
+        assert_eq!(output.bits_per_pixel(), 2);
+        assert_eq!(
+            output.glyphs(),
+            [
+                (
+                    0x20,
+                    LvglGlyph {
+                        bitmap_index: 0,
+                        adv_w: 64,
+                        box_w: 0,
+                        box_h: 0,
+                        ofs_x: 0,
+                        ofs_y: 0,
+                    }
+                ),
+                (
+                    0x41,
+                    LvglGlyph {
+                        bitmap_index: 0,
+                        adv_w: 96,
+                        box_w: 3,
+                        box_h: 2,
+                        ofs_x: 1,
+                        ofs_y: 0,
+                    }
+                ),
+                (
+                    0x2190,
+                    LvglGlyph {
+                        bitmap_index: 2,
+                        adv_w: 80,
+                        box_w: 2,
+                        box_h: 1,
+                        ofs_x: 0,
+                        ofs_y: -1,
+                    }
+                ),
+            ]
+        );
+
+        // A: 00 01 10, 11 10 01 (12 bits, padded to 16 bits)
+        // arrow: 11 11 (4 bits, padded to 8 bits)
+        assert_eq!(output.data(), [0x1B, 0x90, 0xF0]);
+    }
+
+    #[test]
+    fn source() {
+        let output = FontConverter::with_string(FONT, "test_font")
+            .comment("Test font")
+            .convert_lvgl()
+            .unwrap();
+
+        let source = output.source();
+        assert!(source.starts_with("// Test font\n\n#ifdef LV_LVGL_H_INCLUDE_SIMPLE\n"));
+        for expected in [
+            "    /* U+0041 \"A\" */\n    0x1b, 0x90,\n    /* U+2190 \"←\" */\n    0xf0,\n};\n",
+            "    {.bitmap_index = 0, .adv_w = 96, .box_w = 3, .box_h = 2, .ofs_x = 1, .ofs_y = 0}, /* U+0041 \"A\" */\n",
+            "static const uint16_t test_font_unicode_list_0[] = {\n    0x0, 0x21, 0x2170,\n};\n",
+            "        .range_start = 32, .range_length = 8561, .glyph_id_start = 1,\n",
+            "        .unicode_list = test_font_unicode_list_0, .glyph_id_ofs_list = NULL, .list_length = 3, .type = LV_FONT_FMT_TXT_CMAP_SPARSE_TINY\n",
+            "    .cmap_num = 1,\n    .bpp = 2,\n",
+            "    .line_height = 8,\n    .base_line = 2,\n",
+            "    .underline_position = -1,\n    .underline_thickness = 1,\n",
+        ] {
+            assert!(source.contains(expected), "{expected}\n\n{source}");
+        }
+    }
+
+    #[test]
+    fn range_and_sparse_cmaps() {
+        let code_points = [0x10, 0x12]
+            .into_iter()
+            .chain(0x20..0x7F)
+            .chain([0x80, 0x2190, 0x2191])
+            .chain([0x20000, 0x30000])
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            cmaps(&code_points),
+            [
+                Cmap {
+                    range_start: 0x10,
+                    range_length: 3,
+                    glyph_id_start: 1,
+                    unicode_list: vec![0, 2],
+                    cmap_type: CmapType::Sparse,
+                },
+                Cmap {
+                    range_start: 0x20,
+                    range_length: 95,
+                    glyph_id_start: 3,
+                    unicode_list: Vec::new(),
+                    cmap_type: CmapType::Range,
+                },
+                Cmap {
+                    range_start: 0x80,
+                    range_length: 0x2112,
+                    glyph_id_start: 98,
+                    unicode_list: vec![0, 0x2110, 0x2111],
+                    cmap_type: CmapType::Sparse,
+                },
+                Cmap {
+                    range_start: 0x20000,
+                    range_length: 1,
+                    glyph_id_start: 101,
+                    unicode_list: vec![0],
+                    cmap_type: CmapType::Sparse,
+                },
+                Cmap {
+                    range_start: 0x30000,
+                    range_length: 1,
+                    glyph_id_start: 102,
+                    unicode_list: vec![0],
+                    cmap_type: CmapType::Sparse,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn long_range_and_sparse_cmaps() {
+        let code_points = (0x10000..0x30000)
+            .chain([0x40000, 0x4FFFD, 0x4FFFF])
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            cmaps(&code_points),
+            [
+                Cmap {
+                    range_start: 0x10000,
+                    range_length: 0xFFFF,
+                    glyph_id_start: 1,
+                    unicode_list: Vec::new(),
+                    cmap_type: CmapType::Range,
+                },
+                Cmap {
+                    range_start: 0x1FFFF,
+                    range_length: 0xFFFF,
+                    glyph_id_start: 0x10000,
+                    unicode_list: Vec::new(),
+                    cmap_type: CmapType::Range,
+                },
+                Cmap {
+                    range_start: 0x2FFFE,
+                    range_length: 2,
+                    glyph_id_start: 0x1FFFF,
+                    unicode_list: vec![0, 1],
+                    cmap_type: CmapType::Sparse,
+                },
+                Cmap {
+                    range_start: 0x40000,
+                    range_length: 0xFFFE,
+                    glyph_id_start: 0x20001,
+                    unicode_list: vec![0, 0xFFFD],
+                    cmap_type: CmapType::Sparse,
+                },
+                Cmap {
+                    range_start: 0x4FFFF,
+                    range_length: 1,
+                    glyph_id_start: 0x20003,
+                    unicode_list: vec![0],
+                    cmap_type: CmapType::Sparse,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn adv_w_out_of_range() {
+        let font = FONT.replace("DWIDTH 6 0", "DWIDTH 256 0");
+
+        let error = FontConverter::with_string(&font, "test_font")
+            .convert_lvgl()
+            .unwrap_err();
+
+        assert_eq!(error.to_string(), "adv_w of glyph \"A\" is out of range");
+    }
+
+    #[test]
+    fn unsupported_bits_per_pixel() {
+        let mut font = Font::parse(FONT).unwrap();
+        font.metadata.bits_per_pixel = 3;
+
+        let error = FontConverter::with_font(&font, "test_font")
+            .convert_lvgl()
+            .unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "fonts with 3 bits per pixel aren't supported by this output format"
+        );
+    }
+}
@@ -1,21 +1,224 @@
-use std::path::PathBuf;
+use std::{
+    fmt::Write,
+    io,
+    path::{Path, PathBuf},
+};
 
-use anyhow::{anyhow, Context, Result};
-use clap::{command, Parser};
-use eg_font_converter::FontConverter;
-use embedded_graphics::mono_font::mapping::Mapping;
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use bdf_parser::{Encoding, Font, Glyph};
+use clap::{Parser, Subcommand, ValueEnum};
+use eg_bdf::BdfTextStyle;
+use eg_font_converter::{
+    convert_manifest, read_font_file, BitOrder, CHeaderLayout, ConflictResolution, EgBdfOutput,
+    FontConverter, GlyphRange, LineAlignment, MergePolicy, MetricsAdjustment, MonoFontOutput,
+    OutlineRasterizer, PixelOrder, RasterMode, Specimen, StrikeExtractor, Visibility,
+};
+use embedded_graphics::{
+    mono_font::mapping::Mapping,
+    pixelcolor::BinaryColor,
+    prelude::*,
+    text::{Baseline, Text},
+};
+use embedded_graphics_simulator::{OutputSettingsBuilder, SimulatorDisplay};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about)]
 struct Args {
-    /// BDF file.
-    #[arg(required_unless_present = "list_mappings")]
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Convert a font into Rust or C code.
+    Convert(Box<ConvertArgs>),
+    /// Print the metadata, metrics, properties and glyph count of a font.
+    Info {
+        #[command(flatten)]
+        font: FontArgs,
+    },
+    /// Print the code point, name and width of all glyphs in a font.
+    ListGlyphs {
+        #[command(flatten)]
+        font: FontArgs,
+    },
+    /// Render a sample text to a PNG file or as ASCII art to the terminal.
+    Preview {
+        #[command(flatten)]
+        font: FontArgs,
+
+        /// Sample text.
+        text: String,
+
+        /// Save the preview to a PNG file instead of printing it to the terminal.
+        #[arg(long)]
+        png: Option<PathBuf>,
+
+        /// Scale factor for the PNG file.
+        #[arg(long, default_value_t = 1, requires = "png")]
+        scale: u32,
+    },
+    /// Write a BDF font which only contains the selected glyphs.
+    Subset {
+        #[command(flatten)]
+        font: FontArgs,
+
+        #[command(flatten)]
+        glyphs: GlyphArgs,
+
+        /// Output BDF file. The BDF data is written to stdout if no file is specified.
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+}
+
+/// Input font.
+#[derive(clap::Args, Debug)]
+struct FontArgs {
+    /// BDF or PCF file, optionally compressed with gzip.
+    ///
+    /// TrueType and OpenType files are rasterized at the size given by
+    /// `--pixel-size`.
+    file: PathBuf,
+
+    #[command(flatten)]
+    outline: OutlineArgs,
+}
+
+impl FontArgs {
+    fn read(&self) -> Result<Font> {
+        read_font(&self.file, &self.outline)
+    }
+}
+
+/// Options for TrueType and OpenType files.
+#[derive(clap::Args, Debug)]
+struct OutlineArgs {
+    /// Pixel size used to rasterize TrueType and OpenType files.
+    #[arg(long, default_value_t = 16)]
+    pixel_size: u32,
+
+    /// Rasterization mode used for TrueType and OpenType files.
+    #[arg(long, value_enum, default_value_t = OutlineRasterMode::Threshold)]
+    raster_mode: OutlineRasterMode,
+
+    /// Coverage threshold used to rasterize TrueType and OpenType files.
+    ///
+    /// Only used with `--raster-mode threshold`.
+    #[arg(long, default_value_t = 128)]
+    threshold: u8,
+
+    /// Use the embedded bitmap strike with the given pixels per em instead of
+    /// rasterizing the outlines of TrueType and OpenType files.
+    #[arg(long)]
+    strike: Option<u32>,
+}
+
+impl OutlineArgs {
+    fn raster_mode(&self) -> RasterMode {
+        match self.raster_mode {
+            OutlineRasterMode::Threshold => RasterMode::Threshold(self.threshold),
+            OutlineRasterMode::Gray2 => RasterMode::Coverage { bits_per_pixel: 2 },
+            OutlineRasterMode::Gray4 => RasterMode::Coverage { bits_per_pixel: 4 },
+            OutlineRasterMode::Gray8 => RasterMode::Coverage { bits_per_pixel: 8 },
+        }
+    }
+}
+
+/// Glyph selection.
+#[derive(clap::Args, Debug)]
+struct GlyphArgs {
+    /// Limit the selection of glyphs to those included in the provided mapping.
+    #[arg(long, value_parser = parse_mapping)]
+    mapping: Option<Mapping>,
+
+    /// Limit the selection of glyphs to the given inclusive range.
+    #[arg(long, num_args = 2, id = "char", conflicts_with = "mapping")]
+    glyph_range: Vec<char>,
+
+    /// Limit the selection of glyphs to the characters in the given string.
+    #[arg(long, conflicts_with = "mapping")]
+    glyphs: Option<String>,
+}
+
+impl GlyphArgs {
+    /// Returns the selected characters.
+    ///
+    /// An empty list is returned if no glyphs were selected.
+    fn chars(&self) -> Vec<char> {
+        let mut chars = Vec::new();
+
+        if let Some(mapping) = self.mapping {
+            chars.extend(mapping.glyphs());
+        }
+
+        for range in self.glyph_range.chunks(2) {
+            chars.extend((range[0]..=range[1]).glyphs());
+        }
+
+        if let Some(glyphs) = &self.glyphs {
+            chars.extend(glyphs.as_str().glyphs());
+        }
+
+        chars
+    }
+}
+
+#[derive(clap::Args, Debug)]
+struct ConvertArgs {
+    /// BDF or PCF file, optionally compressed with gzip.
+    ///
+    /// TrueType and OpenType files are rasterized at the size given by
+    /// `--pixel-size`.
+    #[arg(required_unless_present_any = ["list_mappings", "manifest"])]
     bdf_file: Option<PathBuf>,
 
     /// Name of the Rust constant.
-    #[arg(required_unless_present = "list_mappings")]
+    #[arg(required_unless_present_any = ["list_mappings", "list_strikes", "manifest"])]
     name: Option<String>,
 
+    /// Convert all fonts in a TOML manifest file and save them to `--output-dir`.
+    ///
+    /// See the documentation of `eg_font_converter::convert_manifest` for a
+    /// description of the manifest format.
+    #[arg(long, requires = "output_dir", conflicts_with_all = ["bdf_file", "name"])]
+    manifest: Option<PathBuf>,
+
+    /// Type of the generated Rust font.
+    #[arg(long, value_enum, default_value_t = FontKind::MonoFont)]
+    kind: FontKind,
+
+    /// Save the Rust file and the data file to the given directory.
+    ///
+    /// The file names are based on the file stem and the data file extension.
+    #[arg(long)]
+    output_dir: Option<PathBuf>,
+
+    /// File stem of the files generated by `--output-dir`.
+    ///
+    /// Defaults to the lowercase constant name.
+    #[arg(long)]
+    file_stem: Option<String>,
+
+    /// File extension of the data file generated by `--output-dir`.
+    #[arg(long, default_value = "data")]
+    data_file_extension: String,
+
+    /// Path which is prepended to the data file name in `include_bytes` statements.
+    ///
+    /// If `--output-dir` is used the data file is saved to this path inside
+    /// the output directory.
+    #[arg(long)]
+    data_file_path: Option<PathBuf>,
+
+    /// Visibility of the generated constant, e.g. `pub(crate)` or `private`.
+    #[arg(long, default_value = "pub", value_parser = parse_visibility)]
+    constant_visibility: Visibility,
+
+    /// Character which is drawn for characters that aren't included in the font.
+    #[arg(long)]
+    replacement_character: Option<char>,
+
     /// Inline PNG image in documentation.
     #[arg(long)]
     inline_png: bool,
@@ -32,13 +235,52 @@ struct Args {
     #[arg(long)]
     png: Option<PathBuf>,
 
-    /// Limit the selection of glyphs to those included in the provided mapping.
-    #[arg(long, value_parser = parse_mapping)]
-    mapping: Option<Mapping>,
+    /// Generate Adafruit GFX C header file.
+    #[arg(long)]
+    adafruit_gfx: Option<PathBuf>,
 
-    /// Limit the selection of glyphs to the given inclusive range.
-    #[arg(long, num_args = 2, id = "char", conflicts_with = "mapping")]
-    glyph_range: Vec<char>,
+    /// Generate u8g2 C source file.
+    #[arg(long)]
+    u8g2: Option<PathBuf>,
+
+    /// Generate LVGL C source file.
+    #[arg(long)]
+    lvgl: Option<PathBuf>,
+
+    /// Generate plain C header file.
+    #[arg(long)]
+    c_header: Option<PathBuf>,
+
+    /// Store the bitmaps in the C header column by column instead of row by row.
+    #[arg(long, requires = "c_header")]
+    c_column_major: bool,
+
+    /// Store the bitmaps in the C header in pages of 8 rows, like page based displays.
+    #[arg(long, requires = "c_header", conflicts_with = "c_column_major")]
+    c_page_major: bool,
+
+    /// Store the first pixel in the least significant bit in the C header.
+    #[arg(long, requires = "c_header")]
+    c_lsb_first: bool,
+
+    /// Don't pad the rows or columns in the C header to a multiple of 8 bits.
+    #[arg(long, requires = "c_header")]
+    c_bit_packed: bool,
+
+    /// Generate BMFont PNG atlas and descriptor in the given directory.
+    #[arg(long)]
+    bmfont: Option<PathBuf>,
+
+    /// Use the JSON format for the BMFont descriptor.
+    #[arg(long, requires = "bmfont")]
+    bmfont_json: bool,
+
+    /// Generate HTML specimen file.
+    #[arg(long)]
+    html_specimen: Option<PathBuf>,
+
+    #[command(flatten)]
+    glyphs: GlyphArgs,
 
     /// Type path to the embedded-graphics crate
     #[arg(long, default_value = "::embedded_graphics")]
@@ -55,6 +297,133 @@ struct Args {
     /// Add a documentation comment to the generated Rust code.
     #[arg(long)]
     comment: Vec<String>,
+
+    /// Merge glyphs from additional BDF or PCF files into the font.
+    #[arg(long)]
+    merge: Vec<PathBuf>,
+
+    /// Resolution for glyphs that are included in more than one merged font.
+    #[arg(long, value_enum, default_value_t = MergeConflicts::KeepExisting, requires = "merge")]
+    merge_conflicts: MergeConflicts,
+
+    /// Adjustment for merged glyphs if the font metrics differ.
+    #[arg(long, value_enum, default_value_t = MergeMetrics::None, requires = "merge")]
+    merge_metrics: MergeMetrics,
+
+    #[command(flatten)]
+    outline: OutlineArgs,
+
+    /// List the embedded bitmap strikes in a TrueType or OpenType file.
+    #[arg(long)]
+    list_strikes: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum FontKind {
+    /// `MonoFont` for embedded-graphics.
+    MonoFont,
+    /// `BdfFont` for the eg-bdf crate.
+    EgBdf,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum MergeConflicts {
+    /// Keep the existing glyph.
+    KeepExisting,
+    /// Replace the existing glyph by the glyph from the merged font.
+    Replace,
+    /// Fail with an error.
+    Error,
+}
+
+impl From<MergeConflicts> for ConflictResolution {
+    fn from(conflicts: MergeConflicts) -> Self {
+        match conflicts {
+            MergeConflicts::KeepExisting => ConflictResolution::KeepExisting,
+            MergeConflicts::Replace => ConflictResolution::Replace,
+            MergeConflicts::Error => ConflictResolution::Error,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum MergeMetrics {
+    /// Copy the glyphs unchanged.
+    None,
+    /// Shift the glyphs vertically.
+    Rebaseline,
+    /// Scale and shift the glyphs vertically.
+    Rescale,
+}
+
+impl From<MergeMetrics> for MetricsAdjustment {
+    fn from(metrics: MergeMetrics) -> Self {
+        match metrics {
+            MergeMetrics::None => MetricsAdjustment::None,
+            MergeMetrics::Rebaseline => MetricsAdjustment::Rebaseline,
+            MergeMetrics::Rescale => MetricsAdjustment::Rescale,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutlineRasterMode {
+    /// Set pixels with a coverage of at least `--threshold` (1 bit per pixel).
+    Threshold,
+    /// Store the coverage as gray values with 2 bits per pixel.
+    Gray2,
+    /// Store the coverage as gray values with 4 bits per pixel.
+    Gray4,
+    /// Store the coverage as gray values with 8 bits per pixel.
+    Gray8,
+}
+
+/// Common interface of the Rust font outputs.
+trait RustOutput {
+    fn rust(&self) -> String;
+    fn data(&self) -> &[u8];
+    fn save(&self, output_directory: &Path) -> io::Result<()>;
+    fn save_png(&self, path: &Path) -> Result<()>;
+}
+
+impl RustOutput for MonoFontOutput {
+    fn rust(&self) -> String {
+        self.rust()
+    }
+
+    fn data(&self) -> &[u8] {
+        self.data()
+    }
+
+    fn save(&self, output_directory: &Path) -> io::Result<()> {
+        self.save(output_directory)
+    }
+
+    fn save_png(&self, path: &Path) -> Result<()> {
+        self.save_png(path)
+    }
+}
+
+impl RustOutput for EgBdfOutput {
+    fn rust(&self) -> String {
+        self.rust()
+    }
+
+    fn data(&self) -> &[u8] {
+        self.data()
+    }
+
+    fn save(&self, output_directory: &Path) -> io::Result<()> {
+        self.save(output_directory)
+    }
+
+    fn save_png(&self, path: &Path) -> Result<()> {
+        self.save_png(path)
+    }
+}
+
+fn parse_visibility(s: &str) -> Result<Visibility> {
+    s.parse()
 }
 
 fn parse_mapping(s: &str) -> Result<Mapping> {
@@ -71,29 +440,203 @@ fn list_mappings() {
     }
 }
 
-fn convert(args: &Args) -> Result<()> {
+fn list_strikes(args: &ConvertArgs) -> Result<()> {
+    let file = args.bdf_file.as_ref().unwrap();
+    let strikes = StrikeExtractor::with_file(file).strikes()?;
+
+    if strikes.is_empty() {
+        println!("No embedded bitmap strikes.");
+        return Ok(());
+    }
+
+    println!("Embedded bitmap strikes:");
+
+    for strike in strikes {
+        println!(
+            "  {} ppem, {} bpp, {} glyphs",
+            strike.pixels_per_em, strike.bits_per_pixel, strike.glyph_count
+        );
+    }
+
+    Ok(())
+}
+
+fn is_outline_font(file: &Path) -> bool {
+    file.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            extension.eq_ignore_ascii_case("ttf") || extension.eq_ignore_ascii_case("otf")
+        })
+}
+
+fn read_font(file: &Path, outline: &OutlineArgs) -> Result<Font> {
+    match (is_outline_font(file), outline.strike) {
+        (true, Some(pixels_per_em)) => StrikeExtractor::with_file(file).extract(pixels_per_em),
+        (true, None) => OutlineRasterizer::with_file(file, outline.pixel_size)
+            .raster_mode(outline.raster_mode())
+            .rasterize(),
+        (false, _) => read_font_file(file),
+    }
+}
+
+fn convert(args: &ConvertArgs) -> Result<()> {
+    ensure!(
+        has_output(args),
+        "no output selected, use --output-dir, --rust or another output option"
+    );
+
     let bdf_file = args.bdf_file.as_ref().unwrap();
     let name = args.name.as_ref().unwrap();
 
-    let mut converter = FontConverter::with_file(bdf_file, name)
-        .embedded_graphics_crate_path(&args.embedded_graphics_crate_path);
-
-    if let Some(mapping) = args.mapping {
-        converter = converter.glyphs(mapping);
-    } else if !args.glyph_range.is_empty() {
-        for range in args.glyph_range.chunks(2) {
-            converter = converter.glyphs(range[0]..=range[1]);
+    let font;
+    let converter = if args.merge.is_empty() {
+        font = read_font(bdf_file, &args.outline)?;
+        FontConverter::with_font(&font, name)
+    } else {
+        if is_outline_font(bdf_file) {
+            bail!("--merge isn't supported for TrueType and OpenType files");
         }
+
+        FontConverter::with_files(std::iter::once(bdf_file).chain(&args.merge), name).merge_policy(
+            MergePolicy {
+                conflicts: args.merge_conflicts.into(),
+                metrics: args.merge_metrics.into(),
+            },
+        )
+    };
+    let mut converter = converter
+        .embedded_graphics_crate_path(&args.embedded_graphics_crate_path)
+        .data_file_extension(&args.data_file_extension)
+        .constant_visibility(args.constant_visibility.clone())
+        .inline_png(args.inline_png);
+
+    if let Some(file_stem) = &args.file_stem {
+        converter = converter.file_stem(file_stem);
+    }
+
+    if let Some(path) = &args.data_file_path {
+        converter = converter.data_file_path(path);
+    }
+
+    if let Some(c) = args.replacement_character {
+        converter = converter.replacement_character(c);
     }
 
+    for comment in &args.comment {
+        converter = converter.comment(comment);
+    }
+
+    converter = converter.glyphs(args.glyphs.chars().as_slice());
+
     if let Some(substitute) = args.missing_glyph_substitute {
         converter = converter.missing_glyph_substitute(substitute);
     }
-    //.inline_png(args.inline_png);
 
-    let font = converter.convert_mono_font()?;
+    if let Some(header) = &args.adafruit_gfx {
+        let font = converter.convert_adafruit_gfx()?;
+        std::fs::write(header, font.header())
+            .with_context(|| format!("Failed to write header file {}", header.to_string_lossy()))?;
+    }
+
+    if let Some(source) = &args.u8g2 {
+        let font = converter.convert_u8g2()?;
+        std::fs::write(source, font.source())
+            .with_context(|| format!("Failed to write source file {}", source.to_string_lossy()))?;
+    }
+
+    if let Some(source) = &args.lvgl {
+        let font = converter.convert_lvgl()?;
+        std::fs::write(source, font.source())
+            .with_context(|| format!("Failed to write source file {}", source.to_string_lossy()))?;
+    }
 
-    //TODO: use FontConverterOutput::save
+    if let Some(header) = &args.c_header {
+        let layout = CHeaderLayout {
+            pixel_order: if args.c_column_major {
+                PixelOrder::ColumnMajor
+            } else if args.c_page_major {
+                PixelOrder::PageMajor
+            } else {
+                PixelOrder::RowMajor
+            },
+            bit_order: if args.c_lsb_first {
+                BitOrder::LsbFirst
+            } else {
+                BitOrder::MsbFirst
+            },
+            line_alignment: if args.c_bit_packed {
+                LineAlignment::Packed
+            } else {
+                LineAlignment::Byte
+            },
+        };
+
+        let font = converter.convert_c_header(layout)?;
+        std::fs::write(header, font.header())
+            .with_context(|| format!("Failed to write header file {}", header.to_string_lossy()))?;
+    }
+
+    if let Some(directory) = &args.bmfont {
+        let font = converter.convert_bmfont()?;
+        if args.bmfont_json {
+            font.save_json(directory)?;
+        } else {
+            font.save(directory)?;
+        }
+    }
+
+    if let Some(html) = &args.html_specimen {
+        let font = converter.convert_eg_bdf()?;
+        Specimen::from_bdf_font(&font.as_font())
+            .title(name)
+            .save_html(html)?;
+    }
+
+    match args.kind {
+        FontKind::MonoFont if has_rust_output(args) => {
+            write_rust_output(args, &converter.convert_mono_font()?)
+        }
+        FontKind::EgBdf if has_rust_output(args) => {
+            write_rust_output(args, &converter.convert_eg_bdf()?)
+        }
+        _ => Ok(()),
+    }
+}
+
+fn has_output(args: &ConvertArgs) -> bool {
+    has_rust_output(args)
+        || args.adafruit_gfx.is_some()
+        || args.u8g2.is_some()
+        || args.lvgl.is_some()
+        || args.c_header.is_some()
+        || args.bmfont.is_some()
+        || args.html_specimen.is_some()
+}
+
+fn has_rust_output(args: &ConvertArgs) -> bool {
+    args.rust.is_some() || args.data.is_some() || args.png.is_some() || args.output_dir.is_some()
+}
+
+fn write_rust_output<T: RustOutput>(args: &ConvertArgs, font: &T) -> Result<()> {
+    if let Some(directory) = &args.output_dir {
+        let data_directory = match &args.data_file_path {
+            Some(path) => directory.join(path),
+            None => directory.clone(),
+        };
+        std::fs::create_dir_all(&data_directory).with_context(|| {
+            format!(
+                "Failed to create output directory {}",
+                data_directory.to_string_lossy()
+            )
+        })?;
+
+        font.save(directory).with_context(|| {
+            format!(
+                "Failed to save font to directory {}",
+                directory.to_string_lossy()
+            )
+        })?;
+    }
 
     if let Some(rust) = &args.rust {
         std::fs::write(rust, font.rust())
@@ -112,16 +655,304 @@ fn convert(args: &Args) -> Result<()> {
     Ok(())
 }
 
-fn main() {
-    let args = Args::parse();
+fn info(font: &Font) -> String {
+    let metadata = &font.metadata;
+    let bounding_box = &metadata.bounding_box;
 
-    if args.list_mappings {
-        list_mappings();
-        return;
+    let mut output = String::new();
+    writeln!(output, "Name:           {}", metadata.name).unwrap();
+    writeln!(output, "Point size:     {}", metadata.point_size).unwrap();
+    writeln!(
+        output,
+        "Resolution:     {}x{} DPI",
+        metadata.resolution.x, metadata.resolution.y
+    )
+    .unwrap();
+    writeln!(output, "Bits per pixel: {}", metadata.bits_per_pixel).unwrap();
+    writeln!(
+        output,
+        "Bounding box:   {}x{} at ({}, {})",
+        bounding_box.size.x, bounding_box.size.y, bounding_box.offset.x, bounding_box.offset.y
+    )
+    .unwrap();
+    writeln!(output, "Ascent:         {}", font.metrics.ascent).unwrap();
+    writeln!(output, "Descent:        {}", font.metrics.descent).unwrap();
+    writeln!(output, "Line height:    {}", font.metrics.line_height()).unwrap();
+    writeln!(output, "Glyphs:         {}", font.glyphs.len()).unwrap();
+
+    if !metadata.properties.is_empty() {
+        writeln!(output, "Properties:").unwrap();
+
+        let mut properties = metadata.properties.iter().collect::<Vec<_>>();
+        properties.sort_by_key(|(name, _)| *name);
+
+        for (name, value) in properties {
+            writeln!(output, "  {name} {value}").unwrap();
+        }
+    }
+
+    output
+}
+
+fn glyph_list(font: &Font) -> String {
+    let mut output = String::new();
+
+    for glyph in font.glyphs.iter() {
+        let Glyph {
+            name,
+            encoding,
+            width_horizontal,
+            ..
+        } = glyph;
+
+        let code_point = match *encoding {
+            Encoding::Standard(index) => format!("U+{index:04X}"),
+            Encoding::NonStandard(index) => format!("-{index}"),
+            Encoding::Unspecified => "-".to_string(),
+        };
+        let character = match *encoding {
+            Encoding::Standard(index) => char::from_u32(index)
+                .filter(|c| !c.is_control())
+                .map_or_else(String::new, String::from),
+            _ => String::new(),
+        };
+        let width =
+            width_horizontal.map_or_else(|| "-".to_string(), |width| width.device.x.to_string());
+
+        writeln!(output, "{code_point:<10} {character:<2} {width:>4}  {name}").unwrap();
+    }
+
+    output
+}
+
+fn render_preview(font: &Font, text: &str) -> Result<SimulatorDisplay<BinaryColor>> {
+    let converted = FontConverter::with_font(font, "PREVIEW").convert_eg_bdf()?;
+    let font = converted.as_font();
+    let style = BdfTextStyle::new(&font, BinaryColor::On);
+
+    let text = Text::with_baseline(text, Point::zero(), style, Baseline::Top);
+    let mut display = SimulatorDisplay::new(
+        text.bounding_box()
+            .bottom_right()
+            .map_or(Size::zero(), |bottom_right| {
+                Size::new(bottom_right.x as u32 + 1, bottom_right.y as u32 + 1)
+            }),
+    );
+    text.draw(&mut display).unwrap();
+
+    Ok(display)
+}
+
+fn ascii_art(display: &SimulatorDisplay<BinaryColor>) -> String {
+    let mut output = String::new();
+
+    for y in 0..display.size().height as i32 {
+        for x in 0..display.size().width as i32 {
+            output.push(if display.get_pixel(Point::new(x, y)).is_on() {
+                '#'
+            } else {
+                '.'
+            });
+        }
+        output.push('\n');
+    }
+
+    output
+}
+
+fn run(args: &Args) -> Result<()> {
+    match &args.command {
+        Command::Convert(args) => {
+            if args.list_mappings {
+                list_mappings();
+            } else if let Some(manifest) = &args.manifest {
+                convert_manifest(manifest, args.output_dir.as_ref().unwrap())?;
+            } else if args.list_strikes {
+                list_strikes(args)?;
+            } else {
+                convert(args)?;
+            }
+        }
+        Command::Info { font } => {
+            print!("{}", info(&font.read()?));
+        }
+        Command::ListGlyphs { font } => {
+            print!("{}", glyph_list(&font.read()?));
+        }
+        Command::Preview {
+            font,
+            text,
+            png,
+            scale,
+        } => {
+            let display = render_preview(&font.read()?, text)?;
+
+            if let Some(png) = png {
+                display
+                    .to_grayscale_output_image(&OutputSettingsBuilder::new().scale(*scale).build())
+                    .save_png(png)
+                    .with_context(|| {
+                        format!("Failed to write PNG file {}", png.to_string_lossy())
+                    })?;
+            } else {
+                print!("{}", ascii_art(&display));
+            }
+        }
+        Command::Subset {
+            font,
+            glyphs,
+            output,
+        } => {
+            let chars = glyphs.chars();
+            if chars.is_empty() {
+                bail!("no glyphs selected, use --mapping, --glyph-range or --glyphs");
+            }
+
+            let bdf = font.read()?.subset(chars).to_bdf();
+
+            if let Some(output) = output {
+                std::fs::write(output, bdf).with_context(|| {
+                    format!("Failed to write BDF file {}", output.to_string_lossy())
+                })?;
+            } else {
+                print!("{bdf}");
+            }
+        }
     }
 
-    if let Err(e) = convert(&args) {
+    Ok(())
+}
+
+fn main() {
+    let args = Args::parse();
+
+    if let Err(e) = run(&args) {
         eprintln!("Error: {e:#}");
         std::process::exit(1);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FONT: &str = r#"
+        STARTFONT 2.1
+        FONT test
+        SIZE 8 96 96
+        FONTBOUNDINGBOX 4 4 0 0
+        STARTPROPERTIES 2
+        FONT_ASCENT 4
+        FONT_DESCENT 0
+        ENDPROPERTIES
+        CHARS 2
+        STARTCHAR A
+        ENCODING 65
+        DWIDTH 5 0
+        BBX 3 4 0 0
+        BITMAP
+        40
+        A0
+        E0
+        A0
+        ENDCHAR
+        STARTCHAR uni0001
+        ENCODING 1
+        DWIDTH 4 0
+        BBX 1 1 0 0
+        BITMAP
+        80
+        ENDCHAR
+        ENDFONT
+    "#;
+
+    #[test]
+    fn font_info() {
+        let font = Font::parse(FONT).unwrap();
+
+        assert_eq!(
+            info(&font),
+            concat!(
+                "Name:           test\n",
+                "Point size:     8\n",
+                "Resolution:     96x96 DPI\n",
+                "Bits per pixel: 1\n",
+                "Bounding box:   4x4 at (0, 0)\n",
+                "Ascent:         4\n",
+                "Descent:        0\n",
+                "Line height:    4\n",
+                "Glyphs:         2\n",
+                "Properties:\n",
+                "  FONT_ASCENT 4\n",
+                "  FONT_DESCENT 0\n",
+            )
+        );
+    }
+
+    #[test]
+    fn list_glyphs() {
+        let font = Font::parse(FONT).unwrap();
+
+        assert_eq!(
+            glyph_list(&font),
+            concat!(
+                "U+0041     A     5  A\n", //
+                "U+0001           4  uni0001\n",
+            )
+        );
+    }
+
+    #[test]
+    fn preview() {
+        let font = Font::parse(FONT).unwrap();
+        let display = render_preview(&font, "AA").unwrap();
+
+        assert_eq!(
+            ascii_art(&display),
+            concat!(
+                ".#....#...\n", //
+                "#.#..#.#..\n",
+                "###..###..\n",
+                "#.#..#.#..\n",
+            )
+        );
+    }
+
+    #[test]
+    fn convert_without_output() {
+        let args =
+            Args::try_parse_from(["eg-font-converter", "convert", "font.bdf", "FONT"]).unwrap();
+        let Command::Convert(args) = args.command else {
+            unreachable!()
+        };
+
+        assert_eq!(
+            convert(&args).unwrap_err().to_string(),
+            "no output selected, use --output-dir, --rust or another output option"
+        );
+    }
+
+    #[test]
+    fn raster_mode() {
+        let raster_mode = |args: &[&str]| {
+            let args =
+                Args::try_parse_from(["eg-font-converter", "info", "font.ttf"].iter().chain(args))
+                    .unwrap();
+            let Command::Info { font } = args.command else {
+                unreachable!()
+            };
+
+            font.outline.raster_mode()
+        };
+
+        assert_eq!(raster_mode(&[]), RasterMode::Threshold(128));
+        assert_eq!(
+            raster_mode(&["--threshold", "64"]),
+            RasterMode::Threshold(64)
+        );
+        assert_eq!(
+            raster_mode(&["--raster-mode", "gray4"]),
+            RasterMode::Coverage { bits_per_pixel: 4 }
+        );
+    }
+}
@@ -0,0 +1,368 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Context, Result};
+use bdf_parser::{Font, MergePolicy};
+use serde::Deserialize;
+
+use crate::{read_font_file, CHeaderLayout, FontConverter, Mapping, Visibility};
+
+/// Converts all fonts described by a manifest file.
+///
+/// A manifest is a TOML file which describes the fonts that should be
+/// converted. Each `[[font]]` table specifies a source font and the glyphs
+/// which should be included. The `[[font.output]]` tables inside a font
+/// specify the generated constants and their output kind. This makes it
+/// possible to generate multiple variants of the same font without repeating
+/// the glyph selection:
+///
+/// ```toml
+/// [[font]]
+/// file = "fonts/6x10.bdf"
+/// mapping = "ISO_8859_15"
+/// missing_glyph_substitute = " "
+///
+/// [[font.output]]
+/// name = "FONT_6X10"
+/// kind = "eg-bdf"
+///
+/// [[font.output]]
+/// name = "FONT_6X10_MONO"
+/// kind = "mono-font"
+/// ```
+///
+/// The following keys are supported in `[[font]]` tables:
+///
+/// | Key                            | Description                                                      |
+/// |--------------------------------|------------------------------------------------------------------|
+/// | `file`                         | BDF or PCF file, optionally compressed with gzip (required)      |
+/// | `merge`                        | Array of additional files which are merged into the font         |
+/// | `mapping`                      | Include all glyphs in the given [`Mapping`], e.g. `"ASCII"`      |
+/// | `glyphs`                       | Include all characters in the given string                       |
+/// | `glyph_ranges`                 | Array of inclusive ranges, e.g. `[["A", "Z"], ["0", "9"]]`       |
+/// | `missing_glyph_substitute`     | See [`FontConverter::missing_glyph_substitute`]                  |
+/// | `replacement_character`        | See [`FontConverter::replacement_character`]                     |
+/// | `comments`                     | Array of documentation comments, see [`FontConverter::comment`]  |
+/// | `inline_png`                   | See [`FontConverter::inline_png`]                                |
+/// | `embedded_graphics_crate_path` | See [`FontConverter::embedded_graphics_crate_path`]              |
+/// | `data_file_extension`          | See [`FontConverter::data_file_extension`]                       |
+/// | `data_file_path`               | See [`FontConverter::data_file_path`]                            |
+///
+/// If no glyphs are selected all glyphs in the source font are included.
+///
+/// The following keys are supported in `[[font.output]]` tables:
+///
+/// | Key                   | Description                                                        |
+/// |-----------------------|--------------------------------------------------------------------|
+/// | `name`                | Name of the generated constant (required)                          |
+/// | `kind`                | Output kind (required), see below                                  |
+/// | `file_stem`           | See [`FontConverter::file_stem`]                                   |
+/// | `constant_visibility` | Visibility of the constant, e.g. `"pub(crate)"` or `"private"`     |
+///
+/// The supported output kinds are `eg-bdf`, `mono-font`, `adafruit-gfx`,
+/// `u8g2`, `lvgl`, `c-header`, `bmfont` and `bmfont-json`. All outputs are
+/// saved to `output_directory` by using the `save` method of the output.
+///
+/// Relative paths in the manifest are resolved relative to the directory
+/// which contains the manifest file.
+pub fn convert_manifest<P: AsRef<Path>, Q: AsRef<Path>>(
+    manifest_file: P,
+    output_directory: Q,
+) -> Result<()> {
+    let manifest_file = manifest_file.as_ref();
+
+    let manifest = fs::read_to_string(manifest_file)
+        .with_context(|| format!("couldn't read manifest file {manifest_file:?}"))?;
+    let manifest: Manifest = toml::from_str(&manifest)
+        .with_context(|| format!("couldn't parse manifest file {manifest_file:?}"))?;
+
+    let base_directory = manifest_file.parent().unwrap_or(Path::new(""));
+    manifest.convert(base_directory, output_directory.as_ref())
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Manifest {
+    #[serde(default, rename = "font")]
+    fonts: Vec<ManifestFont>,
+}
+
+impl Manifest {
+    fn convert(&self, base_directory: &Path, output_directory: &Path) -> Result<()> {
+        for font in &self.fonts {
+            font.convert(base_directory, output_directory)
+                .with_context(|| format!("couldn't convert {:?}", font.file))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ManifestFont {
+    file: PathBuf,
+    #[serde(default)]
+    merge: Vec<PathBuf>,
+
+    mapping: Option<String>,
+    glyphs: Option<String>,
+    #[serde(default)]
+    glyph_ranges: Vec<(char, char)>,
+    missing_glyph_substitute: Option<char>,
+    replacement_character: Option<char>,
+
+    #[serde(default)]
+    comments: Vec<String>,
+    #[serde(default)]
+    inline_png: bool,
+    embedded_graphics_crate_path: Option<String>,
+    data_file_extension: Option<String>,
+    data_file_path: Option<PathBuf>,
+
+    #[serde(default, rename = "output")]
+    outputs: Vec<ManifestOutput>,
+}
+
+impl ManifestFont {
+    fn convert(&self, base_directory: &Path, output_directory: &Path) -> Result<()> {
+        let font = self.merge.iter().try_fold(
+            read_font_file(base_directory.join(&self.file))?,
+            |font, file| {
+                font.merge(
+                    &read_font_file(base_directory.join(file))?,
+                    MergePolicy::default(),
+                )
+                .with_context(|| format!("couldn't merge BDF file {file:?}"))
+            },
+        )?;
+
+        let data_directory = match &self.data_file_path {
+            Some(path) => output_directory.join(path),
+            None => output_directory.to_owned(),
+        };
+        fs::create_dir_all(&data_directory)
+            .with_context(|| format!("couldn't create output directory {data_directory:?}"))?;
+
+        for output in &self.outputs {
+            output
+                .save(self.converter(&font, output)?, output_directory)
+                .with_context(|| format!("couldn't convert {}", output.name))?;
+        }
+
+        Ok(())
+    }
+
+    fn converter<'a>(&self, font: &'a Font, output: &ManifestOutput) -> Result<FontConverter<'a>> {
+        let mut converter =
+            FontConverter::with_font(font, &output.name).inline_png(self.inline_png);
+
+        for comment in &self.comments {
+            converter = converter.comment(comment);
+        }
+
+        if let Some(mapping) = &self.mapping {
+            let mapping = Mapping::iter()
+                .find(|m| m.mime() == mapping)
+                .ok_or_else(|| anyhow!("unknown mapping: {mapping}"))?;
+
+            converter = converter.glyphs(mapping);
+        }
+
+        if let Some(glyphs) = &self.glyphs {
+            converter = converter.glyphs(glyphs.as_str());
+        }
+
+        for (start, end) in &self.glyph_ranges {
+            converter = converter.glyphs(*start..=*end);
+        }
+
+        if let Some(substitute) = self.missing_glyph_substitute {
+            converter = converter.missing_glyph_substitute(substitute);
+        }
+
+        if let Some(c) = self.replacement_character {
+            converter = converter.replacement_character(c);
+        }
+
+        if let Some(path) = &self.embedded_graphics_crate_path {
+            converter = converter.embedded_graphics_crate_path(path);
+        }
+
+        if let Some(extension) = &self.data_file_extension {
+            converter = converter.data_file_extension(extension);
+        }
+
+        if let Some(path) = &self.data_file_path {
+            converter = converter.data_file_path(path);
+        }
+
+        if let Some(file_stem) = &output.file_stem {
+            converter = converter.file_stem(file_stem);
+        }
+
+        if let Some(visibility) = &output.constant_visibility {
+            converter = converter.constant_visibility(visibility.parse::<Visibility>()?);
+        }
+
+        Ok(converter)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ManifestOutput {
+    name: String,
+    kind: OutputKind,
+    file_stem: Option<String>,
+    constant_visibility: Option<String>,
+}
+
+impl ManifestOutput {
+    fn save(&self, converter: FontConverter<'_>, output_directory: &Path) -> Result<()> {
+        match self.kind {
+            OutputKind::EgBdf => converter.convert_eg_bdf()?.save(output_directory)?,
+            OutputKind::MonoFont => converter.convert_mono_font()?.save(output_directory)?,
+            OutputKind::AdafruitGfx => converter.convert_adafruit_gfx()?.save(output_directory)?,
+            OutputKind::U8g2 => converter.convert_u8g2()?.save(output_directory)?,
+            OutputKind::Lvgl => converter.convert_lvgl()?.save(output_directory)?,
+            OutputKind::CHeader => converter
+                .convert_c_header(CHeaderLayout::default())?
+                .save(output_directory)?,
+            OutputKind::Bmfont => converter.convert_bmfont()?.save(output_directory)?,
+            OutputKind::BmfontJson => converter.convert_bmfont()?.save_json(output_directory)?,
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum OutputKind {
+    EgBdf,
+    MonoFont,
+    AdafruitGfx,
+    U8g2,
+    Lvgl,
+    CHeader,
+    Bmfont,
+    BmfontJson,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = r#"
+        [[font]]
+        file = "6x10.bdf"
+        mapping = "ASCII"
+        missing_glyph_substitute = "?"
+        comments = ["6x10 pixel font"]
+
+        [[font.output]]
+        name = "FONT_6X10"
+        kind = "eg-bdf"
+
+        [[font.output]]
+        name = "FONT_6X10_MONO"
+        kind = "mono-font"
+        constant_visibility = "pub(crate)"
+
+        [[font]]
+        file = "10x20.bdf"
+        glyphs = "0123456789"
+        glyph_ranges = [["A", "F"]]
+        data_file_path = "data"
+        data_file_extension = "bin"
+
+        [[font.output]]
+        name = "FONT_10X20_HEX"
+        file_stem = "hex"
+        kind = "eg-bdf"
+    "#;
+
+    /// Returns an empty output directory.
+    ///
+    /// The process ID is included in the directory name to prevent
+    /// concurrent test runs from using the same directory.
+    fn output_directory(name: &str) -> PathBuf {
+        let directory = std::env::temp_dir().join(format!("{name}-{}", std::process::id()));
+        if directory.exists() {
+            fs::remove_dir_all(&directory).unwrap();
+        }
+
+        directory
+    }
+
+    #[test]
+    fn convert() {
+        let manifest: Manifest = toml::from_str(MANIFEST).unwrap();
+        let output_directory = output_directory("eg-font-converter-manifest");
+
+        manifest
+            .convert("../eg-bdf-examples/examples".as_ref(), &output_directory)
+            .unwrap();
+
+        let font_6x10 = fs::read_to_string(output_directory.join("font_6x10.rs")).unwrap();
+        assert!(font_6x10.starts_with("/// 6x10 pixel font\npub const FONT_6X10:"));
+        assert!(output_directory.join("font_6x10.data").exists());
+
+        let font_6x10_mono =
+            fs::read_to_string(output_directory.join("font_6x10_mono.rs")).unwrap();
+        assert!(font_6x10_mono.contains("pub(crate) const FONT_6X10_MONO:"));
+        assert!(output_directory.join("font_6x10_mono.data").exists());
+
+        let hex = fs::read_to_string(output_directory.join("hex.rs")).unwrap();
+        assert!(hex.contains(r#"include_bytes!("data/hex.bin")"#));
+        assert!(hex.contains("character: 'F'"));
+        assert!(!hex.contains("character: 'G'"));
+        assert!(output_directory.join("data/hex.bin").exists());
+
+        fs::remove_dir_all(output_directory).unwrap();
+    }
+
+    #[test]
+    fn unknown_kind() {
+        let error = toml::from_str::<Manifest>(
+            r#"
+            [[font]]
+            file = "6x10.bdf"
+
+            [[font.output]]
+            name = "FONT"
+            kind = "unknown"
+            "#,
+        )
+        .unwrap_err();
+
+        assert!(error.to_string().contains("unknown variant `unknown`"));
+    }
+
+    #[test]
+    fn unknown_mapping() {
+        let manifest: Manifest = toml::from_str(
+            r#"
+            [[font]]
+            file = "6x10.bdf"
+            mapping = "UNKNOWN"
+
+            [[font.output]]
+            name = "FONT"
+            kind = "eg-bdf"
+            "#,
+        )
+        .unwrap();
+        let output_directory = output_directory("eg-font-converter-manifest-mapping");
+
+        let error = manifest
+            .convert("../eg-bdf-examples/examples".as_ref(), &output_directory)
+            .unwrap_err();
+        assert!(format!("{error:#}").contains("unknown mapping: UNKNOWN"));
+
+        fs::remove_dir_all(output_directory).unwrap();
+    }
+}
@@ -2,13 +2,11 @@ use std::{fs, io, ops::RangeInclusive, path::Path};
 
 use anyhow::{bail, Context, Result};
 use bdf_parser::{Encoding, Font, Glyph};
-use eg_bdf::BdfTextStyle;
 use embedded_graphics::{
     image::ImageRaw,
     mono_font::{mapping::Mapping, DecorationDimensions, MonoFont},
     pixelcolor::BinaryColor,
     prelude::*,
-    text::{Baseline, Text},
 };
 use embedded_graphics_simulator::{OutputSettings, SimulatorDisplay};
 use quote::{format_ident, quote};
@@ -34,13 +32,7 @@ pub struct MonoFontOutput {
 
 impl MonoFontOutput {
     pub(crate) fn new(bdf: EgBdfOutput) -> Result<Self> {
-        let font = bdf.as_font();
         let metrics = &bdf.font.bdf.metrics;
-        let style = BdfTextStyle::new(&font, BinaryColor::On);
-
-        let glyphs_per_row = 16; //TODO: make configurable
-        let columns = glyphs_per_row; // TODO: allow smaller column count
-        let rows = font.glyphs.len().div_ceil(glyphs_per_row);
 
         let character_size = bdf.bounding_box().size;
         let character_spacing = 0;
@@ -52,10 +44,6 @@ impl MonoFontOutput {
         let underline =
             DecorationDimensions::new(bdf.font.underline_position, bdf.font.underline_thickness);
 
-        let mut bitmap = SimulatorDisplay::new(
-            character_size.component_mul(Size::new(columns as u32, rows as u32)),
-        );
-
         let mapping = glyphs_to_mapping(&bdf.font.glyphs);
 
         // Rearrange the glyphs in the correct order if a mapping is used,
@@ -80,20 +68,18 @@ impl MonoFontOutput {
                 .collect()
         };
 
-        for (i, glyph) in glyphs.iter().enumerate() {
-            let x = (i % glyphs_per_row) as i32 * character_size.width as i32;
-            let y = (i / glyphs_per_row) as i32 * character_size.height as i32;
-
-            // TODO: assumes unicode
-            let c = match glyph.encoding {
-                Encoding::Standard(index) => char::from_u32(index).unwrap(),
-                _ => bail!("invalid encoding: '{:?}'", glyph.encoding),
-            };
+        let characters = glyphs
+            .iter()
+            .map(|glyph| {
+                // TODO: assumes unicode
+                match glyph.encoding {
+                    Encoding::Standard(index) => Ok(char::from_u32(index).unwrap()),
+                    _ => bail!("invalid encoding: '{:?}'", glyph.encoding),
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
 
-            Text::with_baseline(&String::from(c), Point::new(x, y), style, Baseline::Top)
-                .draw(&mut bitmap)
-                .unwrap();
-        }
+        let bitmap = bdf.draw_glyphs(characters.into_iter());
 
         let data = bitmap.to_be_bytes();
 
@@ -118,7 +104,8 @@ impl MonoFontOutput {
 
     fn try_rust(&self) -> Result<String> {
         let const_name = format_ident!("{}", self.font.name);
-        let image_data = self.font.data_file().to_string_lossy().to_string();
+        let visibility = self.font.constant_visibility.to_tokens();
+        let image_data = self.font.data_tokens(self.data());
         let image_width = self.bitmap.size().width;
         let MonoFont {
             character_size:
@@ -156,17 +143,25 @@ impl MonoFontOutput {
             quote!(::embedded_graphics::mono_font::mapping::StrGlyphMapping::new(#str_mapping, #replacement))
         };
 
-        let comments = self.font.comments.iter().map(|comment| {
-            let comment = format!(" {comment}");
-            quote!(
-                #[doc = #comment]
-            )
-        });
+        let comments = self
+            .font
+            .doc_lines(|| {
+                self.bitmap
+                    .to_grayscale_output_image(&OutputSettings::default())
+                    .to_base64_png()
+                    .context("couldn't encode PNG image")
+            })?
+            .into_iter()
+            .map(|comment| {
+                quote!(
+                    #[doc = #comment]
+                )
+            });
 
         Ok(prettyplease::unparse(&syn::parse2(quote!(
             #( #comments )*
-            pub const #const_name: ::embedded_graphics::mono_font::MonoFont = ::embedded_graphics::mono_font::MonoFont {
-                image: ::embedded_graphics::image::ImageRaw::new(include_bytes!(#image_data), #image_width),
+            #visibility const #const_name: ::embedded_graphics::mono_font::MonoFont = ::embedded_graphics::mono_font::MonoFont {
+                image: ::embedded_graphics::image::ImageRaw::new(#image_data, #image_width),
                 glyph_mapping: &#glyph_mapping,
                 character_size: ::embedded_graphics::geometry::Size::new(#character_width, #character_height),
                 character_spacing: #character_spacing,
@@ -0,0 +1,637 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write,
+    fs,
+    path::Path,
+};
+
+use anyhow::{Context, Result};
+use bdf_parser::{Encoding, Font};
+use eg_bdf::BdfFont;
+use embedded_graphics::{
+    iterator::raw::RawDataSlice,
+    pixelcolor::raw::{LittleEndian, RawU1},
+    prelude::*,
+    primitives::Rectangle,
+};
+
+/// Default sample text.
+const SAMPLE_TEXT: &str = "The quick brown fox jumps over the lazy dog.\n0123456789";
+
+/// Glyph color.
+const GLYPH_COLOR: &str = "#000000";
+/// Color of the ascent and descent lines.
+const METRICS_COLOR: &str = "#4682b4";
+/// Color of the baseline.
+const BASELINE_COLOR: &str = "#dc143c";
+/// Color of the glyph bounding boxes.
+const BOUNDING_BOX_COLOR: &str = "#9acd32";
+
+/// Glyph in a specimen.
+///
+/// All coordinates are relative to the glyph origin on the baseline and the
+/// Y axis points down.
+#[derive(Debug)]
+struct SpecimenGlyph {
+    c: char,
+    bounding_box: Rectangle,
+    device_width: i32,
+    pixels: Vec<Point>,
+}
+
+/// Font specimen generator.
+///
+/// Specimens are vector graphics, which scale without blurring the pixels.
+/// The pixels are rendered as SVG rectangles, one for each horizontal run of
+/// set pixels. [`html`](Self::html) generates a complete page with the font
+/// metrics, sample text and a grid with all glyphs.
+///
+/// # Examples
+///
+/// ```no_run
+/// use bdf_parser::Font;
+/// use eg_font_converter::Specimen;
+///
+/// let bdf = std::fs::read_to_string("examples/6x10.bdf").unwrap();
+/// let font = Font::parse(&bdf).unwrap();
+///
+/// Specimen::from_bdf(&font)
+///     .sample_text("Hello, world!")
+///     .save_html("6x10.html")
+///     .unwrap();
+/// ```
+#[derive(Debug)]
+pub struct Specimen {
+    title: String,
+    ascent: i32,
+    descent: i32,
+    glyphs: Vec<SpecimenGlyph>,
+    scale: u32,
+    sample_text: String,
+}
+
+impl Specimen {
+    /// Creates a specimen for a BDF font.
+    ///
+    /// Glyphs without a Unicode encoding are ignored. The specimen is
+    /// monochrome and all pixels with a non zero value are drawn for grayscale
+    /// fonts.
+    pub fn from_bdf(font: &Font) -> Self {
+        let bits_per_pixel = font.metadata.bits_per_pixel;
+        let glyphs = font
+            .glyphs
+            .iter()
+            .filter_map(|glyph| {
+                let c = match glyph.encoding {
+                    Encoding::Standard(code_point) => char::from_u32(code_point)?,
+                    _ => return None,
+                };
+
+                let size = glyph.bounding_box.size;
+                let offset = glyph.bounding_box.offset;
+                let top_left = Point::new(offset.x, -(offset.y + size.y));
+                let size = Size::new(size.x.max(0) as u32, size.y.max(0) as u32);
+
+                let pixels = (0..size.height as usize)
+                    .flat_map(|y| (0..size.width as usize).map(move |x| (x, y)))
+                    .filter(|(x, y)| {
+                        glyph
+                            .pixel_value(*x, *y, bits_per_pixel)
+                            .is_some_and(|value| value != 0)
+                    })
+                    .map(|(x, y)| top_left + Point::new(x as i32, y as i32))
+                    .collect();
+
+                Some(SpecimenGlyph {
+                    c,
+                    bounding_box: Rectangle::new(top_left, size),
+                    device_width: glyph
+                        .width_horizontal
+                        .map(|width| width.device.x)
+                        .unwrap_or_default(),
+                    pixels,
+                })
+            })
+            .collect();
+
+        Self::new(
+            &font.metadata.name,
+            font.metrics.ascent,
+            font.metrics.descent,
+            glyphs,
+        )
+    }
+
+    /// Creates a specimen for an [`eg-bdf`] font.
+    ///
+    /// [`eg-bdf`]: eg_bdf
+    pub fn from_bdf_font(font: &BdfFont<'_>) -> Self {
+        let glyphs = font
+            .glyphs
+            .iter()
+            .map(|glyph| {
+                // eg-bdf bounding boxes are relative to the row above the baseline.
+                let bounding_box = glyph.bounding_box.translate(Point::new(0, -1));
+
+                let pixels = RawDataSlice::<RawU1, LittleEndian>::new(font.data)
+                    .into_iter()
+                    .skip(glyph.start_index)
+                    .zip(bounding_box.points())
+                    .filter(|(bit, _)| *bit == RawU1::new(1))
+                    .map(|(_, point)| point)
+                    .collect();
+
+                SpecimenGlyph {
+                    c: glyph.character,
+                    bounding_box,
+                    device_width: glyph.device_width as i32,
+                    pixels,
+                }
+            })
+            .collect();
+
+        Self::new("BdfFont", font.ascent, font.descent, glyphs)
+    }
+
+    fn new(title: &str, ascent: u32, descent: u32, mut glyphs: Vec<SpecimenGlyph>) -> Self {
+        glyphs.sort_by_key(|glyph| glyph.c);
+        glyphs.dedup_by_key(|glyph| glyph.c);
+
+        Self {
+            title: title.to_string(),
+            ascent: ascent as i32,
+            descent: descent as i32,
+            glyphs,
+            scale: 4,
+            sample_text: SAMPLE_TEXT.to_string(),
+        }
+    }
+
+    /// Sets the title of the HTML page.
+    ///
+    /// The default title is the font name for BDF fonts and `BdfFont` for
+    /// [`eg-bdf`] fonts.
+    ///
+    /// [`eg-bdf`]: eg_bdf
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = title.to_string();
+        self
+    }
+
+    /// Sets the size of a font pixel in CSS pixels.
+    ///
+    /// The default scale is 4.
+    pub fn scale(mut self, scale: u32) -> Self {
+        self.scale = scale.max(1);
+        self
+    }
+
+    /// Sets the sample text.
+    ///
+    /// The text can contain multiple lines, which are separated by `\n`.
+    pub fn sample_text(mut self, text: &str) -> Self {
+        self.sample_text = text.to_string();
+        self
+    }
+
+    fn glyph(&self, c: char) -> Option<&SpecimenGlyph> {
+        self.glyphs
+            .binary_search_by_key(&c, |glyph| glyph.c)
+            .ok()
+            .map(|index| &self.glyphs[index])
+    }
+
+    /// Returns an SVG image of a single glyph.
+    ///
+    /// The image contains the glyph bitmap, the glyph bounding box, the
+    /// ascent, baseline and descent lines and the glyph origin and advance.
+    /// Returns `None` if the font doesn't contain the glyph.
+    pub fn glyph_svg(&self, c: char) -> Option<String> {
+        let glyph = self.glyph(c)?;
+
+        let left = glyph.bounding_box.top_left.x.min(0);
+        let right = glyph
+            .bounding_box
+            .bottom_right()
+            .map_or(0, |point| point.x + 1)
+            .max(glyph.device_width)
+            .max(left + 1);
+        let area = Rectangle::with_corners(
+            Point::new(left, self.top(glyph)),
+            Point::new(right - 1, self.bottom(glyph) - 1),
+        );
+
+        let mut svg = Svg::new(area, self.scale);
+        svg.vertical_line(0, METRICS_COLOR);
+        svg.vertical_line(glyph.device_width, METRICS_COLOR);
+        self.metrics_lines(&mut svg, 0);
+        svg.bounding_box(&glyph.bounding_box);
+        svg.pixels(glyph.pixels.iter().copied());
+
+        Some(svg.finish())
+    }
+
+    /// Returns an SVG image of the sample text.
+    ///
+    /// The image contains the ascent, baseline and descent lines for each
+    /// line of text and the bounding boxes of all glyphs. Characters which
+    /// aren't included in the font are skipped.
+    pub fn text_svg(&self) -> String {
+        let line_height = self.ascent + self.descent;
+
+        let mut bounding_boxes = Vec::new();
+        let mut pixels = Vec::new();
+        let mut width = 1;
+
+        for (line, text) in self.sample_text.lines().enumerate() {
+            let mut position = Point::new(0, line as i32 * line_height + self.ascent);
+
+            for glyph in text.chars().filter_map(|c| self.glyph(c)) {
+                bounding_boxes.push(glyph.bounding_box.translate(position));
+                pixels.extend(glyph.pixels.iter().map(|point| *point + position));
+                position.x += glyph.device_width;
+            }
+
+            width = width.max(position.x);
+        }
+
+        let lines = self.sample_text.lines().count().max(1) as i32;
+        let area = Rectangle::new(
+            Point::zero(),
+            Size::new(width as u32, (lines * line_height).max(1) as u32),
+        );
+
+        let mut svg = Svg::new(area, self.scale);
+        for line in 0..lines {
+            self.metrics_lines(&mut svg, line * line_height + self.ascent);
+        }
+        for bounding_box in &bounding_boxes {
+            svg.bounding_box(bounding_box);
+        }
+        svg.pixels(pixels.into_iter());
+
+        svg.finish()
+    }
+
+    /// Returns an HTML page with the font specimen.
+    pub fn html(&self) -> String {
+        let mut html = String::new();
+
+        writeln!(html, "<!DOCTYPE html>").unwrap();
+        writeln!(html, "<html>").unwrap();
+        writeln!(html, "<head>").unwrap();
+        writeln!(html, "<meta charset=\"utf-8\">").unwrap();
+        writeln!(html, "<title>{}</title>", escape(&self.title)).unwrap();
+        writeln!(html, "<style>").unwrap();
+        writeln!(html, "body {{ font-family: sans-serif; margin: 2em; }}").unwrap();
+        writeln!(html, "svg {{ display: block; }}").unwrap();
+        writeln!(
+            html,
+            ".legend span {{ display: inline-block; margin-right: 1.5em; }}"
+        )
+        .unwrap();
+        writeln!(
+            html,
+            ".grid {{ display: flex; flex-wrap: wrap; gap: 8px; }}"
+        )
+        .unwrap();
+        writeln!(
+            html,
+            ".glyph {{ border: 1px solid #ddd; padding: 4px; text-align: center; font-size: 12px; }}"
+        )
+        .unwrap();
+        writeln!(html, ".glyph svg {{ margin: 0 auto 4px; }}").unwrap();
+        writeln!(html, "</style>").unwrap();
+        writeln!(html, "</head>").unwrap();
+        writeln!(html, "<body>").unwrap();
+
+        writeln!(html, "<h1>{}</h1>", escape(&self.title)).unwrap();
+        writeln!(html, "<table>").unwrap();
+        for (name, value) in [
+            ("Ascent", self.ascent),
+            ("Descent", self.descent),
+            ("Line height", self.ascent + self.descent),
+            ("Glyphs", self.glyphs.len() as i32),
+        ] {
+            writeln!(html, "<tr><th>{name}</th><td>{value}</td></tr>").unwrap();
+        }
+        writeln!(html, "</table>").unwrap();
+
+        writeln!(html, "<p class=\"legend\">").unwrap();
+        for (name, color) in [
+            ("Ascent / descent", METRICS_COLOR),
+            ("Baseline", BASELINE_COLOR),
+            ("Bounding box", BOUNDING_BOX_COLOR),
+        ] {
+            writeln!(html, "<span style=\"color: {color}\">&#9632; {name}</span>").unwrap();
+        }
+        writeln!(html, "</p>").unwrap();
+
+        writeln!(html, "<h2>Sample text</h2>").unwrap();
+        html.push_str(&self.text_svg());
+
+        writeln!(html, "<h2>Glyphs</h2>").unwrap();
+        writeln!(html, "<div class=\"grid\">").unwrap();
+        for glyph in &self.glyphs {
+            writeln!(
+                html,
+                "<div class=\"glyph\" title=\"U+{:04X}\">",
+                u32::from(glyph.c)
+            )
+            .unwrap();
+            html.push_str(&self.glyph_svg(glyph.c).unwrap());
+            write!(html, "U+{:04X}", u32::from(glyph.c)).unwrap();
+            if !glyph.c.is_control() {
+                write!(html, "<br>{}", escape(&glyph.c.to_string())).unwrap();
+            }
+            writeln!(html, "\n</div>").unwrap();
+        }
+        writeln!(html, "</div>").unwrap();
+
+        writeln!(html, "</body>").unwrap();
+        writeln!(html, "</html>").unwrap();
+
+        html
+    }
+
+    /// Saves the HTML page to a file.
+    pub fn save_html<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+
+        fs::write(path, self.html())
+            .with_context(|| format!("failed to write HTML file to {}", path.display()))
+    }
+
+    /// Saves the sample text SVG image to a file.
+    pub fn save_svg<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+
+        fs::write(path, self.text_svg())
+            .with_context(|| format!("failed to write SVG file to {}", path.display()))
+    }
+
+    /// Returns the Y coordinate of the top edge of a glyph cell.
+    fn top(&self, glyph: &SpecimenGlyph) -> i32 {
+        (-self.ascent).min(glyph.bounding_box.top_left.y)
+    }
+
+    /// Returns the Y coordinate below the bottom edge of a glyph cell.
+    fn bottom(&self, glyph: &SpecimenGlyph) -> i32 {
+        let bounding_box_bottom =
+            glyph.bounding_box.top_left.y + glyph.bounding_box.size.height as i32;
+
+        self.descent
+            .max(bounding_box_bottom)
+            .max(self.top(glyph) + 1)
+    }
+
+    /// Draws the ascent, baseline and descent lines.
+    fn metrics_lines(&self, svg: &mut Svg, baseline: i32) {
+        svg.horizontal_line(baseline - self.ascent, METRICS_COLOR);
+        svg.horizontal_line(baseline + self.descent, METRICS_COLOR);
+        svg.horizontal_line(baseline, BASELINE_COLOR);
+    }
+}
+
+/// SVG image builder.
+///
+/// The view box uses font pixels as units and is extended by one pixel on
+/// each side to make lines on the edges visible.
+#[derive(Debug)]
+struct Svg {
+    area: Rectangle,
+    output: String,
+}
+
+impl Svg {
+    fn new(area: Rectangle, scale: u32) -> Self {
+        let view_box = area.offset(1);
+
+        let mut output = String::new();
+        writeln!(
+            output,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"{} {} {} {}\" shape-rendering=\"crispEdges\">",
+            view_box.size.width * scale,
+            view_box.size.height * scale,
+            view_box.top_left.x,
+            view_box.top_left.y,
+            view_box.size.width,
+            view_box.size.height,
+        )
+        .unwrap();
+
+        Self { area, output }
+    }
+
+    fn line(&mut self, start: Point, end: Point, color: &str) {
+        writeln!(
+            self.output,
+            "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"{color}\" stroke-width=\"1\" vector-effect=\"non-scaling-stroke\"/>",
+            start.x, start.y, end.x, end.y
+        )
+        .unwrap();
+    }
+
+    fn horizontal_line(&mut self, y: i32, color: &str) {
+        let left = self.area.top_left.x - 1;
+        let right = left + self.area.size.width as i32 + 2;
+
+        self.line(Point::new(left, y), Point::new(right, y), color);
+    }
+
+    fn vertical_line(&mut self, x: i32, color: &str) {
+        let top = self.area.top_left.y - 1;
+        let bottom = top + self.area.size.height as i32 + 2;
+
+        self.line(Point::new(x, top), Point::new(x, bottom), color);
+    }
+
+    fn bounding_box(&mut self, bounding_box: &Rectangle) {
+        if bounding_box.is_zero_sized() {
+            return;
+        }
+
+        writeln!(
+            self.output,
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"none\" stroke=\"{BOUNDING_BOX_COLOR}\" stroke-width=\"1\" vector-effect=\"non-scaling-stroke\"/>",
+            bounding_box.top_left.x,
+            bounding_box.top_left.y,
+            bounding_box.size.width,
+            bounding_box.size.height,
+        )
+        .unwrap();
+    }
+
+    /// Draws the pixels as horizontal runs.
+    fn pixels(&mut self, pixels: impl Iterator<Item = Point>) {
+        let mut rows = BTreeMap::<i32, BTreeSet<i32>>::new();
+        for point in pixels {
+            rows.entry(point.y).or_default().insert(point.x);
+        }
+
+        writeln!(self.output, "<g fill=\"{GLYPH_COLOR}\">").unwrap();
+        for (y, columns) in rows {
+            for (x, width) in runs(columns.into_iter()) {
+                writeln!(
+                    self.output,
+                    "<rect x=\"{x}\" y=\"{y}\" width=\"{width}\" height=\"1\"/>"
+                )
+                .unwrap();
+            }
+        }
+        writeln!(self.output, "</g>").unwrap();
+    }
+
+    fn finish(mut self) -> String {
+        writeln!(self.output, "</svg>").unwrap();
+        self.output
+    }
+}
+
+/// Combines sorted X coordinates into runs.
+///
+/// Returns the start and length of each run.
+fn runs(columns: impl Iterator<Item = i32>) -> Vec<(i32, u32)> {
+    let mut runs: Vec<(i32, u32)> = Vec::new();
+
+    for x in columns {
+        match runs.last_mut() {
+            Some((start, length)) if *start + *length as i32 == x => *length += 1,
+            _ => runs.push((x, 1)),
+        }
+    }
+
+    runs
+}
+
+/// Escapes special characters in HTML text.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::FontConverter;
+
+    use super::*;
+
+    const FONT: &str = r#"
+        STARTFONT 2.1
+        FONT -test-Test-Medium-R-Normal--8-80-75-75-P-60-ISO10646-1
+        SIZE 8 75 75
+        FONTBOUNDINGBOX 6 8 0 -2
+        STARTPROPERTIES 2
+        FONT_ASCENT 6
+        FONT_DESCENT 2
+        ENDPROPERTIES
+        CHARS 2
+        STARTCHAR A
+        ENCODING 65
+        DWIDTH 6 0
+        BBX 5 6 0 0
+        BITMAP
+        20
+        50
+        88
+        F8
+        88
+        88
+        ENDCHAR
+        STARTCHAR less
+        ENCODING 60
+        DWIDTH 5 0
+        BBX 3 3 1 -1
+        BITMAP
+        20
+        40
+        20
+        ENDCHAR
+        ENDFONT
+    "#;
+
+    #[test]
+    fn bdf_and_bdf_font_are_identical() {
+        let font = Font::parse(FONT).unwrap();
+        let converted = FontConverter::with_font(&font, "FONT")
+            .convert_eg_bdf()
+            .unwrap();
+
+        let bdf = Specimen::from_bdf(&font).sample_text("A<A");
+        let bdf_font = Specimen::from_bdf_font(&converted.as_font())
+            .title(&font.metadata.name)
+            .sample_text("A<A");
+
+        assert_eq!(bdf.html(), bdf_font.html());
+        assert_eq!(bdf.glyphs[1].pixels, bdf_font.glyphs[1].pixels);
+    }
+
+    #[test]
+    fn glyph_svg() {
+        let specimen = Specimen::from_bdf(&Font::parse(FONT).unwrap()).scale(2);
+
+        assert_eq!(specimen.glyph_svg('B'), None);
+        assert_eq!(
+            specimen.glyph_svg('A').unwrap(),
+            concat!(
+                "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"16\" height=\"20\" viewBox=\"-1 -7 8 10\" shape-rendering=\"crispEdges\">\n",
+                "<line x1=\"0\" y1=\"-7\" x2=\"0\" y2=\"3\" stroke=\"#4682b4\" stroke-width=\"1\" vector-effect=\"non-scaling-stroke\"/>\n",
+                "<line x1=\"6\" y1=\"-7\" x2=\"6\" y2=\"3\" stroke=\"#4682b4\" stroke-width=\"1\" vector-effect=\"non-scaling-stroke\"/>\n",
+                "<line x1=\"-1\" y1=\"-6\" x2=\"7\" y2=\"-6\" stroke=\"#4682b4\" stroke-width=\"1\" vector-effect=\"non-scaling-stroke\"/>\n",
+                "<line x1=\"-1\" y1=\"2\" x2=\"7\" y2=\"2\" stroke=\"#4682b4\" stroke-width=\"1\" vector-effect=\"non-scaling-stroke\"/>\n",
+                "<line x1=\"-1\" y1=\"0\" x2=\"7\" y2=\"0\" stroke=\"#dc143c\" stroke-width=\"1\" vector-effect=\"non-scaling-stroke\"/>\n",
+                "<rect x=\"0\" y=\"-6\" width=\"5\" height=\"6\" fill=\"none\" stroke=\"#9acd32\" stroke-width=\"1\" vector-effect=\"non-scaling-stroke\"/>\n",
+                "<g fill=\"#000000\">\n",
+                "<rect x=\"2\" y=\"-6\" width=\"1\" height=\"1\"/>\n",
+                "<rect x=\"1\" y=\"-5\" width=\"1\" height=\"1\"/>\n",
+                "<rect x=\"3\" y=\"-5\" width=\"1\" height=\"1\"/>\n",
+                "<rect x=\"0\" y=\"-4\" width=\"1\" height=\"1\"/>\n",
+                "<rect x=\"4\" y=\"-4\" width=\"1\" height=\"1\"/>\n",
+                "<rect x=\"0\" y=\"-3\" width=\"5\" height=\"1\"/>\n",
+                "<rect x=\"0\" y=\"-2\" width=\"1\" height=\"1\"/>\n",
+                "<rect x=\"4\" y=\"-2\" width=\"1\" height=\"1\"/>\n",
+                "<rect x=\"0\" y=\"-1\" width=\"1\" height=\"1\"/>\n",
+                "<rect x=\"4\" y=\"-1\" width=\"1\" height=\"1\"/>\n",
+                "</g>\n",
+                "</svg>\n",
+            )
+        );
+    }
+
+    #[test]
+    fn text_svg() {
+        let specimen = Specimen::from_bdf(&Font::parse(FONT).unwrap()).sample_text("AA\n<");
+        let svg = specimen.text_svg();
+
+        // Two lines with a width of two glyphs.
+        assert!(
+            svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"56\" height=\"72\" viewBox=\"-1 -1 14 18\""),
+            "{svg}"
+        );
+        // The pixels in each row are combined into runs.
+        assert!(svg.contains("<rect x=\"0\" y=\"3\" width=\"5\" height=\"1\"/>\n<rect x=\"6\" y=\"3\" width=\"5\" height=\"1\"/>\n"), "{svg}");
+        // Baselines of both lines.
+        assert!(
+            svg.contains("y1=\"6\" x2=\"13\" y2=\"6\" stroke=\"#dc143c\""),
+            "{svg}"
+        );
+        assert!(
+            svg.contains("y1=\"14\" x2=\"13\" y2=\"14\" stroke=\"#dc143c\""),
+            "{svg}"
+        );
+    }
+
+    #[test]
+    fn html() {
+        let html = Specimen::from_bdf(&Font::parse(FONT).unwrap()).html();
+
+        assert!(
+            html.contains("<title>-test-Test-Medium-R-Normal--8-80-75-75-P-60-ISO10646-1</title>")
+        );
+        assert!(html.contains("<tr><th>Line height</th><td>8</td></tr>"));
+        assert!(html.contains("U+003C<br>&lt;\n</div>"));
+        assert_eq!(html.matches("<div class=\"glyph\"").count(), 2);
+    }
+}
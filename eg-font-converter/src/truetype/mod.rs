@@ -0,0 +1,846 @@
+use std::{borrow::Cow, collections::BTreeSet, ops::Range, path::Path};
+
+use anyhow::{anyhow, ensure, Context, Result};
+use bdf_parser::{
+    BoundingBox, Coord, Encoding, Font, Glyph, GlyphWidth, Glyphs, Metadata, Metrics, Property,
+    PropertyValue,
+};
+use ttf_parser::{name_id, Face, GlyphId, OutlineBuilder, Style};
+
+use crate::{FileOrData, GlyphRange};
+
+mod strikes;
+
+pub use strikes::{BitmapStrike, StrikeExtractor};
+
+/// Number of samples per pixel in each direction.
+const SUBSAMPLES: usize = 16;
+
+/// Number of line segments used to approximate a curve.
+const CURVE_SEGMENTS: usize = 16;
+
+/// Rasterization mode.
+///
+/// See [`OutlineRasterizer::raster_mode`] for more information.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RasterMode {
+    /// Pixels are set if their coverage is greater than or equal to the threshold.
+    ///
+    /// The threshold is specified in the range from 0 (uncovered) to 255
+    /// (fully covered). The generated font uses 1 bit per pixel.
+    Threshold(u8),
+
+    /// The coverage of each pixel is stored as a gray value.
+    Coverage {
+        /// Bits per pixel.
+        ///
+        /// Supported values are 2, 4 and 8.
+        bits_per_pixel: u32,
+    },
+}
+
+impl Default for RasterMode {
+    fn default() -> Self {
+        Self::Threshold(128)
+    }
+}
+
+/// Outline font rasterizer.
+///
+/// Rasterizes the glyph outlines in TrueType or OpenType fonts at a fixed pixel
+/// size. The pixel size is the size of the em square in pixels.
+///
+/// # Examples
+///
+/// ```no_run
+/// use eg_font_converter::{FontConverter, OutlineRasterizer};
+///
+/// let font = OutlineRasterizer::with_file("DejaVuSans.ttf", 12)
+///     .glyphs(' '..='~')
+///     .rasterize()
+///     .unwrap();
+///
+/// let output = FontConverter::with_font(&font, "DEJAVU_SANS_12")
+///     .convert_eg_bdf()
+///     .unwrap();
+/// ```
+#[derive(Debug)]
+pub struct OutlineRasterizer<'a> {
+    font: FileOrData<'a>,
+    pixel_size: u32,
+    face_index: u32,
+    glyphs: BTreeSet<char>,
+    raster_mode: RasterMode,
+}
+
+impl<'a> OutlineRasterizer<'a> {
+    /// Creates a rasterizer for a TrueType or OpenType file.
+    pub fn with_file<P: AsRef<Path>>(font_file: P, pixel_size: u32) -> Self {
+        Self::new(FileOrData::File(font_file.as_ref().to_owned()), pixel_size)
+    }
+
+    /// Creates a rasterizer for TrueType or OpenType data.
+    pub fn with_data(font: &'a [u8], pixel_size: u32) -> Self {
+        Self::new(FileOrData::Data(font), pixel_size)
+    }
+
+    fn new(font: FileOrData<'a>, pixel_size: u32) -> Self {
+        Self {
+            font,
+            pixel_size,
+            face_index: 0,
+            glyphs: BTreeSet::new(),
+            raster_mode: RasterMode::default(),
+        }
+    }
+
+    /// Adds glyphs to the rasterized font.
+    ///
+    /// If no glyphs are added, all glyphs which are mapped to a Unicode code
+    /// point are rasterized. See [`FontConverter::glyphs`](crate::FontConverter::glyphs)
+    /// for the supported argument types.
+    pub fn glyphs<G: GlyphRange>(mut self, glyphs: G) -> Self {
+        self.glyphs.extend(glyphs.glyphs());
+
+        self
+    }
+
+    /// Sets the rasterization mode.
+    ///
+    /// Defaults to [`RasterMode::Threshold`] with a threshold of 128.
+    pub fn raster_mode(mut self, raster_mode: RasterMode) -> Self {
+        self.raster_mode = raster_mode;
+
+        self
+    }
+
+    /// Sets the index of the font in a font collection.
+    ///
+    /// Defaults to `0`.
+    pub fn face_index(mut self, index: u32) -> Self {
+        self.face_index = index;
+
+        self
+    }
+
+    /// Rasterizes the font.
+    pub fn rasterize(&self) -> Result<Font> {
+        ensure!(self.pixel_size > 0, "pixel size must not be zero");
+
+        let bits_per_pixel = match self.raster_mode {
+            RasterMode::Threshold(_) => 1,
+            RasterMode::Coverage { bits_per_pixel } => {
+                ensure!(
+                    [2, 4, 8].contains(&bits_per_pixel),
+                    "unsupported bits per pixel: {bits_per_pixel}"
+                );
+                bits_per_pixel
+            }
+        };
+
+        let data = read_font(&self.font)?;
+        let face = parse_face(&data, self.face_index)?;
+
+        let units_per_em = i32::from(face.units_per_em());
+        let scale = self.pixel_size as f32 / units_per_em as f32;
+
+        let chars = if self.glyphs.is_empty() {
+            unicode_chars(&face)
+        } else {
+            self.glyphs.clone()
+        };
+
+        let glyphs = chars
+            .into_iter()
+            .map(|c| {
+                let glyph_id = glyph_index(&face, c)?;
+
+                let advance = i32::from(face.glyph_hor_advance(glyph_id).unwrap_or_default());
+                let device_width = (advance as f32 * scale).round() as i32;
+                let scalable_width = scalable_width(&face, glyph_id);
+
+                let (bounding_box, bitmap) = self.rasterize_glyph(&face, glyph_id, scale);
+
+                Ok(Glyph {
+                    name: glyph_name(&face, glyph_id, c),
+                    encoding: Encoding::Standard(c.into()),
+                    width_horizontal: Some(GlyphWidth {
+                        scalable: Coord::new(scalable_width, 0),
+                        device: Coord::new(device_width, 0),
+                    }),
+                    width_vertical: None,
+                    bounding_box,
+                    origin_offset: None,
+                    bitmap,
+                })
+            })
+            .collect::<Result<Glyphs>>()?;
+
+        let metrics = Metrics {
+            ascent: (f32::from(face.ascender()) * scale).ceil().max(0.0) as u32,
+            descent: (-f32::from(face.descender()) * scale).ceil().max(0.0) as u32,
+        };
+
+        let mut metadata = Metadata::for_imported_font(&glyphs, &metrics, self.pixel_size, true);
+        metadata.bits_per_pixel = bits_per_pixel;
+        set_xlfd_properties(&mut metadata, &face);
+
+        Ok(Font {
+            metadata,
+            glyphs,
+            metrics,
+        })
+    }
+
+    /// Rasterizes a glyph and returns the bounding box and bitmap.
+    fn rasterize_glyph(
+        &self,
+        face: &Face<'_>,
+        glyph_id: GlyphId,
+        scale: f32,
+    ) -> (BoundingBox, Vec<u8>) {
+        let mut outline = Outline::new(scale);
+        face.outline_glyph(glyph_id, &mut outline);
+
+        let coverage = outline.coverage();
+
+        let max_value = (1u32 << bits_per_pixel(self.raster_mode)) - 1;
+        let value = |x: usize, y: usize| -> u32 {
+            let coverage = u32::from(coverage.values[x + y * coverage.width]);
+            match self.raster_mode {
+                RasterMode::Threshold(threshold) => u32::from(coverage >= u32::from(threshold)),
+                RasterMode::Coverage { .. } => (coverage * max_value + 127) / 255,
+            }
+        };
+
+        // Trim the bitmap to the set pixels.
+        let mut columns: Option<Range<usize>> = None;
+        let mut rows: Option<Range<usize>> = None;
+        for y in 0..coverage.height {
+            for x in 0..coverage.width {
+                if value(x, y) != 0 {
+                    columns = Some(columns.map_or(x..x + 1, |c| c.start.min(x)..c.end.max(x + 1)));
+                    rows = Some(rows.map_or(y..y + 1, |r| r.start.min(y)..r.end.max(y + 1)));
+                }
+            }
+        }
+        let (Some(columns), Some(rows)) = (columns, rows) else {
+            return (BoundingBox::default(), Vec::new());
+        };
+
+        let bits_per_pixel = bits_per_pixel(self.raster_mode) as usize;
+        let bytes_per_row = (columns.len() * bits_per_pixel).div_ceil(8);
+        let mut bitmap = vec![0; bytes_per_row * rows.len()];
+        for (y, row) in rows.clone().enumerate() {
+            for (x, column) in columns.clone().enumerate() {
+                let bit_offset = x * bits_per_pixel;
+                let shift = 8 - bits_per_pixel - bit_offset % 8;
+                bitmap[bit_offset / 8 + y * bytes_per_row] |= (value(column, row) << shift) as u8;
+            }
+        }
+
+        let bounding_box = BoundingBox {
+            offset: Coord::new(
+                coverage.offset.x + columns.start as i32,
+                coverage.offset.y + (coverage.height - rows.end) as i32,
+            ),
+            size: Coord::new(columns.len() as i32, rows.len() as i32),
+        };
+
+        (bounding_box, bitmap)
+    }
+}
+
+fn bits_per_pixel(raster_mode: RasterMode) -> u32 {
+    match raster_mode {
+        RasterMode::Threshold(_) => 1,
+        RasterMode::Coverage { bits_per_pixel } => bits_per_pixel,
+    }
+}
+
+/// Reads the font data.
+fn read_font<'a>(font: &'a FileOrData<'_>) -> Result<Cow<'a, [u8]>> {
+    Ok(match font {
+        FileOrData::File(file) => Cow::Owned(
+            std::fs::read(file)
+                .with_context(|| format!("couldn't read font file from {file:?}"))?,
+        ),
+        FileOrData::Data(data) => Cow::Borrowed(*data),
+    })
+}
+
+fn parse_face(data: &[u8], face_index: u32) -> Result<Face<'_>> {
+    Face::parse(data, face_index).context("couldn't parse TrueType or OpenType font")
+}
+
+/// Returns the glyph ID which is mapped to a character.
+fn glyph_index(face: &Face<'_>, c: char) -> Result<GlyphId> {
+    face.glyph_index(c).ok_or_else(|| {
+        anyhow!(
+            "glyph '{}' (U+{:04X}) is not contained in the font",
+            c,
+            u32::from(c)
+        )
+    })
+}
+
+/// Returns the glyph name from the `post` table or a name based on the code point.
+fn glyph_name(face: &Face<'_>, glyph_id: GlyphId, c: char) -> String {
+    face.glyph_name(glyph_id)
+        .filter(|name| !name.is_empty())
+        .map_or_else(|| format!("U+{:04X}", u32::from(c)), str::to_string)
+}
+
+/// Returns the advance width in units of 1/1000 em.
+fn scalable_width(face: &Face<'_>, glyph_id: GlyphId) -> i32 {
+    let units_per_em = i32::from(face.units_per_em());
+    let advance = i32::from(face.glyph_hor_advance(glyph_id).unwrap_or_default());
+
+    (advance * 1000 + units_per_em / 2) / units_per_em
+}
+
+/// Returns all characters which are mapped to a glyph in the Unicode cmap subtables.
+fn unicode_chars(face: &Face<'_>) -> BTreeSet<char> {
+    let mut chars = BTreeSet::new();
+
+    for subtable in face
+        .tables()
+        .cmap
+        .iter()
+        .flat_map(|cmap| cmap.subtables)
+        .filter(|subtable| subtable.is_unicode())
+    {
+        subtable.codepoints(|code_point| {
+            if let Some(c) = char::from_u32(code_point) {
+                if subtable.glyph_index(code_point).is_some_and(|id| id.0 != 0) {
+                    chars.insert(c);
+                }
+            }
+        });
+    }
+
+    chars
+}
+
+/// Adds an XLFD font name and the XLFD properties, which are available in the font.
+fn set_xlfd_properties(metadata: &mut Metadata, face: &Face<'_>) {
+    let name = |name_id| {
+        face.names()
+            .into_iter()
+            .filter(|name| name.name_id == name_id && name.is_unicode())
+            .find_map(|name| name.to_string())
+    };
+
+    // Hyphens are used as separators in XLFD names.
+    let family = name(name_id::FAMILY)
+        .unwrap_or_else(|| "unknown".to_string())
+        .replace('-', " ");
+    let weight = match face.weight().to_number() {
+        0..=150 => "Thin",
+        151..=250 => "ExtraLight",
+        251..=350 => "Light",
+        351..=550 => "Medium",
+        551..=650 => "SemiBold",
+        651..=750 => "Bold",
+        751..=850 => "ExtraBold",
+        _ => "Black",
+    };
+    let slant = match face.style() {
+        Style::Normal => "R",
+        Style::Italic => "I",
+        Style::Oblique => "O",
+    };
+    let setwidth = format!("{:?}", face.width());
+    let spacing = if face.is_monospaced() { "M" } else { "P" };
+
+    let properties = &mut metadata.properties;
+    for (property, value) in [
+        (Property::FamilyName, family.as_str()),
+        (Property::WeightName, weight),
+        (Property::Slant, slant),
+        (Property::SetwidthName, &setwidth),
+        (Property::Spacing, spacing),
+    ] {
+        properties.set(property, PropertyValue::Text(value.to_string()));
+    }
+    if let Some(copyright) = name(name_id::COPYRIGHT_NOTICE) {
+        properties.set(Property::Copyright, PropertyValue::Text(copyright));
+    }
+
+    let pixel_size = properties
+        .try_get::<i32>(Property::PixelSize)
+        .ok()
+        .flatten();
+    let average_width = properties
+        .try_get::<i32>(Property::AverageWidth)
+        .ok()
+        .flatten();
+    metadata.name = format!(
+        "-unknown-{family}-{weight}-{slant}-{setwidth}--{}-{}-{}-{}-{spacing}-{}-ISO10646-1",
+        pixel_size.unwrap_or_default(),
+        metadata.point_size * 10,
+        metadata.resolution.x,
+        metadata.resolution.y,
+        average_width.unwrap_or_default(),
+    );
+}
+
+/// Glyph outline, which is converted into line segments in pixel coordinates.
+#[derive(Debug)]
+struct Outline {
+    scale: f32,
+    edges: Vec<[(f32, f32); 2]>,
+    start: (f32, f32),
+    position: (f32, f32),
+}
+
+impl Outline {
+    fn new(scale: f32) -> Self {
+        Self {
+            scale,
+            edges: Vec::new(),
+            start: (0.0, 0.0),
+            position: (0.0, 0.0),
+        }
+    }
+
+    fn add_line(&mut self, to: (f32, f32)) {
+        if to != self.position {
+            self.edges.push([self.position, to]);
+        }
+        self.position = to;
+    }
+
+    fn add_curve(&mut self, point: impl Fn(f32) -> (f32, f32)) {
+        for i in 1..=CURVE_SEGMENTS {
+            self.add_line(point(i as f32 / CURVE_SEGMENTS as f32));
+        }
+    }
+
+    /// Calculates the pixel coverage by using the nonzero winding rule.
+    fn coverage(&self) -> Coverage {
+        let (mut min, mut max) = ((f32::MAX, f32::MAX), (f32::MIN, f32::MIN));
+        for (x, y) in self.edges.iter().flatten() {
+            min = (min.0.min(*x), min.1.min(*y));
+            max = (max.0.max(*x), max.1.max(*y));
+        }
+        if self.edges.is_empty() {
+            return Coverage::default();
+        }
+
+        let left = min.0.floor() as i32;
+        let bottom = min.1.floor() as i32;
+        let width = (max.0.ceil() as i32 - left) as usize;
+        let height = (max.1.ceil() as i32 - bottom) as usize;
+
+        let mut samples = vec![0u32; width * height];
+        let mut crossings = Vec::new();
+
+        for y in 0..height {
+            for subsample in 0..SUBSAMPLES {
+                // Rows are stored from top to bottom.
+                let sample_y = (bottom + (height - 1 - y) as i32) as f32
+                    + (subsample as f32 + 0.5) / SUBSAMPLES as f32;
+
+                crossings.clear();
+                for [(x1, y1), (x2, y2)] in &self.edges {
+                    if (*y1 <= sample_y && sample_y < *y2) || (*y2 <= sample_y && sample_y < *y1) {
+                        let x = x1 + (sample_y - y1) * (x2 - x1) / (y2 - y1);
+                        crossings.push((x, if y2 > y1 { 1 } else { -1 }));
+                    }
+                }
+                crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+                let mut winding = 0;
+                let mut span_start = 0.0;
+                for (x, direction) in &crossings {
+                    if winding == 0 {
+                        span_start = *x;
+                    }
+                    winding += direction;
+                    if winding != 0 {
+                        continue;
+                    }
+
+                    // Count all sample points inside the span.
+                    let sample_index = |x: f32| {
+                        ((x - left as f32) * SUBSAMPLES as f32 - 0.5)
+                            .ceil()
+                            .clamp(0.0, (width * SUBSAMPLES) as f32)
+                            as usize
+                    };
+                    for index in sample_index(span_start)..sample_index(*x) {
+                        samples[index / SUBSAMPLES + y * width] += 1;
+                    }
+                }
+            }
+        }
+
+        let max_samples = (SUBSAMPLES * SUBSAMPLES) as u32;
+        Coverage {
+            offset: Coord::new(left, bottom),
+            width,
+            height,
+            values: samples
+                .into_iter()
+                .map(|samples| ((samples * 255 + max_samples / 2) / max_samples) as u8)
+                .collect(),
+        }
+    }
+}
+
+impl OutlineBuilder for Outline {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.position = (x * self.scale, y * self.scale);
+        self.start = self.position;
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.add_line((x * self.scale, y * self.scale));
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let p0 = self.position;
+        let (p1, p2) = (
+            (x1 * self.scale, y1 * self.scale),
+            (x * self.scale, y * self.scale),
+        );
+
+        self.add_curve(|t| {
+            let u = 1.0 - t;
+            (
+                u * u * p0.0 + 2.0 * u * t * p1.0 + t * t * p2.0,
+                u * u * p0.1 + 2.0 * u * t * p1.1 + t * t * p2.1,
+            )
+        });
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let p0 = self.position;
+        let (p1, p2, p3) = (
+            (x1 * self.scale, y1 * self.scale),
+            (x2 * self.scale, y2 * self.scale),
+            (x * self.scale, y * self.scale),
+        );
+
+        self.add_curve(|t| {
+            let u = 1.0 - t;
+            (
+                u * u * u * p0.0
+                    + 3.0 * u * u * t * p1.0
+                    + 3.0 * u * t * t * p2.0
+                    + t * t * t * p3.0,
+                u * u * u * p0.1
+                    + 3.0 * u * u * t * p1.1
+                    + 3.0 * u * t * t * p2.1
+                    + t * t * t * p3.1,
+            )
+        });
+    }
+
+    fn close(&mut self) {
+        self.add_line(self.start);
+    }
+}
+
+/// Pixel coverage of a glyph.
+#[derive(Debug, Default)]
+struct Coverage {
+    /// Offset of the lower left corner.
+    offset: Coord,
+    width: usize,
+    height: usize,
+    /// Coverage values from top to bottom, in the range from 0 to 255.
+    values: Vec<u8>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    pub(super) fn u16s(values: &[u16]) -> Vec<u8> {
+        values.iter().flat_map(|v| v.to_be_bytes()).collect()
+    }
+
+    /// Builds a font file from the given tables.
+    pub(super) fn sfnt(mut tables: Vec<([u8; 4], Vec<u8>)>) -> Vec<u8> {
+        tables.sort_by_key(|(tag, _)| *tag);
+
+        let mut font = 0x0001_0000u32.to_be_bytes().to_vec();
+        font.extend(u16s(&[tables.len() as u16, 0, 0, 0]));
+        let mut offset = 12 + 16 * tables.len();
+        for (tag, table) in &tables {
+            font.extend(tag);
+            font.extend([0; 4]);
+            font.extend((offset as u32).to_be_bytes());
+            font.extend((table.len() as u32).to_be_bytes());
+            offset += table.len().next_multiple_of(4);
+        }
+        for (_, table) in &tables {
+            font.extend(table);
+            font.resize(font.len().next_multiple_of(4), 0);
+        }
+
+        font
+    }
+
+    fn test_font() -> Vec<u8> {
+        sfnt(test_tables())
+    }
+
+    /// Returns the tables of a TrueType font with an `A` (square), `B`
+    /// (rectangle) and space glyph.
+    ///
+    /// The font uses 1000 units per em, which results in 100 units per pixel at
+    /// a pixel size of 10.
+    pub(super) fn test_tables() -> Vec<([u8; 4], Vec<u8>)> {
+        // Rectangular glyph outlines.
+        let rectangles: [Option<[i16; 4]>; 4] =
+            [None, Some([100, 0, 500, 400]), Some([0, 0, 250, 100]), None];
+        let advances = [500u16, 600, 300, 250];
+
+        let mut glyf = Vec::new();
+        let mut loca = Vec::new();
+        for rectangle in rectangles {
+            loca.extend((glyf.len() as u32).to_be_bytes());
+
+            let Some([x_min, y_min, x_max, y_max]) = rectangle else {
+                continue;
+            };
+
+            // Header, end point of the single contour and instruction length.
+            glyf.extend(u16s(&[1]));
+            for value in [x_min, y_min, x_max, y_max] {
+                glyf.extend(value.to_be_bytes());
+            }
+            glyf.extend(u16s(&[3, 0]));
+
+            // On curve points with 16 bit deltas.
+            glyf.extend([0x01; 4]);
+            let points = [
+                (x_min, y_min),
+                (x_min, y_max),
+                (x_max, y_max),
+                (x_max, y_min),
+            ];
+            let mut previous = (0, 0);
+            for (x, _) in points {
+                glyf.extend((x - previous.0).to_be_bytes());
+                previous.0 = x;
+            }
+            for (_, y) in points {
+                glyf.extend((y - previous.1).to_be_bytes());
+                previous.1 = y;
+            }
+        }
+        loca.extend((glyf.len() as u32).to_be_bytes());
+
+        let mut head = vec![0; 54];
+        head[0..4].copy_from_slice(&0x0001_0000u32.to_be_bytes());
+        head[12..16].copy_from_slice(&0x5F0F_3CF5u32.to_be_bytes());
+        head[18..20].copy_from_slice(&1000u16.to_be_bytes());
+        head[50..52].copy_from_slice(&1u16.to_be_bytes()); // long loca offsets
+
+        let mut hhea = vec![0; 36];
+        hhea[0..4].copy_from_slice(&0x0001_0000u32.to_be_bytes());
+        hhea[4..6].copy_from_slice(&800i16.to_be_bytes());
+        hhea[6..8].copy_from_slice(&(-200i16).to_be_bytes());
+        hhea[34..36].copy_from_slice(&4u16.to_be_bytes());
+
+        let mut maxp = 0x0000_5000u32.to_be_bytes().to_vec();
+        maxp.extend(u16s(&[4]));
+
+        let hmtx = advances
+            .iter()
+            .flat_map(|advance| u16s(&[*advance, 0]))
+            .collect::<Vec<_>>();
+
+        // Format 12 cmap with the groups ' ' -> 3 and 'A'..='B' -> 1.
+        let groups: [[u32; 3]; 2] = [[0x20, 0x20, 3], [0x41, 0x42, 1]];
+        let mut cmap = u16s(&[0, 1, 3, 10]);
+        cmap.extend(12u32.to_be_bytes());
+        cmap.extend(u16s(&[12, 0]));
+        cmap.extend((16 + groups.len() as u32 * 12).to_be_bytes());
+        cmap.extend(0u32.to_be_bytes());
+        cmap.extend((groups.len() as u32).to_be_bytes());
+        cmap.extend(groups.iter().flatten().flat_map(|v| v.to_be_bytes()));
+
+        let names = [(0, "Copyright Test"), (1, "Test-Sans")];
+        let mut name = u16s(&[0, names.len() as u16, 6 + names.len() as u16 * 12]);
+        let mut strings = Vec::new();
+        for (name_id, value) in names {
+            let value = u16s(&value.encode_utf16().collect::<Vec<_>>());
+            name.extend(u16s(&[
+                3,
+                1,
+                0x409,
+                name_id,
+                value.len() as u16,
+                strings.len() as u16,
+            ]));
+            strings.extend(value);
+        }
+        name.extend(strings);
+
+        vec![
+            (*b"cmap", cmap),
+            (*b"glyf", glyf),
+            (*b"head", head),
+            (*b"hhea", hhea),
+            (*b"hmtx", hmtx),
+            (*b"loca", loca),
+            (*b"maxp", maxp),
+            (*b"name", name),
+        ]
+    }
+
+    #[test]
+    fn threshold() {
+        let data = test_font();
+        let font = OutlineRasterizer::with_data(&data, 10).rasterize().unwrap();
+
+        assert_eq!(font.glyphs.len(), 3);
+        assert_eq!(
+            font.metrics,
+            Metrics {
+                ascent: 8,
+                descent: 2
+            }
+        );
+        assert_eq!(font.metadata.bits_per_pixel, 1);
+        assert_eq!(
+            font.metadata.name,
+            "-unknown-Test Sans-Medium-R-Normal--10-100-72-72-P-40-ISO10646-1"
+        );
+
+        let properties = &font.metadata.properties;
+        assert_eq!(properties.try_get::<i32>(Property::FontAscent), Ok(Some(8)));
+        assert_eq!(
+            properties.try_get::<i32>(Property::FontDescent),
+            Ok(Some(2))
+        );
+        assert_eq!(
+            properties.try_get::<String>(Property::FamilyName),
+            Ok(Some("Test Sans".to_string()))
+        );
+        assert_eq!(
+            properties.try_get::<String>(Property::Copyright),
+            Ok(Some("Copyright Test".to_string()))
+        );
+
+        let a = font.glyphs.get('A').unwrap();
+        assert_eq!(a.name, "U+0041");
+        assert_eq!(
+            a.bounding_box,
+            BoundingBox {
+                offset: Coord::new(1, 0),
+                size: Coord::new(4, 4),
+            }
+        );
+        assert_eq!(
+            a.width_horizontal,
+            Some(GlyphWidth {
+                scalable: Coord::new(600, 0),
+                device: Coord::new(6, 0),
+            })
+        );
+        assert_eq!(a.bitmap, [0xF0; 4]);
+
+        // The right column of `B` is half covered.
+        let b = font.glyphs.get('B').unwrap();
+        assert_eq!(b.bounding_box.size, Coord::new(3, 1));
+        assert_eq!(b.bitmap, [0xE0]);
+
+        let space = font.glyphs.get(' ').unwrap();
+        assert_eq!(space.bounding_box, BoundingBox::default());
+        assert_eq!(space.width_horizontal.unwrap().device, Coord::new(3, 0));
+        assert_eq!(space.width_horizontal.unwrap().scalable, Coord::new(250, 0));
+
+        let font = OutlineRasterizer::with_data(&data, 10)
+            .raster_mode(RasterMode::Threshold(200))
+            .rasterize()
+            .unwrap();
+        assert_eq!(font.glyphs.get('B').unwrap().bitmap, [0xC0]);
+    }
+
+    #[test]
+    fn coverage() {
+        let data = test_font();
+        let font = OutlineRasterizer::with_data(&data, 10)
+            .raster_mode(RasterMode::Coverage { bits_per_pixel: 4 })
+            .glyphs('B')
+            .rasterize()
+            .unwrap();
+
+        assert_eq!(font.metadata.bits_per_pixel, 4);
+        assert_eq!(font.glyphs.len(), 1);
+
+        let b = font.glyphs.get('B').unwrap();
+        assert_eq!(b.bounding_box.size, Coord::new(3, 1));
+        assert_eq!(b.bitmap, [0xFF, 0x80]);
+        assert_eq!(b.pixel_value(2, 0, 4), Some(8));
+    }
+
+    #[test]
+    fn scaled() {
+        let data = test_font();
+        let font = OutlineRasterizer::with_data(&data, 20)
+            .glyphs('A')
+            .rasterize()
+            .unwrap();
+
+        let a = font.glyphs.get('A').unwrap();
+        assert_eq!(
+            a.bounding_box,
+            BoundingBox {
+                offset: Coord::new(2, 0),
+                size: Coord::new(8, 8),
+            }
+        );
+        assert_eq!(a.width_horizontal.unwrap().device, Coord::new(12, 0));
+    }
+
+    #[test]
+    fn errors() {
+        let data = test_font();
+
+        for (rasterizer, message) in [
+            (
+                OutlineRasterizer::with_data(&data, 10).glyphs('Z'),
+                "glyph 'Z' (U+005A) is not contained in the font",
+            ),
+            (
+                OutlineRasterizer::with_data(&data, 0),
+                "pixel size must not be zero",
+            ),
+            (
+                OutlineRasterizer::with_data(&data, 10)
+                    .raster_mode(RasterMode::Coverage { bits_per_pixel: 3 }),
+                "unsupported bits per pixel: 3",
+            ),
+            (
+                OutlineRasterizer::with_data(b"STARTFONT 2.1", 10),
+                "couldn't parse TrueType or OpenType font",
+            ),
+        ] {
+            assert_eq!(rasterizer.rasterize().unwrap_err().to_string(), message);
+        }
+    }
+
+    #[test]
+    fn grayscale_fonts_are_rejected_by_the_converter() {
+        let data = test_font();
+        let font = OutlineRasterizer::with_data(&data, 10)
+            .raster_mode(RasterMode::Coverage { bits_per_pixel: 2 })
+            .rasterize()
+            .unwrap();
+
+        let error = crate::FontConverter::with_font(&font, "TEST")
+            .convert_eg_bdf()
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "fonts with 2 bits per pixel aren't supported by this output format"
+        );
+    }
+}
@@ -0,0 +1,842 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::Cursor,
+    path::Path,
+};
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use bdf_parser::{
+    BoundingBox, Coord, Encoding, Font, Glyph, GlyphWidth, Glyphs, Metadata, Metrics,
+};
+use ttf_parser::{Face, Tag};
+
+use super::{
+    glyph_index, glyph_name, parse_face, read_font, scalable_width, set_xlfd_properties,
+    unicode_chars,
+};
+use crate::{glyph_sheet::luma, FileOrData, GlyphRange};
+
+/// Embedded bitmap strike.
+///
+/// A strike is a set of bitmap glyphs for a single pixel size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BitmapStrike {
+    /// Pixels per em.
+    pub pixels_per_em: u32,
+
+    /// Bits per pixel.
+    ///
+    /// Monochrome and grayscale strikes use 1, 2, 4 or 8 bits per pixel. Color
+    /// strikes use 32 bits per pixel.
+    pub bits_per_pixel: u32,
+
+    /// Number of glyphs in the strike.
+    pub glyph_count: usize,
+
+    /// Distance from the baseline to the top of the line.
+    pub ascent: i32,
+
+    /// Distance from the baseline to the bottom of the line.
+    pub descent: i32,
+}
+
+/// Embedded bitmap strike extractor.
+///
+/// Converts the hand-tuned bitmap strikes, which are stored in the `EBLC` and
+/// `EBDT` or `CBLC` and `CBDT` tables of TrueType and OpenType fonts, into BDF
+/// fonts. Color glyphs are converted into monochrome glyphs by using the alpha
+/// channel.
+///
+/// # Examples
+///
+/// ```no_run
+/// use eg_font_converter::StrikeExtractor;
+///
+/// let extractor = StrikeExtractor::with_file("font.ttf");
+///
+/// for strike in extractor.strikes().unwrap() {
+///     println!("{} ppem, {} bpp", strike.pixels_per_em, strike.bits_per_pixel);
+/// }
+///
+/// let font = extractor.extract(12).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct StrikeExtractor<'a> {
+    font: FileOrData<'a>,
+    face_index: u32,
+    glyphs: BTreeSet<char>,
+}
+
+impl<'a> StrikeExtractor<'a> {
+    /// Creates a strike extractor for a TrueType or OpenType file.
+    pub fn with_file<P: AsRef<Path>>(font_file: P) -> Self {
+        Self::new(FileOrData::File(font_file.as_ref().to_owned()))
+    }
+
+    /// Creates a strike extractor for TrueType or OpenType data.
+    pub fn with_data(font: &'a [u8]) -> Self {
+        Self::new(FileOrData::Data(font))
+    }
+
+    fn new(font: FileOrData<'a>) -> Self {
+        Self {
+            font,
+            face_index: 0,
+            glyphs: BTreeSet::new(),
+        }
+    }
+
+    /// Adds glyphs to the extracted font.
+    ///
+    /// If no glyphs are added, all glyphs in the strike which are mapped to a
+    /// Unicode code point are extracted. See [`FontConverter::glyphs`](crate::FontConverter::glyphs)
+    /// for the supported argument types.
+    pub fn glyphs<G: GlyphRange>(mut self, glyphs: G) -> Self {
+        self.glyphs.extend(glyphs.glyphs());
+
+        self
+    }
+
+    /// Sets the index of the font in a font collection.
+    ///
+    /// Defaults to `0`.
+    pub fn face_index(mut self, index: u32) -> Self {
+        self.face_index = index;
+
+        self
+    }
+
+    /// Returns the available strikes.
+    pub fn strikes(&self) -> Result<Vec<BitmapStrike>> {
+        let data = read_font(&self.font)?;
+        let face = parse_face(&data, self.face_index)?;
+
+        Ok(parse_strikes(&face)?
+            .into_iter()
+            .map(|strike| strike.info)
+            .collect())
+    }
+
+    /// Extracts the strike with the given number of pixels per em.
+    pub fn extract(&self, pixels_per_em: u32) -> Result<Font> {
+        let data = read_font(&self.font)?;
+        let face = parse_face(&data, self.face_index)?;
+
+        let strikes = parse_strikes(&face)?;
+        let strike = strikes
+            .iter()
+            .find(|strike| strike.info.pixels_per_em == pixels_per_em)
+            .ok_or_else(|| {
+                anyhow!("font doesn't contain a strike with {pixels_per_em} pixels per em")
+            })?;
+
+        let chars = if self.glyphs.is_empty() {
+            unicode_chars(&face)
+                .into_iter()
+                .filter(|c| {
+                    face.glyph_index(*c)
+                        .is_some_and(|glyph_id| strike.glyphs.contains_key(&glyph_id.0))
+                })
+                .collect()
+        } else {
+            self.glyphs.clone()
+        };
+
+        let glyphs = chars
+            .into_iter()
+            .map(|c| {
+                let glyph_id = glyph_index(&face, c)?;
+                let image = strike.glyphs.get(&glyph_id.0).ok_or_else(|| {
+                    anyhow!(
+                        "glyph '{}' (U+{:04X}) is not contained in the {} ppem strike",
+                        c,
+                        u32::from(c),
+                        pixels_per_em
+                    )
+                })?;
+
+                let (metrics, bitmap) =
+                    image.decode(strike.info.bits_per_pixel).with_context(|| {
+                        format!("couldn't decode bitmap for glyph ID {}", glyph_id.0)
+                    })?;
+
+                let bounding_box = if bitmap.is_empty() {
+                    BoundingBox::default()
+                } else {
+                    BoundingBox {
+                        offset: Coord::new(
+                            i32::from(metrics.bearing_x),
+                            i32::from(metrics.bearing_y) - i32::from(metrics.height),
+                        ),
+                        size: Coord::new(i32::from(metrics.width), i32::from(metrics.height)),
+                    }
+                };
+
+                Ok(Glyph {
+                    name: glyph_name(&face, glyph_id, c),
+                    encoding: Encoding::Standard(c.into()),
+                    width_horizontal: Some(GlyphWidth {
+                        scalable: Coord::new(scalable_width(&face, glyph_id), 0),
+                        device: Coord::new(i32::from(metrics.advance), 0),
+                    }),
+                    width_vertical: None,
+                    bounding_box,
+                    origin_offset: None,
+                    bitmap,
+                })
+            })
+            .collect::<Result<Glyphs>>()?;
+
+        let metrics = Metrics {
+            ascent: strike.info.ascent.max(0) as u32,
+            descent: strike.info.descent.max(0) as u32,
+        };
+
+        let mut metadata = Metadata::for_imported_font(&glyphs, &metrics, pixels_per_em, true);
+        metadata.bits_per_pixel = output_bits_per_pixel(strike.info.bits_per_pixel);
+        set_xlfd_properties(&mut metadata, &face);
+
+        Ok(Font {
+            metadata,
+            glyphs,
+            metrics,
+        })
+    }
+}
+
+/// Returns the bits per pixel of the converted glyphs.
+///
+/// Color glyphs are converted to monochrome glyphs.
+fn output_bits_per_pixel(bits_per_pixel: u32) -> u32 {
+    if bits_per_pixel == 32 {
+        1
+    } else {
+        bits_per_pixel
+    }
+}
+
+#[derive(Debug)]
+struct Strike<'a> {
+    info: BitmapStrike,
+    glyphs: BTreeMap<u16, GlyphImage<'a>>,
+}
+
+/// Glyph metrics in the `smallGlyphMetrics` or `bigGlyphMetrics` format.
+///
+/// Only the horizontal metrics are used.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct GlyphMetrics {
+    height: u8,
+    width: u8,
+    bearing_x: i8,
+    bearing_y: i8,
+    advance: u8,
+}
+
+impl GlyphMetrics {
+    fn parse(data: &[u8], offset: usize) -> Result<Self> {
+        let [height, width, bearing_x, bearing_y, advance] = read(data, offset)?;
+
+        Ok(Self {
+            height,
+            width,
+            bearing_x: bearing_x as i8,
+            bearing_y: bearing_y as i8,
+            advance,
+        })
+    }
+}
+
+/// Glyph image in the `EBDT` or `CBDT` table.
+#[derive(Debug)]
+struct GlyphImage<'a> {
+    format: u16,
+    data: &'a [u8],
+    /// Metrics from the index subtable, which are used by image formats 5 and 19.
+    metrics: Option<GlyphMetrics>,
+}
+
+impl GlyphImage<'_> {
+    /// Decodes the glyph image into a bitmap with rows padded to whole bytes.
+    fn decode(&self, bits_per_pixel: u32) -> Result<(GlyphMetrics, Vec<u8>)> {
+        let (metrics, offset) = match self.format {
+            1 | 2 | 17 => (GlyphMetrics::parse(self.data, 0)?, 5),
+            6 | 7 | 18 => (GlyphMetrics::parse(self.data, 0)?, 8),
+            5 | 19 => (self.metrics.context("missing shared glyph metrics")?, 0),
+            8 | 9 => bail!("composite glyphs aren't supported"),
+            format => bail!("unsupported image format: {format}"),
+        };
+        let data = self.data.get(offset..).unwrap_or_default();
+
+        let width = usize::from(metrics.width);
+        let height = usize::from(metrics.height);
+        let values = match self.format {
+            17..=19 => {
+                let length = u32::from_be_bytes(read(data, 0)?) as usize;
+                let png = data.get(4..4 + length).context("unexpected end of data")?;
+
+                decode_png(png, width, height)?
+            }
+            _ => {
+                let bits_per_pixel = bits_per_pixel as usize;
+                let bits_per_row = if [1, 6].contains(&self.format) {
+                    (width * bits_per_pixel).next_multiple_of(8)
+                } else {
+                    width * bits_per_pixel
+                };
+                ensure!(
+                    data.len() * 8 >= bits_per_row * height,
+                    "unexpected end of data"
+                );
+
+                let mut values = Vec::with_capacity(width * height);
+                for y in 0..height {
+                    for x in 0..width {
+                        let bit_offset = y * bits_per_row + x * bits_per_pixel;
+                        values.push(if bits_per_pixel == 32 {
+                            // Premultiplied BGRA
+                            u8::from(data[bit_offset / 8 + 3] >= 128)
+                        } else {
+                            let shift = 8 - bits_per_pixel - bit_offset % 8;
+                            (data[bit_offset / 8] >> shift) & ((1 << bits_per_pixel) - 1)
+                        });
+                    }
+                }
+                values
+            }
+        };
+
+        if values.iter().all(|value| *value == 0) {
+            return Ok((metrics, Vec::new()));
+        }
+
+        let bits_per_pixel = output_bits_per_pixel(bits_per_pixel) as usize;
+        let bytes_per_row = (width * bits_per_pixel).div_ceil(8);
+        let mut bitmap = vec![0; bytes_per_row * height];
+        for (index, value) in values.into_iter().enumerate() {
+            let (x, y) = (index % width, index / width);
+            let bit_offset = x * bits_per_pixel;
+            let shift = 8 - bits_per_pixel - bit_offset % 8;
+            bitmap[bit_offset / 8 + y * bytes_per_row] |= value << shift;
+        }
+
+        Ok((metrics, bitmap))
+    }
+}
+
+/// Decodes a PNG glyph image into monochrome pixel values.
+fn decode_png(data: &[u8], width: usize, height: usize) -> Result<Vec<u8>> {
+    let mut decoder = png::Decoder::new(Cursor::new(data));
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+
+    let mut reader = decoder.read_info().context("couldn't decode PNG image")?;
+    let mut buffer = vec![
+        0;
+        reader
+            .output_buffer_size()
+            .context("PNG image is too large")?
+    ];
+    let info = reader
+        .next_frame(&mut buffer)
+        .context("couldn't decode PNG image")?;
+    ensure!(
+        (info.width as usize, info.height as usize) == (width, height),
+        "PNG image size doesn't match the glyph metrics"
+    );
+
+    let samples = info.color_type.samples();
+    Ok(buffer[..info.buffer_size()]
+        .chunks_exact(samples)
+        .map(|pixel| {
+            let ink = match pixel {
+                [luma] => *luma < 128,
+                [_, alpha] | [_, _, _, alpha] => *alpha >= 128,
+                [r, g, b] => luma(*r, *g, *b) < 128,
+                _ => unreachable!(),
+            };
+            u8::from(ink)
+        })
+        .collect())
+}
+
+/// Parses the strikes in the `EBLC`/`EBDT` and `CBLC`/`CBDT` tables.
+fn parse_strikes<'a>(face: &Face<'a>) -> Result<Vec<Strike<'a>>> {
+    let mut strikes = Vec::new();
+
+    for (location_tag, data_tag) in [(b"EBLC", b"EBDT"), (b"CBLC", b"CBDT")] {
+        let table = |tag: &[u8; 4]| face.raw_face().table(Tag::from_bytes(tag));
+        let (Some(location), Some(data)) = (table(location_tag), table(data_tag)) else {
+            continue;
+        };
+
+        let context = || {
+            format!(
+                "invalid {} table",
+                String::from_utf8_lossy(location_tag.as_slice())
+            )
+        };
+        strikes.extend(parse_location_table(location, data).with_context(context)?);
+    }
+
+    Ok(strikes)
+}
+
+/// Parses the strikes in an `EBLC` or `CBLC` table.
+fn parse_location_table<'a>(location: &'a [u8], data: &'a [u8]) -> Result<Vec<Strike<'a>>> {
+    let num_sizes = u32::from_be_bytes(read(location, 4)?) as usize;
+
+    (0..num_sizes)
+        .map(|index| {
+            // BitmapSize record
+            let record = 8 + index * 48;
+            let subtable_array = u32::from_be_bytes(read(location, record)?) as usize;
+            let num_subtables = u32::from_be_bytes(read(location, record + 8)?) as usize;
+            let [ascender, descender] = read(location, record + 16)?;
+            let [ppem_x, _ppem_y, bit_depth] = read(location, record + 44)?;
+
+            ensure!(
+                [1, 2, 4, 8, 32].contains(&bit_depth),
+                "unsupported bit depth: {bit_depth}"
+            );
+
+            let mut glyphs = BTreeMap::new();
+            for subtable in 0..num_subtables {
+                let entry = subtable_array + subtable * 8;
+                let first = u16::from_be_bytes(read(location, entry)?);
+                let last = u16::from_be_bytes(read(location, entry + 2)?);
+                let offset = u32::from_be_bytes(read(location, entry + 4)?) as usize;
+                ensure!(first <= last, "invalid glyph range");
+
+                parse_index_subtable(
+                    location,
+                    data,
+                    subtable_array + offset,
+                    first..=last,
+                    &mut glyphs,
+                )?;
+            }
+
+            Ok(Strike {
+                info: BitmapStrike {
+                    pixels_per_em: u32::from(ppem_x),
+                    bits_per_pixel: u32::from(bit_depth),
+                    glyph_count: glyphs.len(),
+                    ascent: i32::from(ascender as i8),
+                    descent: -i32::from(descender as i8),
+                },
+                glyphs,
+            })
+        })
+        .collect()
+}
+
+/// Parses an index subtable and adds the contained glyphs.
+fn parse_index_subtable<'a>(
+    location: &[u8],
+    data: &'a [u8],
+    offset: usize,
+    glyph_ids: std::ops::RangeInclusive<u16>,
+    glyphs: &mut BTreeMap<u16, GlyphImage<'a>>,
+) -> Result<()> {
+    let index_format = u16::from_be_bytes(read(location, offset)?);
+    let format = u16::from_be_bytes(read(location, offset + 2)?);
+    let image_data_offset = u32::from_be_bytes(read(location, offset + 4)?) as usize;
+    let offset = offset + 8;
+
+    let mut add_glyph = |glyph_id: u16, range: (usize, usize), metrics| -> Result<()> {
+        // Glyphs without image data aren't contained in the strike.
+        if range.0 < range.1 {
+            let data = data
+                .get(image_data_offset + range.0..image_data_offset + range.1)
+                .context("glyph image is outside of the data table")?;
+
+            glyphs.insert(
+                glyph_id,
+                GlyphImage {
+                    format,
+                    data,
+                    metrics,
+                },
+            );
+        }
+
+        Ok(())
+    };
+
+    match index_format {
+        1 | 3 => {
+            let offset_size = if index_format == 1 { 4 } else { 2 };
+            let read_offset = |index: usize| -> Result<usize> {
+                let position = offset + index * offset_size;
+                Ok(if index_format == 1 {
+                    u32::from_be_bytes(read(location, position)?) as usize
+                } else {
+                    usize::from(u16::from_be_bytes(read(location, position)?))
+                })
+            };
+
+            for (index, glyph_id) in glyph_ids.enumerate() {
+                add_glyph(
+                    glyph_id,
+                    (read_offset(index)?, read_offset(index + 1)?),
+                    None,
+                )?;
+            }
+        }
+        2 => {
+            let image_size = u32::from_be_bytes(read(location, offset)?) as usize;
+            let metrics = GlyphMetrics::parse(location, offset + 4)?;
+
+            for (index, glyph_id) in glyph_ids.enumerate() {
+                let start = index * image_size;
+                add_glyph(glyph_id, (start, start + image_size), Some(metrics))?;
+            }
+        }
+        4 => {
+            let num_glyphs = u32::from_be_bytes(read(location, offset)?) as usize;
+            let pair = |index: usize| -> Result<(u16, usize)> {
+                let [glyph_id, offset] = read::<4>(location, offset + 4 + index * 4)
+                    .map(|pair| [[pair[0], pair[1]], [pair[2], pair[3]]])?;
+                Ok((
+                    u16::from_be_bytes(glyph_id),
+                    usize::from(u16::from_be_bytes(offset)),
+                ))
+            };
+
+            for index in 0..num_glyphs {
+                let (glyph_id, start) = pair(index)?;
+                let (_, end) = pair(index + 1)?;
+                add_glyph(glyph_id, (start, end), None)?;
+            }
+        }
+        5 => {
+            let image_size = u32::from_be_bytes(read(location, offset)?) as usize;
+            let metrics = GlyphMetrics::parse(location, offset + 4)?;
+            let num_glyphs = u32::from_be_bytes(read(location, offset + 12)?) as usize;
+
+            for index in 0..num_glyphs {
+                let glyph_id = u16::from_be_bytes(read(location, offset + 16 + index * 2)?);
+                let start = index * image_size;
+                add_glyph(glyph_id, (start, start + image_size), Some(metrics))?;
+            }
+        }
+        _ => bail!("unsupported index subtable format: {index_format}"),
+    }
+
+    Ok(())
+}
+
+/// Reads a fixed number of bytes.
+fn read<const N: usize>(data: &[u8], offset: usize) -> Result<[u8; N]> {
+    data.get(offset..)
+        .and_then(|data| data.get(..N))
+        .and_then(|bytes| bytes.try_into().ok())
+        .context("unexpected end of table")
+}
+
+#[cfg(test)]
+mod tests {
+    use bdf_parser::Property;
+
+    use super::*;
+    use crate::truetype::tests::{sfnt, test_tables, u16s};
+
+    struct TestStrike {
+        pixels_per_em: u8,
+        bit_depth: u8,
+        ascender: i8,
+        descender: i8,
+        /// First and last glyph ID and index subtable.
+        subtables: Vec<(u16, u16, Vec<u8>)>,
+    }
+
+    /// Builds an `EBLC` or `CBLC` table.
+    fn location_table(version: u16, strikes: &[TestStrike]) -> Vec<u8> {
+        let mut records = u16s(&[version, 0]);
+        records.extend((strikes.len() as u32).to_be_bytes());
+
+        let mut subtables = Vec::new();
+        let mut offset = records.len() + strikes.len() * 48;
+        for strike in strikes {
+            let mut array = Vec::new();
+            let mut index_subtables: Vec<u8> = Vec::new();
+            for (first, last, subtable) in &strike.subtables {
+                array.extend(u16s(&[*first, *last]));
+                let subtable_offset = strike.subtables.len() * 8 + index_subtables.len();
+                array.extend((subtable_offset as u32).to_be_bytes());
+                index_subtables.extend(subtable);
+            }
+            let size = array.len() + index_subtables.len();
+
+            records.extend((offset as u32).to_be_bytes());
+            records.extend((size as u32).to_be_bytes());
+            records.extend((strike.subtables.len() as u32).to_be_bytes());
+            records.extend([0; 4]);
+            let mut line_metrics = [0; 12];
+            line_metrics[0] = strike.ascender as u8;
+            line_metrics[1] = strike.descender as u8;
+            records.extend(line_metrics);
+            records.extend([0; 12]);
+            let first = strike.subtables.iter().map(|s| s.0).min().unwrap();
+            let last = strike.subtables.iter().map(|s| s.1).max().unwrap();
+            records.extend(u16s(&[first, last]));
+            records.extend([
+                strike.pixels_per_em,
+                strike.pixels_per_em,
+                strike.bit_depth,
+                0x01,
+            ]);
+
+            subtables.extend(array);
+            subtables.extend(index_subtables);
+            offset += size;
+        }
+
+        records.extend(subtables);
+        records
+    }
+
+    fn index_subtable_header(index_format: u16, image_format: u16, offset: u32) -> Vec<u8> {
+        let mut header = u16s(&[index_format, image_format]);
+        header.extend(offset.to_be_bytes());
+        header
+    }
+
+    fn test_png() -> Vec<u8> {
+        let mut png = Vec::new();
+        let mut encoder = png::Encoder::new(&mut png, 2, 2);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder
+            .write_header()
+            .unwrap()
+            .write_image_data(&[
+                255, 0, 0, 255, 255, 0, 0, 0, //
+                255, 0, 0, 0, 255, 0, 0, 255, //
+            ])
+            .unwrap();
+        png
+    }
+
+    /// Builds the test font with a 10 ppem 1 bpp strike, a 20 ppem 2 bpp strike
+    /// and a 12 ppem color strike.
+    fn test_font() -> Vec<u8> {
+        let mut ebdt = u16s(&[2, 0]);
+        // 10 ppem: `A`, `B` and space with small metrics and byte aligned data.
+        ebdt.extend([4, 4, 1, 4, 6, 0xF0, 0xF0, 0xF0, 0xF0]);
+        ebdt.extend([1, 3, 0, 1, 3, 0xE0]);
+        ebdt.extend([0, 0, 0, 0, 3]);
+        // 20 ppem: `A` and `B` with shared metrics and bit aligned data.
+        ebdt.extend([0b1110_0100, 0b1111_0000]);
+
+        let mut format_1 = index_subtable_header(1, 1, 4);
+        for offset in [0u32, 9, 15, 20] {
+            format_1.extend(offset.to_be_bytes());
+        }
+
+        let mut format_2 = index_subtable_header(2, 5, 24);
+        format_2.extend(1u32.to_be_bytes());
+        format_2.extend([2, 2, 0, 2, 3, 0, 0, 0]);
+
+        let eblc = location_table(
+            2,
+            &[
+                TestStrike {
+                    pixels_per_em: 10,
+                    bit_depth: 1,
+                    ascender: 8,
+                    descender: -2,
+                    subtables: vec![(1, 3, format_1)],
+                },
+                TestStrike {
+                    pixels_per_em: 20,
+                    bit_depth: 2,
+                    ascender: 16,
+                    descender: -4,
+                    subtables: vec![(1, 2, format_2)],
+                },
+            ],
+        );
+
+        let png = test_png();
+        let mut cbdt = u16s(&[3, 0]);
+        cbdt.extend([2, 2, 0, 2, 3]);
+        cbdt.extend((png.len() as u32).to_be_bytes());
+        cbdt.extend(&png);
+
+        let mut format_3 = index_subtable_header(3, 17, 4);
+        format_3.extend(u16s(&[0, 9 + png.len() as u16]));
+
+        let cblc = location_table(
+            3,
+            &[TestStrike {
+                pixels_per_em: 12,
+                bit_depth: 32,
+                ascender: 10,
+                descender: -2,
+                subtables: vec![(1, 1, format_3)],
+            }],
+        );
+
+        let mut tables = test_tables();
+        tables.extend([
+            (*b"EBDT", ebdt),
+            (*b"EBLC", eblc),
+            (*b"CBDT", cbdt),
+            (*b"CBLC", cblc),
+        ]);
+        sfnt(tables)
+    }
+
+    #[test]
+    fn strikes() {
+        let data = test_font();
+
+        assert_eq!(
+            StrikeExtractor::with_data(&data).strikes().unwrap(),
+            [
+                BitmapStrike {
+                    pixels_per_em: 10,
+                    bits_per_pixel: 1,
+                    glyph_count: 3,
+                    ascent: 8,
+                    descent: 2,
+                },
+                BitmapStrike {
+                    pixels_per_em: 20,
+                    bits_per_pixel: 2,
+                    glyph_count: 2,
+                    ascent: 16,
+                    descent: 4,
+                },
+                BitmapStrike {
+                    pixels_per_em: 12,
+                    bits_per_pixel: 32,
+                    glyph_count: 1,
+                    ascent: 10,
+                    descent: 2,
+                },
+            ]
+        );
+
+        let outline_font = sfnt(test_tables());
+        assert_eq!(
+            StrikeExtractor::with_data(&outline_font).strikes().unwrap(),
+            []
+        );
+    }
+
+    #[test]
+    fn monochrome_strike() {
+        let data = test_font();
+        let font = StrikeExtractor::with_data(&data).extract(10).unwrap();
+
+        assert_eq!(font.glyphs.len(), 3);
+        assert_eq!(font.metadata.bits_per_pixel, 1);
+        assert_eq!(
+            font.metrics,
+            Metrics {
+                ascent: 8,
+                descent: 2
+            }
+        );
+        assert_eq!(
+            font.metadata.name,
+            "-unknown-Test Sans-Medium-R-Normal--10-100-72-72-P-40-ISO10646-1"
+        );
+        assert_eq!(
+            font.metadata
+                .properties
+                .try_get::<i32>(Property::FontAscent),
+            Ok(Some(8))
+        );
+
+        let a = font.glyphs.get('A').unwrap();
+        assert_eq!(
+            a.bounding_box,
+            BoundingBox {
+                offset: Coord::new(1, 0),
+                size: Coord::new(4, 4),
+            }
+        );
+        assert_eq!(
+            a.width_horizontal,
+            Some(GlyphWidth {
+                scalable: Coord::new(600, 0),
+                device: Coord::new(6, 0),
+            })
+        );
+        assert_eq!(a.bitmap, [0xF0; 4]);
+
+        let b = font.glyphs.get('B').unwrap();
+        assert_eq!(
+            b.bounding_box,
+            BoundingBox {
+                offset: Coord::new(0, 0),
+                size: Coord::new(3, 1),
+            }
+        );
+        assert_eq!(b.bitmap, [0xE0]);
+
+        let space = font.glyphs.get(' ').unwrap();
+        assert_eq!(space.bounding_box, BoundingBox::default());
+        assert_eq!(space.width_horizontal.unwrap().device, Coord::new(3, 0));
+    }
+
+    #[test]
+    fn grayscale_strike() {
+        let data = test_font();
+        let font = StrikeExtractor::with_data(&data)
+            .glyphs('A')
+            .extract(20)
+            .unwrap();
+
+        assert_eq!(font.glyphs.len(), 1);
+        assert_eq!(font.metadata.bits_per_pixel, 2);
+
+        let a = font.glyphs.get('A').unwrap();
+        assert_eq!(
+            a.bounding_box,
+            BoundingBox {
+                offset: Coord::new(0, 0),
+                size: Coord::new(2, 2),
+            }
+        );
+        assert_eq!(a.bitmap, [0b1110_0000, 0b0100_0000]);
+        assert_eq!(a.width_horizontal.unwrap().device, Coord::new(3, 0));
+    }
+
+    #[test]
+    fn color_strike() {
+        let data = test_font();
+        let font = StrikeExtractor::with_data(&data).extract(12).unwrap();
+
+        assert_eq!(font.glyphs.len(), 1);
+        assert_eq!(font.metadata.bits_per_pixel, 1);
+
+        let a = font.glyphs.get('A').unwrap();
+        assert_eq!(a.bounding_box.size, Coord::new(2, 2));
+        assert_eq!(a.bitmap, [0x80, 0x40]);
+    }
+
+    #[test]
+    fn errors() {
+        let data = test_font();
+
+        for (result, message) in [
+            (
+                StrikeExtractor::with_data(&data).extract(11),
+                "font doesn't contain a strike with 11 pixels per em",
+            ),
+            (
+                StrikeExtractor::with_data(&data).glyphs('B').extract(12),
+                "glyph 'B' (U+0042) is not contained in the 12 ppem strike",
+            ),
+            (
+                StrikeExtractor::with_data(&data).glyphs('Z').extract(10),
+                "glyph 'Z' (U+005A) is not contained in the font",
+            ),
+        ] {
+            assert_eq!(result.unwrap_err().to_string(), message);
+        }
+    }
+}
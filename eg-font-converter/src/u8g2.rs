@@ -0,0 +1,571 @@
+use std::{fmt::Write, fs, io, path::Path};
+
+use anyhow::{anyhow, bail, Context, Result};
+use bdf_parser::{Encoding, Glyph};
+
+use crate::ConvertedFont;
+
+/// Size of the u8g2 font header.
+const HEADER_SIZE: usize = 23;
+
+/// Maximum number of glyphs in each block of the Unicode jump table.
+const UNICODE_BLOCK_SIZE: usize = 100;
+
+/// Maximum line length of the generated string literal.
+const LINE_LENGTH: usize = 100;
+
+/// Font conversion output for the [u8g2] library.
+///
+/// The output is C source code, which contains a `uint8_t` array with the
+/// font in the compressed u8g2 font format. Glyphs with encodings below 256
+/// are stored in the 8 bit glyph list and all other glyphs in the Unicode
+/// glyph list. Glyphs with encodings above U+FFFF and glyphs with non
+/// standard encodings aren't supported by u8g2 and are skipped.
+///
+/// [u8g2]: https://github.com/olikraus/u8g2
+#[derive(Debug)]
+pub struct U8g2Output {
+    font: ConvertedFont,
+    data: Vec<u8>,
+}
+
+impl U8g2Output {
+    pub(crate) fn new(font: ConvertedFont) -> Result<Self> {
+        font.ensure_monochrome()?;
+
+        let mut glyphs = font
+            .glyphs
+            .iter()
+            .filter_map(|glyph| match glyph.encoding {
+                Encoding::Standard(code_point) if code_point <= 0xFFFF => Some((code_point, glyph)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        glyphs.sort_by_key(|(code_point, _)| *code_point);
+        glyphs.dedup_by_key(|(code_point, _)| *code_point);
+
+        if glyphs.is_empty() {
+            bail!("font doesn't contain any glyphs which are supported by u8g2");
+        }
+
+        let data = encode_font(&font, &glyphs)?;
+
+        Ok(Self { font, data })
+    }
+
+    /// Returns the generated C source code.
+    ///
+    /// The array is declared in the same way as the fonts that are included
+    /// in u8g2.
+    pub fn source(&self) -> String {
+        let name = &self.font.name;
+        let mut output = String::new();
+
+        for comment in &self.font.comments {
+            writeln!(output, "// {comment}").unwrap();
+        }
+        if !self.font.comments.is_empty() {
+            output.push('\n');
+        }
+
+        // The array size includes the terminating null byte of the string literal.
+        writeln!(
+            output,
+            "const uint8_t {name}[{}] U8G2_FONT_SECTION(\"{name}\") = ",
+            self.data.len() + 1
+        )
+        .unwrap();
+
+        let mut line = String::new();
+        for (index, byte) in self.data.iter().enumerate() {
+            let next = self.data.get(index + 1).copied();
+
+            let escaped = match byte {
+                b'"' | b'\\' | b'?' => format!("\\{}", *byte as char),
+                0x20..=0x7E => (*byte as char).to_string(),
+                // Octal escapes with less than 3 digits can't be followed by a digit.
+                _ if next.is_some_and(|next| next.is_ascii_digit()) => format!("\\{byte:03o}"),
+                _ => format!("\\{byte:o}"),
+            };
+
+            if line.len() + escaped.len() > LINE_LENGTH {
+                writeln!(output, "  \"{line}\"").unwrap();
+                line.clear();
+            }
+            line.push_str(&escaped);
+        }
+        writeln!(output, "  \"{line}\";").unwrap();
+
+        output
+    }
+
+    /// Returns the font data.
+    ///
+    /// The data doesn't include the terminating null byte, which is added by
+    /// the string literal in the generated C source code.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Saves the C source code to the given directory.
+    ///
+    /// The file name is the file stem with a `.c` extension.
+    pub fn save<P: AsRef<Path>>(&self, output_directory: P) -> io::Result<()> {
+        let path = output_directory
+            .as_ref()
+            .join(&self.font.file_stem)
+            .with_extension("c");
+
+        fs::write(path, self.source())
+    }
+}
+
+/// Number of bits used for the values in the glyph headers.
+#[derive(Debug, Clone, Copy)]
+struct BitWidths {
+    bits_per_0: u32,
+    bits_per_1: u32,
+    char_width: u32,
+    char_height: u32,
+    char_x: u32,
+    char_y: u32,
+    delta_x: u32,
+}
+
+fn encode_font(font: &ConvertedFont, glyphs: &[(u32, &Glyph)]) -> Result<Vec<u8>> {
+    let mut widths = BitWidths {
+        bits_per_0: 0,
+        bits_per_1: 0,
+        char_width: unsigned_bits(glyphs.iter().map(|(_, glyph)| glyph.bounding_box.size.x))
+            .context("glyph width is out of range")?,
+        char_height: unsigned_bits(glyphs.iter().map(|(_, glyph)| glyph.bounding_box.size.y))
+            .context("glyph height is out of range")?,
+        char_x: signed_bits(glyphs.iter().map(|(_, glyph)| glyph.bounding_box.offset.x))
+            .context("glyph X offset is out of range")?,
+        char_y: signed_bits(glyphs.iter().map(|(_, glyph)| glyph.bounding_box.offset.y))
+            .context("glyph Y offset is out of range")?,
+        delta_x: signed_bits(glyphs.iter().map(|(_, glyph)| device_width(glyph)))
+            .context("glyph device width is out of range")?,
+    };
+
+    // Use the run length bit widths which result in the smallest font.
+    let (bits_per_0, bits_per_1) = (2..=8)
+        .flat_map(|bits_per_0| (1..=8).map(move |bits_per_1| (bits_per_0, bits_per_1)))
+        .min_by_key(|&(bits_per_0, bits_per_1)| {
+            let widths = BitWidths {
+                bits_per_0,
+                bits_per_1,
+                ..widths
+            };
+            glyphs
+                .iter()
+                .map(|(_, glyph)| encode_glyph(glyph, widths).len())
+                .sum::<usize>()
+        })
+        .unwrap();
+    widths.bits_per_0 = bits_per_0;
+    widths.bits_per_1 = bits_per_1;
+
+    let mut data = vec![0; HEADER_SIZE];
+    let mut start_pos_upper_a = None;
+    let mut start_pos_lower_a = None;
+
+    // Glyphs with 8 bit encodings: encoding, size, data
+    let (glyphs_8bit, glyphs_unicode) =
+        glyphs.split_at(glyphs.partition_point(|(code_point, _)| *code_point < 0x100));
+
+    for (code_point, glyph) in glyphs_8bit {
+        if *code_point >= u32::from(b'A') && start_pos_upper_a.is_none() {
+            start_pos_upper_a = Some(data.len() - HEADER_SIZE);
+        }
+        if *code_point >= u32::from(b'a') && start_pos_lower_a.is_none() {
+            start_pos_lower_a = Some(data.len() - HEADER_SIZE);
+        }
+
+        let glyph_data = encode_glyph(glyph, widths);
+        data.push(*code_point as u8);
+        data.push(glyph_size(glyph, 2, &glyph_data)?);
+        data.extend(glyph_data);
+    }
+    let end_8bit = data.len() - HEADER_SIZE;
+    data.extend([0, 0]);
+
+    // Glyphs with 16 bit encodings, which are preceded by a jump table.
+    // Each entry in the jump table contains the offset to the start of the
+    // next block of glyphs and the last encoding in the block.
+    let start_pos_unicode = data.len() - HEADER_SIZE;
+
+    let blocks = glyphs_unicode
+        .chunks(UNICODE_BLOCK_SIZE)
+        .map(|block| {
+            let mut block_data = Vec::new();
+            for (code_point, glyph) in block {
+                let glyph_data = encode_glyph(glyph, widths);
+                block_data.extend((*code_point as u16).to_be_bytes());
+                block_data.push(glyph_size(glyph, 3, &glyph_data)?);
+                block_data.extend(glyph_data);
+            }
+            Ok((block.last().unwrap().0, block_data))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    // The last entry in the jump table uses 0xFFFF as the encoding.
+    let jump_table_size = (blocks.len().max(1) * 4) as u16;
+    let mut offset = jump_table_size;
+    for (index, (code_point, block_data)) in blocks.iter().enumerate() {
+        let code_point = if index == blocks.len() - 1 {
+            0xFFFF
+        } else {
+            *code_point as u16
+        };
+        data.extend(offset.to_be_bytes());
+        data.extend(code_point.to_be_bytes());
+        offset = block_data.len() as u16;
+    }
+    if blocks.is_empty() {
+        data.extend(jump_table_size.to_be_bytes());
+        data.extend(0xFFFFu16.to_be_bytes());
+    }
+    for (_, block_data) in blocks {
+        data.extend(block_data);
+    }
+    data.extend([0, 0]);
+
+    let start_pos_unicode =
+        u16::try_from(start_pos_unicode).context("8 bit glyph list exceeds the maximum size")?;
+
+    // Font bounding box, which also includes the ascent and descent.
+    let metrics = &font.bdf.metrics;
+    let left = glyphs
+        .iter()
+        .map(|(_, glyph)| glyph.bounding_box.offset.x)
+        .min()
+        .unwrap_or_default();
+    let right = glyphs
+        .iter()
+        .map(|(_, glyph)| glyph.bounding_box.offset.x + glyph.bounding_box.size.x)
+        .max()
+        .unwrap_or_default();
+    let font_top = glyphs
+        .iter()
+        .map(|(_, glyph)| top(glyph))
+        .max()
+        .unwrap_or_default()
+        .max(metrics.ascent as i32);
+    let font_bottom = glyphs
+        .iter()
+        .map(|(_, glyph)| glyph.bounding_box.offset.y)
+        .min()
+        .unwrap_or_default()
+        .min(-(metrics.descent as i32));
+
+    let byte = |value: i32, field: &str| -> Result<u8> {
+        u8::try_from(value).map_err(|_| anyhow!("{field} is out of range"))
+    };
+    let signed_byte = |value: i32, field: &str| -> Result<u8> {
+        i8::try_from(value)
+            .map(|value| value as u8)
+            .map_err(|_| anyhow!("{field} is out of range"))
+    };
+    let glyph = |c: char| {
+        glyphs
+            .iter()
+            .find(|(code_point, _)| *code_point == c as u32)
+            .map(|(_, glyph)| *glyph)
+    };
+
+    let header = [
+        glyphs.len().min(255) as u8,
+        // Bounding box mode 0: each glyph uses its own bounding box.
+        0,
+        widths.bits_per_0 as u8,
+        widths.bits_per_1 as u8,
+        widths.char_width as u8,
+        widths.char_height as u8,
+        widths.char_x as u8,
+        widths.char_y as u8,
+        widths.delta_x as u8,
+        byte(right - left, "font bounding box width")?,
+        byte(font_top - font_bottom, "font bounding box height")?,
+        signed_byte(left, "font bounding box X offset")?,
+        signed_byte(font_bottom, "font bounding box Y offset")?,
+        signed_byte(glyph('A').map(top).unwrap_or_default(), "ascent of 'A'")?,
+        signed_byte(
+            glyph('g').map_or(0, |glyph| glyph.bounding_box.offset.y),
+            "descent of 'g'",
+        )?,
+        signed_byte(glyph('(').map(top).unwrap_or(font_top), "ascent of '('")?,
+        signed_byte(
+            glyph('(').map_or(font_bottom, |glyph| glyph.bounding_box.offset.y),
+            "descent of '('",
+        )?,
+    ];
+    data[..header.len()].copy_from_slice(&header);
+
+    // The start positions of 'A' and 'a' point to the end of the 8 bit glyph
+    // list if the font doesn't contain any glyphs after them.
+    let start_pos_upper_a = start_pos_upper_a.unwrap_or(end_8bit);
+    let start_pos_lower_a = start_pos_lower_a.unwrap_or(end_8bit);
+    for (index, position) in [start_pos_upper_a, start_pos_lower_a]
+        .into_iter()
+        .enumerate()
+    {
+        let position =
+            u16::try_from(position).context("8 bit glyph list exceeds the maximum size")?;
+        data[17 + index * 2..19 + index * 2].copy_from_slice(&position.to_be_bytes());
+    }
+    data[21..23].copy_from_slice(&start_pos_unicode.to_be_bytes());
+
+    Ok(data)
+}
+
+/// Returns the size of a glyph, including the glyph header.
+fn glyph_size(glyph: &Glyph, header_size: usize, data: &[u8]) -> Result<u8> {
+    u8::try_from(header_size + data.len())
+        .map_err(|_| anyhow!("glyph \"{}\" is too large for the u8g2 format", glyph.name))
+}
+
+fn device_width(glyph: &Glyph) -> i32 {
+    glyph
+        .width_horizontal
+        .map(|width| width.device.x)
+        .unwrap_or_default()
+}
+
+fn top(glyph: &Glyph) -> i32 {
+    glyph.bounding_box.offset.y + glyph.bounding_box.size.y
+}
+
+/// Returns the number of bits required to store the unsigned values.
+fn unsigned_bits(values: impl Iterator<Item = i32>) -> Option<u32> {
+    let mut max = 0;
+    for value in values {
+        max = max.max(u32::try_from(value).ok()?);
+    }
+
+    let bits = u32::BITS - max.leading_zeros();
+    (bits <= 8).then_some(bits)
+}
+
+/// Returns the number of bits required to store the signed values.
+///
+/// Signed values are stored with an offset of `2^(bits - 1)`, which requires
+/// at least one bit.
+fn signed_bits(values: impl Iterator<Item = i32>) -> Option<u32> {
+    let (min, max) = values.fold((0, 0), |(min, max), value| (value.min(min), value.max(max)));
+
+    (1..=8).find(|bits| min >= -(1 << (bits - 1)) && max < 1 << (bits - 1))
+}
+
+/// Bit writer for the glyph data.
+///
+/// Values are stored with the least significant bit first.
+#[derive(Debug, Default)]
+struct BitWriter {
+    data: Vec<u8>,
+    position: usize,
+}
+
+impl BitWriter {
+    fn unsigned(&mut self, value: u32, bits: u32) {
+        for bit in 0..bits {
+            if self.position.is_multiple_of(8) {
+                self.data.push(0);
+            }
+            if value & (1 << bit) != 0 {
+                *self.data.last_mut().unwrap() |= 1 << (self.position % 8);
+            }
+            self.position += 1;
+        }
+    }
+
+    fn signed(&mut self, value: i32, bits: u32) {
+        self.unsigned((value + (1 << (bits - 1))) as u32, bits);
+    }
+}
+
+fn encode_glyph(glyph: &Glyph, widths: BitWidths) -> Vec<u8> {
+    let bounding_box = &glyph.bounding_box;
+
+    let mut writer = BitWriter::default();
+    writer.unsigned(bounding_box.size.x as u32, widths.char_width);
+    writer.unsigned(bounding_box.size.y as u32, widths.char_height);
+    writer.signed(bounding_box.offset.x, widths.char_x);
+    writer.signed(bounding_box.offset.y, widths.char_y);
+    writer.signed(device_width(glyph), widths.delta_x);
+
+    if bounding_box.size.x > 0 && bounding_box.size.y > 0 {
+        let runs = run_lengths(glyph.pixels(), widths);
+
+        // Identical consecutive runs are stored once, followed by one set bit
+        // for each repetition.
+        let mut runs = runs.into_iter().peekable();
+        while let Some((zeros, ones)) = runs.next() {
+            writer.unsigned(zeros, widths.bits_per_0);
+            writer.unsigned(ones, widths.bits_per_1);
+            while runs.next_if_eq(&(zeros, ones)).is_some() {
+                writer.unsigned(1, 1);
+            }
+            writer.unsigned(0, 1);
+        }
+    }
+
+    writer.data
+}
+
+/// Splits the pixels into runs of zeros followed by ones.
+///
+/// The lengths of the runs are limited by the number of bits used to store
+/// them.
+fn run_lengths(pixels: impl Iterator<Item = bool>, widths: BitWidths) -> Vec<(u32, u32)> {
+    let max_zeros = (1 << widths.bits_per_0) - 1;
+    let max_ones = (1 << widths.bits_per_1) - 1;
+
+    let mut runs = Vec::new();
+    let mut pixels = pixels.peekable();
+
+    while pixels.peek().is_some() {
+        let mut zeros = 0;
+        while pixels.next_if_eq(&false).is_some() {
+            zeros += 1;
+        }
+        let mut ones = 0;
+        while pixels.next_if_eq(&true).is_some() {
+            ones += 1;
+        }
+
+        while zeros > max_zeros {
+            runs.push((max_zeros, 0));
+            zeros -= max_zeros;
+        }
+        while ones > max_ones {
+            runs.push((zeros, max_ones));
+            zeros = 0;
+            ones -= max_ones;
+        }
+        if zeros > 0 || ones > 0 {
+            runs.push((zeros, ones));
+        }
+    }
+
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use bdf_parser::Font;
+
+    use crate::FontConverter;
+
+    use super::*;
+
+    /// Checks that the decoded glyphs and metrics match the original font.
+    fn assert_decoded_font(original: &Font, decoded: &Font, glyphs: &str) {
+        assert_eq!(decoded.metrics, original.metrics);
+        assert_eq!(decoded.glyphs.len(), glyphs.chars().count());
+
+        for c in glyphs.chars() {
+            let original = original.glyphs.get(c).unwrap();
+            let decoded = decoded.glyphs.get(c).unwrap();
+
+            assert_eq!(decoded.encoding, original.encoding, "{c}");
+            assert_eq!(device_width(decoded), device_width(original), "{c}");
+            assert_eq!(decoded.bounding_box, original.bounding_box, "{c}");
+            assert!(decoded.pixels().eq(original.pixels()), "{c}");
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let original =
+            Font::parse(&fs::read_to_string("../eg-bdf-examples/examples/6x10.bdf").unwrap())
+                .unwrap();
+
+        let glyphs = (' '..='~').chain("äöüß€←".chars()).collect::<String>();
+        let output = FontConverter::with_font(&original, "u8g2_font_6x10")
+            .glyphs(glyphs.as_str())
+            .convert_u8g2()
+            .unwrap();
+
+        let decoded = Font::parse_u8g2(output.data()).unwrap();
+        assert_decoded_font(&original, &decoded, &glyphs);
+    }
+
+    #[test]
+    fn round_trip_c_source() {
+        let original =
+            Font::parse(&fs::read_to_string("../eg-bdf-examples/examples/6x10.bdf").unwrap())
+                .unwrap();
+
+        let output = FontConverter::with_font(&original, "u8g2_font_6x10")
+            .glyphs("\"?\\019AZaz")
+            .comment("Test")
+            .convert_u8g2()
+            .unwrap();
+
+        let source = output.source();
+        assert!(source.starts_with(&format!(
+            "// Test\n\nconst uint8_t u8g2_font_6x10[{}] U8G2_FONT_SECTION(\"u8g2_font_6x10\") = \n",
+            output.data().len() + 1
+        )));
+
+        let fonts = Font::parse_u8g2_source(&source).unwrap();
+        assert_eq!(fonts.len(), 1);
+        assert_eq!(fonts[0].0, "u8g2_font_6x10");
+        assert_decoded_font(&original, &fonts[0].1, "\"?\\019AZaz");
+    }
+
+    #[test]
+    fn header() {
+        let output = FontConverter::with_file("../eg-bdf-examples/examples/6x10.bdf", "font")
+            .glyphs("Aag")
+            .convert_u8g2()
+            .unwrap();
+
+        let header = &output.data()[..HEADER_SIZE];
+        assert_eq!(header[0], 3); // glyph count
+        assert_eq!(header[9..11], [6, 10]); // max char width and height
+        assert_eq!(header[11..13], [0, (-2i8) as u8]); // X and Y offset
+
+        // 'A' is the first glyph, 'a' is the second glyph.
+        assert_eq!(header[17..19], [0, 0]);
+        assert_eq!(
+            u16::from_be_bytes([header[19], header[20]]),
+            u16::from(output.data()[HEADER_SIZE + 1])
+        );
+    }
+
+    #[test]
+    fn long_runs() {
+        let widths = BitWidths {
+            bits_per_0: 2,
+            bits_per_1: 2,
+            char_width: 0,
+            char_height: 0,
+            char_x: 0,
+            char_y: 0,
+            delta_x: 0,
+        };
+
+        let pixels = [false; 7].into_iter().chain([true; 4]).chain([false; 2]);
+        assert_eq!(
+            run_lengths(pixels, widths),
+            [(3, 0), (3, 0), (1, 3), (0, 1), (2, 0)]
+        );
+    }
+
+    #[test]
+    fn bit_widths() {
+        assert_eq!(unsigned_bits([0, 0].into_iter()), Some(0));
+        assert_eq!(unsigned_bits([1, 5].into_iter()), Some(3));
+        assert_eq!(unsigned_bits([255].into_iter()), Some(8));
+        assert_eq!(unsigned_bits([256].into_iter()), None);
+        assert_eq!(unsigned_bits([-1].into_iter()), None);
+
+        assert_eq!(signed_bits([0].into_iter()), Some(1));
+        assert_eq!(signed_bits([-1].into_iter()), Some(1));
+        assert_eq!(signed_bits([1].into_iter()), Some(2));
+        assert_eq!(signed_bits([-128, 127].into_iter()), Some(8));
+        assert_eq!(signed_bits([128].into_iter()), None);
+    }
+}
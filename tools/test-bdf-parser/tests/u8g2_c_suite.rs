@@ -0,0 +1,70 @@
+use std::{collections::BTreeSet, path::Path};
+
+use bdf_parser::{Font, Glyph};
+
+fn u8g2_fonts() -> Vec<(String, Font)> {
+    let path = Path::new("../../target/fonts/u8g2/csrc/u8g2_fonts.c")
+        .canonicalize()
+        .unwrap();
+    let source = std::fs::read_to_string(path).unwrap();
+
+    Font::parse_u8g2_source(&source).expect("Could not decode fonts")
+}
+
+/// Returns the coordinates of all set pixels relative to the glyph origin.
+///
+/// u8g2 stores glyphs with a reduced bounding box, which is why the bitmaps
+/// can't be compared directly.
+fn set_pixels(glyph: &Glyph) -> BTreeSet<(i32, i32)> {
+    let offset = glyph.bounding_box.offset;
+    let size = glyph.bounding_box.size;
+
+    (0..size.y)
+        .flat_map(|y| (0..size.x).map(move |x| (x, y)))
+        .filter(|(x, y)| glyph.pixel(*x as usize, *y as usize).unwrap())
+        .map(|(x, y)| (offset.x + x, offset.y + size.y - 1 - y))
+        .collect()
+}
+
+#[test]
+fn it_decodes_all_u8g2_c_fonts() {
+    let fonts = u8g2_fonts();
+
+    assert!(!fonts.is_empty(), "No fonts found");
+    for (name, font) in &fonts {
+        assert!(!font.glyphs.is_empty(), "{} contains no glyphs", name);
+    }
+}
+
+#[test]
+fn decoded_glyphs_match_bdf_source() {
+    let fonts = u8g2_fonts();
+    let (_, decoded) = fonts
+        .iter()
+        .find(|(name, _)| name == "u8g2_font_6x10_tf")
+        .expect("u8g2_font_6x10_tf not found");
+
+    let path = Path::new("../../target/fonts/u8g2/tools/font/bdf/6x10.bdf")
+        .canonicalize()
+        .unwrap();
+    let bdf = std::fs::read_to_string(path).unwrap();
+    let source = Font::parse(&bdf).unwrap();
+
+    assert_eq!(decoded.metadata.bits_per_pixel, 1);
+
+    for c in ' '..='~' {
+        let decoded_glyph = decoded.glyphs.get(c).unwrap();
+        let source_glyph = source.glyphs.get(c).unwrap();
+
+        assert_eq!(
+            decoded_glyph.width_horizontal.map(|width| width.device),
+            source_glyph.width_horizontal.map(|width| width.device),
+            "device width of {c:?}"
+        );
+        assert_eq!(
+            set_pixels(decoded_glyph),
+            set_pixels(source_glyph),
+            "bitmap of {c:?}"
+        );
+    }
+}